@@ -0,0 +1,233 @@
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use xscape_common::{CrashReport, LogMessage, SystemEventType};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::{Duration, SystemTime};
+use tokio::process::Command;
+use tracing::{debug, error, info, warn};
+use uuid::Uuid;
+
+use crate::server::AppState;
+
+mod symbolicate;
+
+pub use symbolicate::{symbolicate, Symbolicated};
+
+/// How often a launched process is checked
+const POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+/// How long to wait for ReportCrash to write the .ips file after the process died
+const REPORT_TIMEOUT: Duration = Duration::from_secs(15);
+
+/// Number of crashing-thread frames included in the AppCrashed event
+const EVENT_FRAMES: usize = 8;
+
+/// Watch a launched app and report when it exits or crashes
+pub fn spawn_monitor(state: Arc<AppState>, session_id: Uuid) {
+    tokio::spawn(async move {
+        if let Err(e) = monitor(&state, session_id).await {
+            error!("Crash monitor for session {} failed: {}", session_id, e);
+        }
+    });
+}
+
+async fn monitor(state: &AppState, session_id: Uuid) -> Result<()> {
+    let session = match state.sessions.read().await.get(&session_id) {
+        Some(session) => session.clone(),
+        None => return Ok(()),
+    };
+    let Some(pid) = session.pid else {
+        return Ok(());
+    };
+
+    debug!("Monitoring {} (pid {}) for crashes", session.bundle_id, pid);
+    while is_running(pid).await {
+        tokio::time::sleep(POLL_INTERVAL).await;
+    }
+    if let Some(session) = state.sessions.write().await.get_mut(&session_id) {
+        session.ended_at = Some(Utc::now());
+    }
+
+    let report = wait_for_report(&session.device_udid, &session.bundle_id, pid, session.started_at).await;
+
+    let message = match &report {
+        Some((path, report)) => {
+            info!("{} (pid {}) crashed, report at {:?}", session.bundle_id, pid, path);
+            if let Some(session) = state.sessions.write().await.get_mut(&session_id) {
+                session.crash_report = Some(path.clone());
+            }
+
+            let symbolicated = match load_report(path).await {
+                Ok((text, _)) => symbolicate_with_build(state, &session.build_id, &text, None)
+                    .await
+                    .and_then(|s| CrashReport::parse(&s.report).ok()),
+                Err(_) => None,
+            };
+            LogMessage::session_event(
+                SystemEventType::AppCrashed,
+                session_id,
+                crash_message(&session.bundle_id, symbolicated.as_ref().unwrap_or(report)),
+            )
+        }
+        None => {
+            info!("{} (pid {}) exited", session.bundle_id, pid);
+            LogMessage::session_event(
+                SystemEventType::AppExited,
+                session_id,
+                format!("{} exited", session.bundle_id),
+            )
+        }
+    };
+
+    if let Some(sender) = state.get_log_sender(&session.build_id).await {
+        let _ = sender.send(serde_json::to_string(&message)?);
+    }
+
+    Ok(())
+}
+
+/// Check whether a process is still alive
+async fn is_running(pid: u32) -> bool {
+    Command::new("kill")
+        .args(["-0", &pid.to_string()])
+        .output()
+        .await
+        .map(|output| output.status.success())
+        .unwrap_or(false)
+}
+
+/// Poll the diagnostic report directories until a matching report shows up
+async fn wait_for_report(
+    udid: &str,
+    bundle_id: &str,
+    pid: u32,
+    started_at: DateTime<Utc>,
+) -> Option<(PathBuf, CrashReport)> {
+    let deadline = tokio::time::Instant::now() + REPORT_TIMEOUT;
+    loop {
+        match find_report(udid, bundle_id, pid, started_at.into()).await {
+            Ok(Some(found)) => return Some(found),
+            Ok(None) => {}
+            Err(e) => warn!("Failed to scan diagnostic reports: {}", e),
+        }
+        if tokio::time::Instant::now() >= deadline {
+            return None;
+        }
+        tokio::time::sleep(POLL_INTERVAL).await;
+    }
+}
+
+/// Directories where crash reports for simulator apps are written
+fn report_dirs(udid: &str) -> Vec<PathBuf> {
+    let Some(home) = std::env::var_os("HOME").map(PathBuf::from) else {
+        return Vec::new();
+    };
+    vec![
+        home.join("Library/Logs/DiagnosticReports"),
+        home.join("Library/Developer/CoreSimulator/Devices")
+            .join(udid)
+            .join("data/Library/Logs/DiagnosticReports"),
+    ]
+}
+
+/// Find the newest .ips report for the process written after `since`
+async fn find_report(
+    udid: &str,
+    bundle_id: &str,
+    pid: u32,
+    since: SystemTime,
+) -> Result<Option<(PathBuf, CrashReport)>> {
+    let mut best: Option<(SystemTime, PathBuf, CrashReport)> = None;
+
+    for dir in report_dirs(udid) {
+        let mut entries = match tokio::fs::read_dir(&dir).await {
+            Ok(entries) => entries,
+            Err(_) => continue,
+        };
+        while let Some(entry) = entries.next_entry().await? {
+            let path = entry.path();
+            if path.extension().is_none_or(|ext| ext != "ips") {
+                continue;
+            }
+            let modified = match entry.metadata().await.and_then(|m| m.modified()) {
+                Ok(modified) if modified >= since => modified,
+                _ => continue,
+            };
+            if best.as_ref().is_some_and(|(newest, _, _)| *newest >= modified) {
+                continue;
+            }
+
+            let Some(report) = read_report(&path).await else {
+                continue;
+            };
+            let pid_matches = report.body.pid == Some(pid);
+            let bundle_matches = report.header.bundle_id.as_deref() == Some(bundle_id);
+            if pid_matches || (report.body.pid.is_none() && bundle_matches) {
+                best = Some((modified, path, report));
+            }
+        }
+    }
+
+    Ok(best.map(|(_, path, report)| (path, report)))
+}
+
+async fn read_report(path: &Path) -> Option<CrashReport> {
+    let text = tokio::fs::read_to_string(path).await.ok()?;
+    CrashReport::parse(&text).ok()
+}
+
+/// Load and parse a crash report from disk
+pub async fn load_report(path: &Path) -> Result<(String, CrashReport)> {
+    let text = tokio::fs::read_to_string(path)
+        .await
+        .with_context(|| format!("Failed to read crash report {:?}", path))?;
+    let report = CrashReport::parse(&text)?;
+    Ok((text, report))
+}
+
+/// Symbolicate a report with the debug symbols of a build, if the build is known
+pub async fn symbolicate_with_build(
+    state: &AppState,
+    build_id: &Uuid,
+    text: &str,
+    source_root: Option<&str>,
+) -> Option<Symbolicated> {
+    let artifacts = state.get_artifacts(build_id).await?;
+
+    // Source paths in debug info point into the agent's copy of the project
+    let project_dir = match state.get_build(build_id).await {
+        Some(build) => state
+            .get_project(&build.project_id)
+            .await
+            .map(|p| PathBuf::from(p.path)),
+        None => None,
+    };
+
+    match symbolicate(text, &artifacts, project_dir.as_deref(), source_root).await {
+        Ok(symbolicated) => Some(symbolicated),
+        Err(e) => {
+            warn!("Failed to symbolicate crash report: {}", e);
+            None
+        }
+    }
+}
+
+/// Event message: exception and the top of the crashing thread
+fn crash_message(bundle_id: &str, report: &CrashReport) -> String {
+    let summary = report.summary();
+    let mut message = format!("{} crashed: {}", bundle_id, report.exception_description());
+    if let Some(reason) = &summary.termination_reason {
+        message.push_str(&format!(" - {}", reason));
+    }
+    if let Some(thread) = summary.crashed_thread {
+        match &summary.crashed_thread_name {
+            Some(name) => message.push_str(&format!("\nThread {} crashed ({}):", thread, name)),
+            None => message.push_str(&format!("\nThread {} crashed:", thread)),
+        }
+        for (i, frame) in summary.frames.iter().take(EVENT_FRAMES).enumerate() {
+            message.push_str(&format!("\n{:<3} {}", i, frame));
+        }
+    }
+    message
+}
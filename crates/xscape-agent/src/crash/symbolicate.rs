@@ -0,0 +1,182 @@
+use anyhow::{anyhow, Context, Result};
+use xscape_common::{symbolicate_report, CrashReport, IpsImage, ResolvedSymbol};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use tokio::process::Command;
+use tracing::{debug, warn};
+
+use crate::storage::BuildArtifacts;
+
+/// A report with frames rewritten from the build's debug symbols
+pub struct Symbolicated {
+    /// Symbolicated .ips text
+    pub report: String,
+    /// Names of the images that were symbolicated
+    pub images: Vec<String>,
+}
+
+/// Resolve the frames of images produced by a build with atos.
+///
+/// Source paths under `project_dir` are rewritten relative to `source_root`
+/// (the CLI's local checkout), or made relative when no root is given.
+pub async fn symbolicate(
+    text: &str,
+    artifacts: &BuildArtifacts,
+    project_dir: Option<&Path>,
+    source_root: Option<&str>,
+) -> Result<Symbolicated> {
+    let report = CrashReport::parse(text)?;
+    let mut symbols = HashMap::new();
+    let mut images = Vec::new();
+
+    for (image_index, offsets) in report.frame_offsets() {
+        let Some(image) = report.body.used_images.get(image_index) else {
+            continue;
+        };
+        let Some(binary) = find_binary(image, artifacts).await else {
+            continue;
+        };
+
+        debug!("Symbolicating {:?} with {:?}", image.name, binary);
+        let resolved = match atos(&binary, image, &offsets).await {
+            Ok(resolved) => resolved,
+            Err(e) => {
+                warn!("Failed to symbolicate {:?}: {}", image.name, e);
+                continue;
+            }
+        };
+
+        let mut any = false;
+        for (offset, symbol) in offsets.iter().zip(resolved) {
+            let Some(mut symbol) = symbol else {
+                continue;
+            };
+            symbol.file = symbol
+                .file
+                .map(|file| map_source_path(&file, project_dir, source_root));
+            symbols.insert((image_index, *offset), symbol);
+            any = true;
+        }
+        if any {
+            images.push(image.name.clone().unwrap_or_else(|| binary.display().to_string()));
+        }
+    }
+
+    let report = if symbols.is_empty() {
+        text.to_string()
+    } else {
+        symbolicate_report(text, &symbols)?
+    };
+    Ok(Symbolicated { report, images })
+}
+
+/// Find a binary with debug info for an image: the build's dSYMs first, then
+/// the binaries inside the built app (Debug builds keep DWARF in object files)
+async fn find_binary(image: &IpsImage, artifacts: &BuildArtifacts) -> Option<PathBuf> {
+    let name = image.name.as_deref()?;
+    let mut candidates: Vec<PathBuf> = artifacts
+        .dsym_paths
+        .iter()
+        .map(|dsym| dsym.join("Contents/Resources/DWARF").join(name))
+        .collect();
+    if let Some(ref app_path) = artifacts.app_path {
+        let app = Path::new(app_path);
+        candidates.push(app.join(name));
+        candidates.push(app.join("Frameworks").join(format!("{}.framework", name)).join(name));
+    }
+
+    for candidate in candidates.into_iter().filter(|c| c.is_file()) {
+        let Some(uuid) = image.uuid.as_deref() else {
+            return Some(candidate);
+        };
+        match binary_uuids(&candidate).await {
+            Ok(uuids) if uuids.iter().any(|u| same_uuid(u, uuid)) => return Some(candidate),
+            Ok(_) => debug!("{:?} does not match image UUID {}", candidate, uuid),
+            Err(e) => warn!("Failed to read UUID of {:?}: {}", candidate, e),
+        }
+    }
+    None
+}
+
+/// UUIDs of all architecture slices of a Mach-O file
+async fn binary_uuids(path: &Path) -> Result<Vec<String>> {
+    let output = Command::new("xcrun")
+        .args(["dwarfdump", "--uuid"])
+        .arg(path)
+        .output()
+        .await
+        .context("Failed to run dwarfdump")?;
+
+    if !output.status.success() {
+        return Err(anyhow!(
+            "dwarfdump failed: {}",
+            String::from_utf8_lossy(&output.stderr)
+        ));
+    }
+
+    // Format: "UUID: 1A2B3C4D-... (arm64) /path/to/binary"
+    Ok(String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .filter_map(|line| line.strip_prefix("UUID: "))
+        .filter_map(|rest| rest.split_whitespace().next())
+        .map(String::from)
+        .collect())
+}
+
+fn same_uuid(a: &str, b: &str) -> bool {
+    let normalize = |s: &str| s.replace('-', "").to_ascii_uppercase();
+    normalize(a) == normalize(b)
+}
+
+/// Resolve image offsets with atos, one result per offset
+async fn atos(binary: &Path, image: &IpsImage, offsets: &[u64]) -> Result<Vec<Option<ResolvedSymbol>>> {
+    let mut cmd = Command::new("xcrun");
+    cmd.arg("atos")
+        .arg("-o")
+        .arg(binary)
+        .args(["-l", &format!("0x{:x}", image.base)])
+        .arg("-fullPath");
+    if let Some(arch) = &image.arch {
+        cmd.args(["-arch", arch]);
+    }
+    for offset in offsets {
+        cmd.arg(format!("0x{:x}", image.base + offset));
+    }
+
+    let output = cmd.output().await.context("Failed to run atos")?;
+    if !output.status.success() {
+        return Err(anyhow!("atos failed: {}", String::from_utf8_lossy(&output.stderr)));
+    }
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let lines: Vec<&str> = stdout.lines().collect();
+    if lines.len() != offsets.len() {
+        return Err(anyhow!(
+            "atos returned {} lines for {} addresses",
+            lines.len(),
+            offsets.len()
+        ));
+    }
+
+    Ok(lines.into_iter().map(ResolvedSymbol::from_atos_line).collect())
+}
+
+/// Map a source path from the agent's project directory to the local checkout
+fn map_source_path(file: &str, project_dir: Option<&Path>, source_root: Option<&str>) -> String {
+    let Some(project_dir) = project_dir else {
+        return file.to_string();
+    };
+
+    // Paths in debug info may use the canonical form (/private/var/...)
+    let canonical = std::fs::canonicalize(project_dir).ok();
+    let relative = Path::new(file)
+        .strip_prefix(project_dir)
+        .ok()
+        .or_else(|| canonical.as_deref().and_then(|dir| Path::new(file).strip_prefix(dir).ok()));
+
+    match (relative, source_root) {
+        (Some(relative), Some(root)) => Path::new(root).join(relative).to_string_lossy().into_owned(),
+        (Some(relative), None) => relative.to_string_lossy().into_owned(),
+        (None, _) => file.to_string(),
+    }
+}
@@ -0,0 +1,112 @@
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    Json,
+};
+use xscape_common::{
+    ApiError, StatusBarOverride, StatusBarResponse, UiSettingsRequest, UiSettingsResponse,
+};
+use std::sync::Arc;
+use tracing::error;
+
+use crate::server::AppState;
+use crate::simctl;
+
+/// POST /simulator/{udid}/ui - Set appearance and/or Dynamic Type size
+pub async fn set_ui(
+    State(state): State<Arc<AppState>>,
+    Path(udid): Path<String>,
+    Json(request): Json<UiSettingsRequest>,
+) -> Result<Json<UiSettingsResponse>, (StatusCode, Json<ApiError>)> {
+    if request.appearance.is_none() && request.content_size.is_none() {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            Json(ApiError::bad_request(
+                "Nothing to change (set appearance and/or content_size)",
+            )),
+        ));
+    }
+
+    if let Some(appearance) = request.appearance {
+        simctl::set_appearance(&udid, appearance).await.map_err(|e| {
+            error!("Failed to set appearance: {}", e);
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ApiError::internal(format!("Failed to set appearance: {}", e))),
+            )
+        })?;
+    }
+
+    if let Some(size) = request.content_size {
+        simctl::set_content_size(&udid, size).await.map_err(|e| {
+            error!("Failed to set content size: {}", e);
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ApiError::internal(format!("Failed to set content size: {}", e))),
+            )
+        })?;
+    }
+    state.activity.touch(&udid);
+
+    Ok(Json(UiSettingsResponse {
+        device_udid: udid,
+        appearance: request.appearance,
+        content_size: request.content_size,
+    }))
+}
+
+/// POST /simulator/{udid}/status-bar - Override status bar values
+pub async fn override_status_bar(
+    State(state): State<Arc<AppState>>,
+    Path(udid): Path<String>,
+    Json(request): Json<StatusBarOverride>,
+) -> Result<Json<StatusBarResponse>, (StatusCode, Json<ApiError>)> {
+    if request.is_empty() {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            Json(ApiError::bad_request("No status bar values to override")),
+        ));
+    }
+    request.validate().map_err(|e| {
+        (
+            StatusCode::BAD_REQUEST,
+            Json(ApiError::bad_request(e)),
+        )
+    })?;
+
+    simctl::override_status_bar(&udid, &request)
+        .await
+        .map_err(|e| {
+            error!("Failed to override status bar: {}", e);
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ApiError::internal(format!("Failed to override status bar: {}", e))),
+            )
+        })?;
+    state.activity.touch(&udid);
+
+    Ok(Json(StatusBarResponse {
+        device_udid: udid,
+        status_bar: Some(request),
+    }))
+}
+
+/// DELETE /simulator/{udid}/status-bar - Remove status bar overrides
+pub async fn clear_status_bar(
+    State(state): State<Arc<AppState>>,
+    Path(udid): Path<String>,
+) -> Result<Json<StatusBarResponse>, (StatusCode, Json<ApiError>)> {
+    simctl::clear_status_bar(&udid).await.map_err(|e| {
+        error!("Failed to clear status bar: {}", e);
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ApiError::internal(format!("Failed to clear status bar: {}", e))),
+        )
+    })?;
+    state.activity.touch(&udid);
+
+    Ok(Json(StatusBarResponse {
+        device_udid: udid,
+        status_bar: None,
+    }))
+}
@@ -0,0 +1,302 @@
+use axum::{body::Body, extract::State, http::StatusCode, Json};
+use chrono::Utc;
+use futures_util::StreamExt;
+use xscape_common::{ApiError, BuildStatus, BuildStatusResponse, PlistValue, UploadAppResponse};
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use tokio::io::AsyncWriteExt;
+use tracing::{error, info};
+use uuid::Uuid;
+use walkdir::WalkDir;
+
+use crate::server::AppState;
+use crate::storage::BuildArtifacts;
+use crate::xcode;
+
+/// Maximum size of an uploaded app archive
+const MAX_APP_UPLOAD_BYTES: u64 = 2 * 1024 * 1024 * 1024;
+
+/// Maximum total size of the files an uploaded archive unpacks to
+const MAX_APP_EXTRACTED_BYTES: u64 = 4 * MAX_APP_UPLOAD_BYTES;
+
+type HandlerError = (StatusCode, Json<ApiError>);
+
+/// POST /apps/upload - Upload a zipped or tarred simulator .app and register it as a build
+pub async fn upload_app(
+    State(state): State<Arc<AppState>>,
+    body: Body,
+) -> Result<Json<UploadAppResponse>, HandlerError> {
+    let build_id = Uuid::new_v4();
+    let upload_dir = state.config.storage.logs_dir.join(build_id.to_string());
+    tokio::fs::create_dir_all(&upload_dir).await.map_err(internal)?;
+
+    match register_upload(&state, build_id, &upload_dir, body).await {
+        Ok(response) => Ok(Json(response)),
+        Err(e) => {
+            let _ = tokio::fs::remove_dir_all(&upload_dir).await;
+            Err(e)
+        }
+    }
+}
+
+async fn register_upload(
+    state: &AppState,
+    build_id: Uuid,
+    upload_dir: &Path,
+    body: Body,
+) -> Result<UploadAppResponse, HandlerError> {
+    let archive_path = upload_dir.join("upload");
+    let size = write_body(&archive_path, body).await?;
+    info!("Received app archive ({} bytes)", size);
+
+    let extract_dir = upload_dir.join("app");
+    let (archive, dir) = (archive_path.clone(), extract_dir.clone());
+    tokio::task::spawn_blocking(move || extract_archive(&archive, &dir))
+        .await
+        .map_err(internal)??;
+    let _ = tokio::fs::remove_file(&archive_path).await;
+
+    let app_path = find_app(&extract_dir)?;
+    let app_path_str = app_path.to_string_lossy().into_owned();
+    let app_name = app_path
+        .file_name()
+        .map(|n| n.to_string_lossy().into_owned())
+        .unwrap_or_default();
+
+    let info_plist = xcode::read_info_plist(&app_path_str)
+        .await
+        .map_err(|e| bad_request(format!("Invalid app bundle: {:#}", e)))?;
+    let platforms = ensure_simulator_build(&info_plist)?;
+    let app_info = xcode::read_app_info(&app_path_str)
+        .await
+        .map_err(|e| bad_request(format!("Invalid app bundle: {:#}", e)))?;
+    let bundle_id = app_info.bundle_id.clone();
+
+    // dSYMs shipped next to the app are kept for symbolication
+    let dsym_paths = WalkDir::new(&extract_dir)
+        .max_depth(2)
+        .into_iter()
+        .filter_map(|e| e.ok())
+        .map(|e| e.into_path())
+        .filter(|p| p.is_dir() && p.extension().is_some_and(|e| e == "dSYM"))
+        .collect();
+
+    let now = Utc::now();
+    state
+        .store_build(BuildStatusResponse {
+            build_id,
+            project_id: Uuid::nil(),
+            scheme: app_name.trim_end_matches(".app").to_string(),
+            status: BuildStatus::Succeeded,
+            started_at: now,
+            finished_at: Some(now),
+            app_path: Some(app_path_str.clone()),
+            bundle_id: Some(bundle_id.clone()),
+            error_message: None,
+            warnings: Vec::new(),
+            duration_secs: Some(0.0),
+            app_info: Some(app_info.clone()),
+        })
+        .await;
+    state
+        .store_artifacts(
+            build_id,
+            BuildArtifacts {
+                app_path: Some(app_path_str),
+                bundle_id: Some(bundle_id.clone()),
+                app_info: Some(app_info),
+                warnings: Vec::new(),
+                dsym_paths,
+                result_bundle_path: None,
+                log_path: None,
+                archive_path: None,
+                ipa_path: None,
+            },
+        )
+        .await;
+    // Lets clients stream app events (e.g. crashes) like for a regular build
+    state.create_log_channel(build_id).await;
+
+    info!("Registered uploaded app {} ({}) as build {}", app_name, bundle_id, build_id);
+
+    Ok(UploadAppResponse {
+        build_id,
+        bundle_id,
+        app_name,
+        platforms,
+    })
+}
+
+/// Stream the request body to `path`. `DefaultBodyLimit` doesn't apply to a
+/// raw `Body`, so the upload limit is enforced here.
+async fn write_body(path: &Path, body: Body) -> Result<u64, HandlerError> {
+    let mut file = tokio::fs::File::create(path).await.map_err(internal)?;
+    let mut stream = body.into_data_stream();
+    let mut size = 0u64;
+    while let Some(chunk) = stream.next().await {
+        let chunk = chunk.map_err(|e| {
+            error!("Failed to receive app upload: {}", e);
+            bad_request(format!("Failed to receive upload: {}", e))
+        })?;
+        size += chunk.len() as u64;
+        if size > MAX_APP_UPLOAD_BYTES {
+            return Err(too_large(format!(
+                "App archive is larger than {} bytes",
+                MAX_APP_UPLOAD_BYTES
+            )));
+        }
+        file.write_all(&chunk).await.map_err(internal)?;
+    }
+    file.flush().await.map_err(internal)?;
+    Ok(size)
+}
+
+/// Extract a zip, tar or tar.gz, told apart by their magic bytes
+fn extract_archive(path: &Path, dest: &Path) -> Result<(), HandlerError> {
+    let mut header = [0u8; 262];
+    let read = std::fs::File::open(path)
+        .and_then(|mut file| read_prefix(&mut file, &mut header))
+        .map_err(internal)?;
+    let header = &header[..read];
+
+    let (format, result) = if header.starts_with(b"PK") {
+        ("zip", extract_zip(path, dest))
+    } else if header.starts_with(&[0x1f, 0x8b]) {
+        ("tar.gz", extract_tar(path, dest, true))
+    } else if header.get(257..262) == Some(b"ustar") {
+        ("tar", extract_tar(path, dest, false))
+    } else {
+        return Err(bad_request(
+            "Unsupported archive format; upload the .app as a zip, tar or tar.gz",
+        ));
+    };
+    result.map_err(|e| bad_request(format!("Invalid {} archive: {:#}", format, e)))
+}
+
+/// Read up to `buf.len()` bytes, stopping early only at the end of the file
+fn read_prefix(file: &mut std::fs::File, buf: &mut [u8]) -> std::io::Result<usize> {
+    let mut read = 0;
+    while read < buf.len() {
+        match file.read(&mut buf[read..])? {
+            0 => break,
+            n => read += n,
+        }
+    }
+    Ok(read)
+}
+
+/// Extract a zip, rejecting entries that would land outside `dest` and
+/// archives whose files add up to more than `MAX_APP_EXTRACTED_BYTES`
+fn extract_zip(zip_path: &Path, dest: &Path) -> anyhow::Result<()> {
+    let file = std::fs::File::open(zip_path)?;
+    let mut archive = zip::ZipArchive::new(file)?;
+
+    let mut total = 0u64;
+    for i in 0..archive.len() {
+        total = total.saturating_add(archive.by_index_raw(i)?.size());
+    }
+    if total > MAX_APP_EXTRACTED_BYTES {
+        anyhow::bail!(
+            "archive unpacks to {} bytes, more than the {} allowed",
+            total,
+            MAX_APP_EXTRACTED_BYTES
+        );
+    }
+
+    archive.extract(dest)?;
+    Ok(())
+}
+
+/// Extract a tar (gzipped or not) with the same checks as `extract_zip`.
+/// Entry sizes come from the tar headers, which bound what is written.
+fn extract_tar(path: &Path, dest: &Path, gzipped: bool) -> anyhow::Result<()> {
+    let file = std::fs::File::open(path)?;
+    let reader: Box<dyn Read> = if gzipped {
+        Box::new(flate2::read::GzDecoder::new(file))
+    } else {
+        Box::new(file)
+    };
+    let mut archive = tar::Archive::new(reader);
+    std::fs::create_dir_all(dest)?;
+
+    let mut total = 0u64;
+    for entry in archive.entries()? {
+        let mut entry = entry?;
+        total = total.saturating_add(entry.size());
+        if total > MAX_APP_EXTRACTED_BYTES {
+            anyhow::bail!("archive unpacks to more than the {} bytes allowed", MAX_APP_EXTRACTED_BYTES);
+        }
+        if !entry.unpack_in(dest)? {
+            anyhow::bail!("entry {:?} would be extracted outside the archive", entry.path()?);
+        }
+    }
+    Ok(())
+}
+
+/// Find the single .app bundle at the top of the archive (or under Payload/)
+fn find_app(dir: &Path) -> Result<PathBuf, HandlerError> {
+    let apps: Vec<PathBuf> = WalkDir::new(dir)
+        .min_depth(1)
+        .max_depth(2)
+        .into_iter()
+        .filter_map(|e| e.ok())
+        .filter(|e| e.file_type().is_dir())
+        .map(|e| e.into_path())
+        .filter(|p| p.extension().is_some_and(|e| e == "app"))
+        .filter(|p| {
+            // Only top-level bundles, not apps nested in another bundle
+            p.parent()
+                .is_some_and(|parent| parent == dir || parent.file_name().is_some_and(|n| n == "Payload"))
+        })
+        .collect();
+
+    match apps.len() {
+        1 => Ok(apps.into_iter().next().unwrap()),
+        0 => Err(bad_request("Archive does not contain a .app bundle")),
+        n => Err(bad_request(format!("Archive contains {} .app bundles, expected one", n))),
+    }
+}
+
+/// Check that the app was built for a simulator SDK
+fn ensure_simulator_build(info_plist: &PlistValue) -> Result<Vec<String>, HandlerError> {
+    let platforms = info_plist
+        .get("CFBundleSupportedPlatforms")
+        .map(|p| p.string_array())
+        .unwrap_or_default();
+    let platform_name = info_plist.get("DTPlatformName").and_then(|p| p.as_str());
+
+    let is_simulator = platforms.iter().any(|p| p.ends_with("Simulator"))
+        || platform_name.is_some_and(|p| p.ends_with("simulator"));
+    if !is_simulator {
+        let found = if platforms.is_empty() {
+            platform_name.unwrap_or("unknown").to_string()
+        } else {
+            platforms.join(", ")
+        };
+        return Err(bad_request(format!(
+            "App is not a simulator build (platform: {}); build with a simulator SDK",
+            found
+        )));
+    }
+
+    Ok(platforms)
+}
+
+fn bad_request(message: impl Into<String>) -> HandlerError {
+    (StatusCode::BAD_REQUEST, Json(ApiError::bad_request(message)))
+}
+
+fn too_large(message: impl Into<String>) -> HandlerError {
+    (
+        StatusCode::PAYLOAD_TOO_LARGE,
+        Json(ApiError::new("PAYLOAD_TOO_LARGE", message)),
+    )
+}
+
+fn internal(e: impl std::fmt::Display) -> HandlerError {
+    (
+        StatusCode::INTERNAL_SERVER_ERROR,
+        Json(ApiError::internal(e.to_string())),
+    )
+}
@@ -0,0 +1,140 @@
+use axum::{
+    extract::{Multipart, State},
+    http::StatusCode,
+    Json,
+};
+use chrono::Utc;
+use xscape_common::{
+    validate_extra_args, ApiError, ArchiveRequest, BuildResponse, BuildStatus, BuildStatusResponse,
+};
+use std::sync::Arc;
+use tracing::{debug, error, info};
+use uuid::Uuid;
+
+use super::build::finish_build;
+use crate::server::AppState;
+use crate::xcode::{self, ProvisioningProfile, SigningAssets};
+
+type HandlerError = (StatusCode, Json<ApiError>);
+
+/// POST /archive - Archive a project for devices and export an .ipa
+///
+/// Multipart fields: `request` (ArchiveRequest JSON), and optionally
+/// `export_options` (plist), `certificate` (.p12), `certificate_password`
+/// and any number of `profile` (.mobileprovision). Signing assets are only
+/// held for the duration of the archive.
+pub async fn start_archive(
+    State(state): State<Arc<AppState>>,
+    mut multipart: Multipart,
+) -> Result<Json<BuildResponse>, HandlerError> {
+    let mut request: Option<ArchiveRequest> = None;
+    let mut export_options = None;
+    let mut signing = SigningAssets::default();
+
+    while let Some(field) = multipart.next_field().await.map_err(|e| {
+        error!("Failed to read multipart field: {}", e);
+        bad_request(format!("Invalid multipart data: {}", e))
+    })? {
+        let name = field.name().unwrap_or("").to_string();
+        let bytes = field
+            .bytes()
+            .await
+            .map_err(|e| bad_request(format!("Failed to read {}: {}", name, e)))?;
+
+        match name.as_str() {
+            "request" => {
+                request = Some(serde_json::from_slice(&bytes).map_err(|e| {
+                    bad_request(format!("Invalid archive request: {}", e))
+                })?);
+            }
+            "export_options" => export_options = Some(bytes.to_vec()),
+            "certificate" => signing.certificate = Some(bytes.to_vec()),
+            "certificate_password" => {
+                signing.certificate_password = Some(
+                    String::from_utf8(bytes.to_vec())
+                        .map_err(|_| bad_request("Certificate password is not valid UTF-8"))?,
+                );
+            }
+            "profile" => {
+                ProvisioningProfile::parse(&bytes)
+                    .map_err(|e| bad_request(format!("Invalid provisioning profile: {}", e)))?;
+                signing.profiles.push(bytes.to_vec());
+            }
+            _ => debug!("Ignoring unknown field: {}", name),
+        }
+    }
+
+    let request = request.ok_or_else(|| bad_request("Missing 'request' field"))?;
+    validate_extra_args(&request.extra_args).map_err(bad_request)?;
+    if request.export_method.is_none() && export_options.is_none() {
+        return Err(bad_request(
+            "An export method or an ExportOptions plist is required",
+        ));
+    }
+
+    let project = state.get_project(&request.project_id).await.ok_or_else(|| {
+        (
+            StatusCode::NOT_FOUND,
+            Json(ApiError::not_found("Project", &request.project_id.to_string())),
+        )
+    })?;
+
+    info!(
+        "Starting archive for project '{}' (scheme: {}, export method: {})",
+        project.project_name,
+        request.scheme,
+        request
+            .export_method
+            .map(|m| m.to_string())
+            .unwrap_or_else(|| "from ExportOptions".to_string())
+    );
+
+    let build_id = Uuid::new_v4();
+    let started_at = Utc::now();
+    state
+        .store_build(BuildStatusResponse {
+            build_id,
+            project_id: request.project_id,
+            scheme: request.scheme.clone(),
+            status: BuildStatus::Queued,
+            started_at,
+            finished_at: None,
+            app_path: None,
+            bundle_id: None,
+            error_message: None,
+            warnings: Vec::new(),
+            duration_secs: None,
+            app_info: None,
+        })
+        .await;
+
+    let log_sender = state.create_log_channel(build_id).await;
+
+    let state_clone = state.clone();
+    let output_dir = state.config.storage.logs_dir.join(build_id.to_string());
+    let cache_dir = state.config.storage.dependencies_dir.clone();
+    tokio::spawn(async move {
+        let result = xcode::run_archive(
+            &project.path,
+            &request,
+            export_options,
+            &signing,
+            &output_dir,
+            &cache_dir,
+            log_sender,
+        )
+        .await;
+
+        finish_build(&state_clone, build_id, result).await;
+    });
+
+    Ok(Json(BuildResponse {
+        build_id,
+        status: BuildStatus::Queued,
+        started_at,
+    }))
+}
+
+fn bad_request(message: impl Into<String>) -> HandlerError {
+    (StatusCode::BAD_REQUEST, Json(ApiError::bad_request(message)))
+}
@@ -0,0 +1,200 @@
+use axum::{
+    body::Body,
+    extract::{Path, Query, State},
+    http::{header, StatusCode},
+    response::{IntoResponse, Response},
+    Json,
+};
+use xscape_common::{
+    ApiError, ArchiveFormat, ArtifactQuery, BuildArtifactInfo, ListArtifactsResponse,
+};
+use std::fs::File;
+use std::path::{Path as FsPath, PathBuf};
+use std::sync::Arc;
+use tokio_util::io::ReaderStream;
+use tracing::{error, info};
+use uuid::Uuid;
+use walkdir::WalkDir;
+
+use crate::server::AppState;
+use crate::storage::BuildArtifacts;
+
+type HandlerError = (StatusCode, Json<ApiError>);
+
+/// GET /build/{build_id}/artifacts - List the products of a build
+pub async fn list_artifacts(
+    State(state): State<Arc<AppState>>,
+    Path(build_id): Path<Uuid>,
+) -> Result<Json<ListArtifactsResponse>, HandlerError> {
+    let artifacts = get_artifacts(&state, build_id).await?;
+
+    let products = artifacts.products();
+    let artifacts = tokio::task::spawn_blocking(move || {
+        products
+            .into_iter()
+            .map(|(kind, path)| BuildArtifactInfo {
+                name: artifact_name(&path),
+                kind,
+                is_dir: path.is_dir(),
+                size_bytes: total_size(&path),
+            })
+            .collect()
+    })
+    .await
+    .map_err(internal)?;
+
+    Ok(Json(ListArtifactsResponse {
+        build_id,
+        artifacts,
+    }))
+}
+
+/// GET /build/{build_id}/artifacts/{name} - Download a build product,
+/// directories as tar.gz or zip
+pub async fn download_artifact(
+    State(state): State<Arc<AppState>>,
+    Path((build_id, name)): Path<(Uuid, String)>,
+    Query(query): Query<ArtifactQuery>,
+) -> Result<Response, HandlerError> {
+    let artifacts = get_artifacts(&state, build_id).await?;
+    let path = artifacts
+        .products()
+        .into_iter()
+        .map(|(_, path)| path)
+        .find(|path| artifact_name(path) == name)
+        .ok_or_else(|| {
+            (
+                StatusCode::NOT_FOUND,
+                Json(ApiError::not_found("Artifact", &name)),
+            )
+        })?;
+
+    if !path.is_dir() {
+        let file = tokio::fs::File::open(&path).await.map_err(internal)?;
+        let size = file.metadata().await.map_err(internal)?.len();
+        return Ok(stream_file(
+            file,
+            size,
+            "application/octet-stream",
+            &name,
+        ));
+    }
+
+    info!("Archiving {} of build {} as {}", name, build_id, query.format);
+    let format = query.format;
+    let archive_path =
+        std::env::temp_dir().join(format!("xscape-artifact-{}.{}", Uuid::new_v4(), format));
+    let (source, target) = (path.clone(), archive_path.clone());
+    let result = tokio::task::spawn_blocking(move || write_archive(&source, &target, format))
+        .await
+        .map_err(internal)?;
+    if let Err(e) = result {
+        let _ = tokio::fs::remove_file(&archive_path).await;
+        error!("Failed to archive {}: {}", name, e);
+        return Err(internal(e));
+    }
+
+    // The open handle keeps the data readable after the file is unlinked
+    let file = tokio::fs::File::open(&archive_path).await.map_err(internal)?;
+    let _ = tokio::fs::remove_file(&archive_path).await;
+    let size = file.metadata().await.map_err(internal)?.len();
+
+    Ok(stream_file(
+        file,
+        size,
+        format.content_type(),
+        &format!("{}.{}", name, format),
+    ))
+}
+
+async fn get_artifacts(state: &AppState, build_id: Uuid) -> Result<BuildArtifacts, HandlerError> {
+    state.get_artifacts(&build_id).await.ok_or_else(|| {
+        (
+            StatusCode::NOT_FOUND,
+            Json(ApiError::not_found("Build", &build_id.to_string())),
+        )
+    })
+}
+
+pub(crate) fn stream_file(file: tokio::fs::File, size: u64, content_type: &str, file_name: &str) -> Response {
+    (
+        [
+            (header::CONTENT_TYPE, content_type.to_string()),
+            (header::CONTENT_LENGTH, size.to_string()),
+            (
+                header::CONTENT_DISPOSITION,
+                format!("attachment; filename=\"{}\"", file_name),
+            ),
+        ],
+        Body::from_stream(ReaderStream::new(file)),
+    )
+        .into_response()
+}
+
+fn artifact_name(path: &FsPath) -> String {
+    path.file_name()
+        .map(|n| n.to_string_lossy().into_owned())
+        .unwrap_or_default()
+}
+
+/// Size of a file, or of all files in a directory (symlinks not followed)
+fn total_size(path: &FsPath) -> u64 {
+    WalkDir::new(path)
+        .into_iter()
+        .filter_map(|e| e.ok())
+        .filter_map(|e| e.metadata().ok())
+        .filter(|m| m.is_file())
+        .map(|m| m.len())
+        .sum()
+}
+
+/// Archive a directory, keeping symlinks and file modes (bundles rely on both)
+fn write_archive(dir: &FsPath, target: &FsPath, format: ArchiveFormat) -> anyhow::Result<()> {
+    let file = File::create(target)?;
+    let base = PathBuf::from(artifact_name(dir));
+
+    match format {
+        ArchiveFormat::TarGz => {
+            let encoder = flate2::write::GzEncoder::new(file, flate2::Compression::default());
+            let mut builder = tar::Builder::new(encoder);
+            builder.follow_symlinks(false);
+            builder.append_dir_all(&base, dir)?;
+            builder.into_inner()?.finish()?;
+        }
+        ArchiveFormat::Zip => {
+            use std::os::unix::fs::PermissionsExt;
+            use zip::write::SimpleFileOptions;
+
+            let mut zip = zip::ZipWriter::new(file);
+            for entry in WalkDir::new(dir) {
+                let entry = entry?;
+                let name = base.join(entry.path().strip_prefix(dir)?);
+                let name = name.to_string_lossy();
+                let metadata = entry.path().symlink_metadata()?;
+                let options = SimpleFileOptions::default()
+                    .unix_permissions(metadata.permissions().mode())
+                    .large_file(metadata.len() >= u32::MAX as u64);
+
+                if metadata.file_type().is_symlink() {
+                    let link = std::fs::read_link(entry.path())?;
+                    zip.add_symlink(name, link.to_string_lossy(), options)?;
+                } else if metadata.is_dir() {
+                    zip.add_directory(name, options)?;
+                } else {
+                    zip.start_file(name, options)?;
+                    std::io::copy(&mut File::open(entry.path())?, &mut zip)?;
+                }
+            }
+            zip.finish()?;
+        }
+    }
+
+    Ok(())
+}
+
+fn internal(e: impl std::fmt::Display) -> HandlerError {
+    (
+        StatusCode::INTERNAL_SERVER_ERROR,
+        Json(ApiError::internal(e.to_string())),
+    )
+}
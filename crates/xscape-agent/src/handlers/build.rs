@@ -1,15 +1,20 @@
 use axum::{
-    extract::{Path, State},
+    extract::{Path, Query, State},
     http::StatusCode,
     Json,
 };
 use chrono::Utc;
-use xscape_common::{ApiError, BuildRequest, BuildResponse, BuildStatus, BuildStatusResponse};
+use xscape_common::{
+    url_types, ApiError, BuildRequest, BuildResponse, BuildStatus, BuildStatusResponse,
+    CrashReport, SymbolicateQuery, SymbolicateResponse, UrlSchemesResponse,
+};
 use std::sync::Arc;
 use tracing::{error, info};
 use uuid::Uuid;
 
+use crate::crash;
 use crate::server::AppState;
+use crate::storage::BuildArtifacts;
 use crate::xcode;
 
 /// POST /build - Start a new build
@@ -25,8 +30,20 @@ pub async fn start_build(
         )
     })?;
 
+    request
+        .validate_build_settings()
+        .map_err(|e| (StatusCode::BAD_REQUEST, Json(ApiError::bad_request(e))))?;
+    if let Some(ref xcconfig) = request.xcconfig {
+        xcode::check_xcconfig(std::path::Path::new(&project.path), xcconfig).map_err(|e| {
+            (
+                StatusCode::BAD_REQUEST,
+                Json(ApiError::bad_request(format!("Invalid xcconfig: {:#}", e))),
+            )
+        })?;
+    }
+
     info!(
-        "Starting build for project '{}' (scheme: {}, config: {:?})",
+        "Starting build for project '{}' (scheme: {}, config: {})",
         project.project_name, request.scheme, request.configuration
     );
 
@@ -46,6 +63,7 @@ pub async fn start_build(
         error_message: None,
         warnings: Vec::new(),
         duration_secs: None,
+        app_info: None,
     };
     state.store_build(build_status).await;
 
@@ -55,39 +73,19 @@ pub async fn start_build(
     // Spawn build task
     let state_clone = state.clone();
     let project_path = project.path.clone();
+    let output_dir = state.config.storage.logs_dir.join(build_id.to_string());
+    let cache_dir = state.config.storage.dependencies_dir.clone();
     tokio::spawn(async move {
         let result = xcode::run_build(
             &project_path,
             &request,
+            &output_dir,
+            &cache_dir,
             log_sender,
         )
         .await;
 
-        // Update build status based on result
-        let mut build_status = state_clone.get_build(&build_id).await.unwrap();
-        let finished_at = Utc::now();
-        build_status.finished_at = Some(finished_at);
-        build_status.duration_secs = Some(
-            (finished_at - build_status.started_at).num_milliseconds() as f64 / 1000.0,
-        );
-
-        match result {
-            Ok(artifacts) => {
-                info!("Build {} succeeded: {:?}", build_id, artifacts.app_path);
-                build_status.status = BuildStatus::Succeeded;
-                build_status.app_path = Some(artifacts.app_path.clone());
-                build_status.bundle_id = artifacts.bundle_id.clone();
-                build_status.warnings = artifacts.warnings.clone();
-                state_clone.store_artifacts(build_id, artifacts).await;
-            }
-            Err(e) => {
-                error!("Build {} failed: {}", build_id, e);
-                build_status.status = BuildStatus::Failed;
-                build_status.error_message = Some(e.to_string());
-            }
-        }
-
-        state_clone.store_build(build_status).await;
+        finish_build(&state_clone, build_id, result).await;
     });
 
     Ok(Json(BuildResponse {
@@ -97,6 +95,41 @@ pub async fn start_build(
     }))
 }
 
+/// Record the outcome of a build or archive task
+pub(crate) async fn finish_build(
+    state: &AppState,
+    build_id: Uuid,
+    result: anyhow::Result<BuildArtifacts>,
+) {
+    let Some(mut build_status) = state.get_build(&build_id).await else {
+        return;
+    };
+    let finished_at = Utc::now();
+    build_status.finished_at = Some(finished_at);
+    build_status.duration_secs = Some(
+        (finished_at - build_status.started_at).num_milliseconds() as f64 / 1000.0,
+    );
+
+    match result {
+        Ok(artifacts) => {
+            info!("Build {} succeeded: {:?}", build_id, artifacts.app_path);
+            build_status.status = BuildStatus::Succeeded;
+            build_status.app_path = artifacts.app_path.clone();
+            build_status.bundle_id = artifacts.bundle_id.clone();
+            build_status.app_info = artifacts.app_info.clone();
+            build_status.warnings = artifacts.warnings.clone();
+            state.store_artifacts(build_id, artifacts).await;
+        }
+        Err(e) => {
+            error!("Build {} failed: {:#}", build_id, e);
+            build_status.status = BuildStatus::Failed;
+            build_status.error_message = Some(format!("{:#}", e));
+        }
+    }
+
+    state.store_build(build_status).await;
+}
+
 /// GET /build/{build_id} - Get build status
 pub async fn get_build_status(
     State(state): State<Arc<AppState>>,
@@ -111,3 +144,104 @@ pub async fn get_build_status(
 
     Ok(Json(build))
 }
+
+/// GET /build/latest - Get the most recent successful build
+pub async fn get_latest_build(
+    State(state): State<Arc<AppState>>,
+) -> Result<Json<BuildStatusResponse>, (StatusCode, Json<ApiError>)> {
+    let build = state.latest_build().await.ok_or_else(|| {
+        (
+            StatusCode::NOT_FOUND,
+            Json(ApiError::new("NOT_FOUND", "No successful builds yet")),
+        )
+    })?;
+
+    Ok(Json(build))
+}
+
+/// GET /build/{build_id}/url-schemes - URL schemes registered by the built app
+pub async fn get_url_schemes(
+    State(state): State<Arc<AppState>>,
+    Path(build_id): Path<Uuid>,
+) -> Result<Json<UrlSchemesResponse>, (StatusCode, Json<ApiError>)> {
+    let artifacts = state.get_artifacts(&build_id).await.ok_or_else(|| {
+        (
+            StatusCode::NOT_FOUND,
+            Json(ApiError::not_found("Build", &build_id.to_string())),
+        )
+    })?;
+
+    let app_path = artifacts.app_path.as_deref().ok_or_else(|| {
+        (
+            StatusCode::BAD_REQUEST,
+            Json(ApiError::bad_request("Build produced no app")),
+        )
+    })?;
+    let info_plist = xcode::read_info_plist(app_path)
+        .await
+        .map_err(|e| {
+            error!("Failed to read Info.plist: {}", e);
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ApiError::internal(format!("Failed to read Info.plist: {}", e))),
+            )
+        })?;
+
+    Ok(Json(UrlSchemesResponse {
+        build_id,
+        bundle_id: artifacts.bundle_id,
+        url_types: url_types(&info_plist),
+    }))
+}
+
+/// POST /build/{build_id}/symbolicate - Symbolicate an uploaded .ips report
+/// with the build's debug symbols
+pub async fn symbolicate_report(
+    State(state): State<Arc<AppState>>,
+    Path(build_id): Path<Uuid>,
+    Query(query): Query<SymbolicateQuery>,
+    body: String,
+) -> Result<Json<SymbolicateResponse>, (StatusCode, Json<ApiError>)> {
+    if state.get_artifacts(&build_id).await.is_none() {
+        return Err((
+            StatusCode::NOT_FOUND,
+            Json(ApiError::not_found("Build", &build_id.to_string())),
+        ));
+    }
+
+    let original = CrashReport::parse(&body).map_err(|e| {
+        (
+            StatusCode::BAD_REQUEST,
+            Json(ApiError::new("INVALID_CRASH_REPORT", e.to_string())),
+        )
+    })?;
+
+    let symbolicated = crash::symbolicate_with_build(
+        &state,
+        &build_id,
+        &body,
+        query.source_root.as_deref(),
+    )
+    .await
+    .ok_or_else(|| {
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ApiError::internal("Failed to symbolicate crash report")),
+        )
+    })?;
+
+    let parsed = CrashReport::parse(&symbolicated.report).unwrap_or(original);
+    info!(
+        "Symbolicated crash report with build {} ({} images)",
+        build_id,
+        symbolicated.images.len()
+    );
+
+    Ok(Json(SymbolicateResponse {
+        build_id,
+        bundle_id: parsed.header.bundle_id.clone(),
+        summary: parsed.summary(),
+        report: symbolicated.report,
+        symbolicated_images: symbolicated.images,
+    }))
+}
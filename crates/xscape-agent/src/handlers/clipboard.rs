@@ -0,0 +1,47 @@
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    Json,
+};
+use xscape_common::{ApiError, PasteboardContent};
+use std::sync::Arc;
+use tracing::error;
+
+use crate::server::AppState;
+use crate::simctl;
+
+/// GET /simulator/{udid}/pasteboard - Read the simulator pasteboard
+///
+/// Does not count as device activity, so a clipboard watcher does not keep
+/// an otherwise idle simulator booted.
+pub async fn get_pasteboard(
+    Path(udid): Path<String>,
+) -> Result<Json<PasteboardContent>, (StatusCode, Json<ApiError>)> {
+    let text = simctl::pbpaste(&udid).await.map_err(|e| {
+        error!("Failed to read pasteboard: {}", e);
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ApiError::internal(format!("Failed to read pasteboard: {}", e))),
+        )
+    })?;
+
+    Ok(Json(PasteboardContent { text }))
+}
+
+/// POST /simulator/{udid}/pasteboard - Replace the simulator pasteboard
+pub async fn set_pasteboard(
+    State(state): State<Arc<AppState>>,
+    Path(udid): Path<String>,
+    Json(request): Json<PasteboardContent>,
+) -> Result<Json<PasteboardContent>, (StatusCode, Json<ApiError>)> {
+    simctl::pbcopy(&udid, &request.text).await.map_err(|e| {
+        error!("Failed to write pasteboard: {}", e);
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ApiError::internal(format!("Failed to write pasteboard: {}", e))),
+        )
+    })?;
+    state.activity.touch(&udid);
+
+    Ok(Json(request))
+}
@@ -0,0 +1,312 @@
+use axum::{
+    body::Body,
+    extract::{Path, Query, State},
+    http::StatusCode,
+    response::Response,
+    Json,
+};
+use chrono::{DateTime, Utc};
+use futures_util::StreamExt;
+use xscape_common::{
+    sanitize_container_path, ApiError, ContainerEntry, ContainerQuery, ListContainerResponse,
+    UploadContainerResponse,
+};
+use std::path::{Path as FsPath, PathBuf};
+use std::sync::Arc;
+use tokio::io::AsyncWriteExt;
+use tracing::{error, info};
+use uuid::Uuid;
+
+use super::artifacts::stream_file;
+use crate::server::AppState;
+use crate::simctl;
+
+/// Maximum size of a container upload
+const MAX_CONTAINER_UPLOAD_BYTES: u64 = 2 * 1024 * 1024 * 1024;
+
+type HandlerError = (StatusCode, Json<ApiError>);
+
+/// GET /simulator/{udid}/apps/{bundle_id}/container - List a container directory
+pub async fn list_container(
+    State(state): State<Arc<AppState>>,
+    Path((udid, bundle_id)): Path<(String, String)>,
+    Query(query): Query<ContainerQuery>,
+) -> Result<Json<ListContainerResponse>, HandlerError> {
+    let (_, target) = resolve_existing(&udid, &bundle_id, &query).await?;
+    state.activity.touch(&udid);
+
+    if !target.is_dir() {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            Json(ApiError::bad_request(format!("'{}' is not a directory", query.path))),
+        ));
+    }
+
+    let mut entries = Vec::new();
+    let mut dir = tokio::fs::read_dir(&target).await.map_err(internal)?;
+    while let Some(entry) = dir.next_entry().await.map_err(internal)? {
+        let metadata = match entry.metadata().await {
+            Ok(metadata) => metadata,
+            Err(_) => continue,
+        };
+        entries.push(ContainerEntry {
+            name: entry.file_name().to_string_lossy().into_owned(),
+            is_dir: metadata.is_dir(),
+            size_bytes: if metadata.is_dir() { 0 } else { metadata.len() },
+            modified: metadata.modified().ok().map(DateTime::<Utc>::from),
+        });
+    }
+    entries.sort_by(|a, b| b.is_dir.cmp(&a.is_dir).then_with(|| a.name.cmp(&b.name)));
+
+    Ok(Json(ListContainerResponse {
+        bundle_id,
+        container: query.container,
+        path: query.path,
+        entries,
+    }))
+}
+
+/// GET /simulator/{udid}/apps/{bundle_id}/container/download - Download a file,
+/// or a directory as tar.gz
+pub async fn download_container(
+    State(state): State<Arc<AppState>>,
+    Path((udid, bundle_id)): Path<(String, String)>,
+    Query(query): Query<ContainerQuery>,
+) -> Result<Response, HandlerError> {
+    let (_, target) = resolve_existing(&udid, &bundle_id, &query).await?;
+    state.activity.touch(&udid);
+
+    let name = target
+        .file_name()
+        .map(|n| n.to_string_lossy().into_owned())
+        .unwrap_or_else(|| bundle_id.clone());
+
+    if !target.is_dir() {
+        let file = tokio::fs::File::open(&target).await.map_err(internal)?;
+        let size = file.metadata().await.map_err(internal)?.len();
+        return Ok(stream_file(file, size, "application/octet-stream", &name));
+    }
+
+    // Containers can be large, so the archive goes through a temporary file
+    info!("Archiving {:?} from {} container", target, bundle_id);
+    let archive_path = std::env::temp_dir().join(format!("xscape-container-{}.tar.gz", Uuid::new_v4()));
+    let destination = archive_path.clone();
+    let result = tokio::task::spawn_blocking(move || archive_directory(&target, &destination))
+        .await
+        .map_err(internal)?;
+    if let Err(e) = result {
+        let _ = tokio::fs::remove_file(&archive_path).await;
+        error!("Failed to archive directory: {}", e);
+        return Err(internal(e));
+    }
+
+    // The open handle keeps the data readable after the file is unlinked
+    let file = tokio::fs::File::open(&archive_path).await.map_err(internal)?;
+    let _ = tokio::fs::remove_file(&archive_path).await;
+    let size = file.metadata().await.map_err(internal)?.len();
+    Ok(stream_file(file, size, "application/gzip", &format!("{}.tar.gz", name)))
+}
+
+/// PUT /simulator/{udid}/apps/{bundle_id}/container/upload - Create or replace a file
+pub async fn upload_container(
+    State(state): State<Arc<AppState>>,
+    Path((udid, bundle_id)): Path<(String, String)>,
+    Query(query): Query<ContainerQuery>,
+    body: Body,
+) -> Result<Json<UploadContainerResponse>, HandlerError> {
+    let (root, relative) = resolve(&udid, &bundle_id, &query).await?;
+    if relative.as_os_str().is_empty() {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            Json(ApiError::bad_request("Upload path must name a file")),
+        ));
+    }
+
+    let target = root.join(&relative);
+    let parent = target.parent().unwrap_or(&root).to_path_buf();
+    ensure_within(&root, &parent, &query.path).await?;
+    tokio::fs::create_dir_all(&parent).await.map_err(internal)?;
+
+    let replaced = match tokio::fs::symlink_metadata(&target).await {
+        Ok(metadata) if metadata.is_dir() => {
+            return Err((
+                StatusCode::BAD_REQUEST,
+                Json(ApiError::bad_request(format!("'{}' is a directory", query.path))),
+            ));
+        }
+        Ok(metadata) if metadata.file_type().is_symlink() => {
+            return Err((
+                StatusCode::BAD_REQUEST,
+                Json(ApiError::bad_request(format!(
+                    "'{}' is a symlink and cannot be replaced",
+                    query.path
+                ))),
+            ));
+        }
+        Ok(_) => true,
+        Err(_) => false,
+    };
+
+    // Write next to the target, then rename so readers never see a partial file
+    let temp_path = parent.join(format!(".xscape-upload-{}", Uuid::new_v4()));
+    let size_bytes = match write_body(&temp_path, body).await {
+        Ok(size) => size,
+        Err(e) => {
+            let _ = tokio::fs::remove_file(&temp_path).await;
+            return Err(e);
+        }
+    };
+    if let Err(e) = tokio::fs::rename(&temp_path, &target).await {
+        let _ = tokio::fs::remove_file(&temp_path).await;
+        return Err(internal(e));
+    }
+    state.activity.touch(&udid);
+
+    info!(
+        "Uploaded {} ({} bytes) to {} container of {}",
+        query.path, size_bytes, query.container, bundle_id
+    );
+
+    Ok(Json(UploadContainerResponse {
+        path: relative.to_string_lossy().into_owned(),
+        size_bytes,
+        replaced,
+    }))
+}
+
+/// Resolve the container root and the sanitized relative path
+async fn resolve(
+    udid: &str,
+    bundle_id: &str,
+    query: &ContainerQuery,
+) -> Result<(PathBuf, PathBuf), HandlerError> {
+    let relative = sanitize_container_path(&query.path).map_err(|e| {
+        (
+            StatusCode::BAD_REQUEST,
+            Json(ApiError::new("INVALID_PATH", e)),
+        )
+    })?;
+
+    let root = simctl::get_app_container(udid, bundle_id, &query.container)
+        .await
+        .map_err(|e| {
+            error!("Failed to resolve app container: {}", e);
+            (
+                StatusCode::NOT_FOUND,
+                Json(ApiError::not_found(
+                    "App container",
+                    &format!("{} ({})", bundle_id, query.container),
+                )),
+            )
+        })?;
+    let root = tokio::fs::canonicalize(&root).await.map_err(internal)?;
+
+    Ok((root, relative))
+}
+
+/// Resolve an existing path, following symlinks only within the container
+async fn resolve_existing(
+    udid: &str,
+    bundle_id: &str,
+    query: &ContainerQuery,
+) -> Result<(PathBuf, PathBuf), HandlerError> {
+    let (root, relative) = resolve(udid, bundle_id, query).await?;
+
+    let target = tokio::fs::canonicalize(root.join(&relative))
+        .await
+        .map_err(|_| {
+            (
+                StatusCode::NOT_FOUND,
+                Json(ApiError::not_found("Path", &query.path)),
+            )
+        })?;
+    if !target.starts_with(&root) {
+        return Err(escapes_container(&query.path));
+    }
+
+    Ok((root, target))
+}
+
+/// Check that the nearest existing ancestor of `path` lies inside `root`
+async fn ensure_within(
+    root: &FsPath,
+    path: &FsPath,
+    requested: &str,
+) -> Result<(), HandlerError> {
+    let mut existing = path;
+    while tokio::fs::metadata(existing).await.is_err() {
+        existing = existing
+            .parent()
+            .ok_or_else(|| escapes_container(requested))?;
+    }
+
+    let canonical = tokio::fs::canonicalize(existing).await.map_err(internal)?;
+    if !canonical.starts_with(root) {
+        return Err(escapes_container(requested));
+    }
+    Ok(())
+}
+
+/// Stream the request body to `path`. `DefaultBodyLimit` doesn't apply to a
+/// raw `Body`, so the upload limit is enforced here.
+async fn write_body(path: &FsPath, body: Body) -> Result<u64, HandlerError> {
+    let mut file = tokio::fs::File::create(path).await.map_err(internal)?;
+    let mut stream = body.into_data_stream();
+    let mut size = 0u64;
+    while let Some(chunk) = stream.next().await {
+        let chunk = chunk.map_err(|e| {
+            error!("Failed to receive upload: {}", e);
+            (
+                StatusCode::BAD_REQUEST,
+                Json(ApiError::bad_request(format!("Failed to receive upload: {}", e))),
+            )
+        })?;
+        size += chunk.len() as u64;
+        if size > MAX_CONTAINER_UPLOAD_BYTES {
+            return Err((
+                StatusCode::PAYLOAD_TOO_LARGE,
+                Json(ApiError::new(
+                    "PAYLOAD_TOO_LARGE",
+                    format!("Upload is larger than {} bytes", MAX_CONTAINER_UPLOAD_BYTES),
+                )),
+            ));
+        }
+        file.write_all(&chunk).await.map_err(internal)?;
+    }
+    file.flush().await.map_err(internal)?;
+    Ok(size)
+}
+
+/// Write a tar.gz of a directory to `target`, without following symlinks
+fn archive_directory(dir: &FsPath, target: &FsPath) -> anyhow::Result<()> {
+    let file = std::fs::File::create(target)?;
+    let encoder = flate2::write::GzEncoder::new(file, flate2::Compression::default());
+    let mut builder = tar::Builder::new(encoder);
+    builder.follow_symlinks(false);
+
+    let base = dir
+        .file_name()
+        .map(PathBuf::from)
+        .unwrap_or_else(|| PathBuf::from("container"));
+    builder.append_dir_all(&base, dir)?;
+
+    builder.into_inner()?.finish()?.sync_all()?;
+    Ok(())
+}
+
+fn escapes_container(path: &str) -> HandlerError {
+    (
+        StatusCode::BAD_REQUEST,
+        Json(ApiError::new(
+            "INVALID_PATH",
+            format!("path '{}' resolves outside the container", path),
+        )),
+    )
+}
+
+fn internal(e: impl std::fmt::Display) -> HandlerError {
+    (
+        StatusCode::INTERNAL_SERVER_ERROR,
+        Json(ApiError::internal(e.to_string())),
+    )
+}
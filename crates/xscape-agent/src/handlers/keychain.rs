@@ -0,0 +1,126 @@
+use anyhow::Context;
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    Json,
+};
+use xscape_common::{
+    certificate_to_pem, parse_certificates, ApiError, KeychainAction, KeychainRequest,
+    KeychainResponse,
+};
+use std::sync::Arc;
+use tracing::{error, info};
+use uuid::Uuid;
+
+use crate::server::AppState;
+use crate::simctl;
+
+/// POST /simulator/{udid}/keychain - Add a certificate or reset the keychain
+pub async fn update_keychain(
+    State(state): State<Arc<AppState>>,
+    Path(udid): Path<String>,
+    Json(request): Json<KeychainRequest>,
+) -> Result<Json<KeychainResponse>, (StatusCode, Json<ApiError>)> {
+    match request.action {
+        KeychainAction::Reset => {
+            simctl::keychain(&udid, KeychainAction::Reset, None)
+                .await
+                .map_err(|e| {
+                    error!("Failed to reset keychain: {}", e);
+                    (
+                        StatusCode::INTERNAL_SERVER_ERROR,
+                        Json(ApiError::internal(format!("Failed to reset keychain: {}", e))),
+                    )
+                })?;
+        }
+        action => {
+            let pem = request.certificate.as_deref().ok_or_else(|| {
+                (
+                    StatusCode::BAD_REQUEST,
+                    Json(ApiError::bad_request(format!(
+                        "certificate is required for {}",
+                        action
+                    ))),
+                )
+            })?;
+            let certificates = parse_pem_certificates(&[pem.to_string()])?;
+
+            for der in &certificates {
+                add_certificate(&udid, action, der).await.map_err(|e| {
+                    error!("Failed to add certificate: {}", e);
+                    (
+                        StatusCode::INTERNAL_SERVER_ERROR,
+                        Json(ApiError::internal(format!("Failed to add certificate: {}", e))),
+                    )
+                })?;
+            }
+        }
+    }
+    state.activity.touch(&udid);
+
+    Ok(Json(KeychainResponse {
+        device_udid: udid,
+        action: request.action,
+    }))
+}
+
+/// Validate PEM certificates from a request, returning their DER encodings
+pub fn parse_pem_certificates(
+    pems: &[String],
+) -> Result<Vec<Vec<u8>>, (StatusCode, Json<ApiError>)> {
+    let mut certificates = Vec::new();
+    for pem in pems {
+        let parsed = parse_certificates(pem.as_bytes()).map_err(|e| {
+            (
+                StatusCode::BAD_REQUEST,
+                Json(ApiError::new("INVALID_CERTIFICATE", e.to_string())),
+            )
+        })?;
+        certificates.extend(parsed);
+    }
+    Ok(certificates)
+}
+
+/// Trust root certificates on a freshly booted device
+pub async fn install_root_certificates(
+    udid: &str,
+    certificates: &[Vec<u8>],
+) -> Result<(), (StatusCode, Json<ApiError>)> {
+    if certificates.is_empty() {
+        return Ok(());
+    }
+
+    info!(
+        "Installing {} root certificates on simulator {}",
+        certificates.len(),
+        udid
+    );
+    for der in certificates {
+        add_certificate(udid, KeychainAction::AddRootCert, der)
+            .await
+            .map_err(|e| {
+                error!("Failed to install root certificate: {}", e);
+                (
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    Json(ApiError::internal(format!(
+                        "Simulator booted but installing a root certificate failed: {}",
+                        e
+                    ))),
+                )
+            })?;
+    }
+
+    Ok(())
+}
+
+/// simctl reads certificates from a file, so stage each one in a temp file
+async fn add_certificate(udid: &str, action: KeychainAction, der: &[u8]) -> anyhow::Result<()> {
+    let path = std::env::temp_dir().join(format!("xscape-cert-{}.pem", Uuid::new_v4()));
+    tokio::fs::write(&path, certificate_to_pem(der))
+        .await
+        .context("Failed to write certificate file")?;
+
+    let result = simctl::keychain(udid, action, Some(&path)).await;
+    let _ = tokio::fs::remove_file(&path).await;
+    result
+}
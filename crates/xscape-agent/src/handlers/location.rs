@@ -0,0 +1,102 @@
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    Json,
+};
+use xscape_common::{
+    validate_route, ApiError, Coordinate, LocationResponse, PlayRouteRequest, SetLocationRequest,
+};
+use std::sync::Arc;
+use tracing::error;
+
+use crate::server::AppState;
+use crate::simctl;
+
+/// POST /simulator/{udid}/location - Set a fixed simulated location
+pub async fn set_location(
+    State(state): State<Arc<AppState>>,
+    Path(udid): Path<String>,
+    Json(request): Json<SetLocationRequest>,
+) -> Result<Json<LocationResponse>, (StatusCode, Json<ApiError>)> {
+    let location = Coordinate::new(request.latitude, request.longitude);
+    location.validate().map_err(|e| {
+        (
+            StatusCode::BAD_REQUEST,
+            Json(ApiError::bad_request(e.to_string())),
+        )
+    })?;
+
+    simctl::set_location(&udid, &location).await.map_err(|e| {
+        error!("Failed to set location: {}", e);
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ApiError::internal(format!("Failed to set location: {}", e))),
+        )
+    })?;
+    state.activity.touch(&udid);
+
+    Ok(Json(LocationResponse {
+        device_udid: udid,
+        location: Some(location),
+        route_waypoints: 0,
+    }))
+}
+
+/// DELETE /simulator/{udid}/location - Clear the simulated location
+pub async fn clear_location(
+    State(state): State<Arc<AppState>>,
+    Path(udid): Path<String>,
+) -> Result<Json<LocationResponse>, (StatusCode, Json<ApiError>)> {
+    simctl::clear_location(&udid).await.map_err(|e| {
+        error!("Failed to clear location: {}", e);
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ApiError::internal(format!("Failed to clear location: {}", e))),
+        )
+    })?;
+    state.activity.touch(&udid);
+
+    Ok(Json(LocationResponse {
+        device_udid: udid,
+        location: None,
+        route_waypoints: 0,
+    }))
+}
+
+/// POST /simulator/{udid}/location/route - Play back a route
+pub async fn play_route(
+    State(state): State<Arc<AppState>>,
+    Path(udid): Path<String>,
+    Json(request): Json<PlayRouteRequest>,
+) -> Result<Json<LocationResponse>, (StatusCode, Json<ApiError>)> {
+    validate_route(&request.waypoints).map_err(|e| {
+        (
+            StatusCode::BAD_REQUEST,
+            Json(ApiError::bad_request(e.to_string())),
+        )
+    })?;
+
+    if !(request.speed_mps.is_finite() && request.speed_mps > 0.0) {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            Json(ApiError::bad_request("Speed must be a positive number")),
+        ));
+    }
+
+    simctl::start_route(&udid, &request.waypoints, request.speed_mps)
+        .await
+        .map_err(|e| {
+            error!("Failed to play route: {}", e);
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ApiError::internal(format!("Failed to play route: {}", e))),
+            )
+        })?;
+    state.activity.touch(&udid);
+
+    Ok(Json(LocationResponse {
+        device_udid: udid,
+        location: None,
+        route_waypoints: request.waypoints.len(),
+    }))
+}
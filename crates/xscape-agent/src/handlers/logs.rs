@@ -40,6 +40,14 @@ async fn handle_socket(socket: WebSocket, state: Arc<AppState>, build_id: Uuid)
 
     let mut log_receiver = log_sender.subscribe();
 
+    // Devices running apps from this build stay busy while logs are streamed
+    let _holds: Vec<_> = state
+        .session_devices(&build_id)
+        .await
+        .iter()
+        .map(|udid| state.activity.hold(udid))
+        .collect();
+
     debug!("WebSocket connected for build {}", build_id);
 
     // Spawn task to forward logs to WebSocket
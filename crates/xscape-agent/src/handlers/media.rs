@@ -0,0 +1,144 @@
+use axum::{
+    extract::{Multipart, Path, State},
+    http::StatusCode,
+    Json,
+};
+use xscape_common::{AddMediaResponse, AddedMedia, ApiError, MediaKind};
+use std::path::PathBuf;
+use std::sync::Arc;
+use tokio::io::AsyncWriteExt;
+use tracing::{debug, error, info, warn};
+use uuid::Uuid;
+
+use crate::server::AppState;
+use crate::simctl;
+
+/// Maximum size of a media upload request
+pub const MAX_MEDIA_UPLOAD_BYTES: usize = 2 * 1024 * 1024 * 1024;
+
+/// Temporary upload directory, removed when dropped
+struct UploadDir(PathBuf);
+
+impl Drop for UploadDir {
+    fn drop(&mut self) {
+        if let Err(e) = std::fs::remove_dir_all(&self.0) {
+            warn!("Failed to remove upload directory {:?}: {}", self.0, e);
+        }
+    }
+}
+
+/// POST /simulator/{udid}/media - Add photos, videos or vCard contacts
+///
+/// Each multipart `file` field is streamed to a temporary directory, then
+/// all files are passed to `simctl addmedia` in one call.
+pub async fn add_media(
+    State(state): State<Arc<AppState>>,
+    Path(udid): Path<String>,
+    mut multipart: Multipart,
+) -> Result<Json<AddMediaResponse>, (StatusCode, Json<ApiError>)> {
+    let upload_path = std::env::temp_dir().join(format!("xscape-media-{}", Uuid::new_v4()));
+    tokio::fs::create_dir_all(&upload_path).await.map_err(|e| {
+        error!("Failed to create upload directory: {}", e);
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ApiError::internal(format!("Failed to create upload directory: {}", e))),
+        )
+    })?;
+    let upload_dir = UploadDir(upload_path);
+
+    let mut paths = Vec::new();
+    let mut added = Vec::new();
+
+    while let Some(mut field) = multipart.next_field().await.map_err(|e| {
+        error!("Failed to read multipart field: {}", e);
+        (
+            StatusCode::BAD_REQUEST,
+            Json(ApiError::bad_request(format!("Invalid multipart data: {}", e))),
+        )
+    })? {
+        let name = field.name().unwrap_or("").to_string();
+        if name != "file" {
+            debug!("Ignoring unknown field: {}", name);
+            continue;
+        }
+
+        // Keep only the final path component of the client-supplied name
+        let file_name = field
+            .file_name()
+            .and_then(|n| std::path::Path::new(n).file_name())
+            .and_then(|n| n.to_str())
+            .map(str::to_string)
+            .ok_or_else(|| {
+                (
+                    StatusCode::BAD_REQUEST,
+                    Json(ApiError::bad_request("File field is missing a file name")),
+                )
+            })?;
+        let kind = MediaKind::from_file_name(&file_name).ok_or_else(|| {
+            (
+                StatusCode::BAD_REQUEST,
+                Json(ApiError::new(
+                    "UNSUPPORTED_MEDIA",
+                    format!("Unsupported media file: {}", file_name),
+                )),
+            )
+        })?;
+
+        // One subdirectory per file so duplicate names don't collide
+        let file_dir = upload_dir.0.join(paths.len().to_string());
+        let file_path = file_dir.join(&file_name);
+        let size_bytes = async {
+            tokio::fs::create_dir_all(&file_dir).await?;
+            let mut file = tokio::fs::File::create(&file_path).await?;
+            let mut size = 0u64;
+            while let Some(chunk) = field.chunk().await.map_err(std::io::Error::other)? {
+                size += chunk.len() as u64;
+                file.write_all(&chunk).await?;
+            }
+            file.flush().await?;
+            Ok::<_, std::io::Error>(size)
+        }
+        .await
+        .map_err(|e| {
+            error!("Failed to store upload {}: {}", file_name, e);
+            (
+                StatusCode::BAD_REQUEST,
+                Json(ApiError::bad_request(format!(
+                    "Failed to receive {}: {}",
+                    file_name, e
+                ))),
+            )
+        })?;
+
+        debug!("Received {} ({} bytes)", file_name, size_bytes);
+        paths.push(file_path);
+        added.push(AddedMedia {
+            file_name,
+            kind,
+            size_bytes,
+        });
+    }
+
+    if paths.is_empty() {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            Json(ApiError::bad_request("No media files uploaded")),
+        ));
+    }
+
+    simctl::add_media(&udid, &paths).await.map_err(|e| {
+        error!("Failed to add media: {}", e);
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ApiError::internal(format!("Failed to add media: {}", e))),
+        )
+    })?;
+    state.activity.touch(&udid);
+
+    info!("Added {} media files to simulator {}", added.len(), udid);
+
+    Ok(Json(AddMediaResponse {
+        device_udid: udid,
+        added,
+    }))
+}
@@ -1,5 +1,19 @@
+pub mod appearance;
+pub mod apps;
+pub mod archive;
+pub mod artifacts;
 pub mod build;
+pub mod clipboard;
+pub mod container;
 pub mod health;
+pub mod keychain;
+pub mod location;
 pub mod logs;
+pub mod media;
+pub mod openurl;
+pub mod privacy;
+pub mod projects;
+pub mod push;
+pub mod sessions;
 pub mod simulator;
 pub mod sync;
@@ -0,0 +1,146 @@
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    Json,
+};
+use xscape_common::{ApiError, IosSimError, OpenUrlRequest, OpenUrlResponse, SimulatorState};
+use std::collections::HashMap;
+use std::sync::Arc;
+use tracing::{error, info};
+
+use super::simulator::make_room;
+use crate::server::AppState;
+use crate::simctl;
+
+/// POST /simulator/{udid}/openurl - Open a deep link or universal link
+pub async fn open_url(
+    State(state): State<Arc<AppState>>,
+    Path(udid): Path<String>,
+    Json(request): Json<OpenUrlRequest>,
+) -> Result<Json<OpenUrlResponse>, (StatusCode, Json<ApiError>)> {
+    if !has_scheme(&request.url) {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            Json(ApiError::bad_request(format!(
+                "Not a URL (missing scheme): {}",
+                request.url
+            ))),
+        ));
+    }
+
+    // simctl openurl fails with an unhelpful error on a shut down device, so
+    // boot it like /simulator/run does
+    let devices = simctl::list_devices().await.map_err(|e| {
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ApiError::internal(format!("Failed to list devices: {}", e))),
+        )
+    })?;
+    let device = devices.iter().find(|d| d.udid == udid).ok_or_else(|| {
+        (
+            StatusCode::NOT_FOUND,
+            Json(ApiError::not_found("Simulator", &udid)),
+        )
+    })?;
+    if device.state != SimulatorState::Booted {
+        if !state.config.simulator.auto_boot {
+            return Err((
+                StatusCode::CONFLICT,
+                Json(ApiError::from(IosSimError::SimulatorNotBooted(udid))),
+            ));
+        }
+
+        make_room(&state, &udid).await?;
+        simctl::boot_device(&udid).await.map_err(|e| {
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ApiError::internal(format!("Failed to boot simulator: {}", e))),
+            )
+        })?;
+    }
+    state.activity.touch(&udid);
+
+    let mut launched_app = false;
+    if let Some(build_id) = request.build_id {
+        let artifacts = state.get_artifacts(&build_id).await.ok_or_else(|| {
+            (
+                StatusCode::NOT_FOUND,
+                Json(ApiError::not_found("Build", &build_id.to_string())),
+            )
+        })?;
+        // Swift package builds have no app to install
+        let app_path = artifacts.app_path.clone().ok_or_else(|| {
+            (
+                StatusCode::BAD_REQUEST,
+                Json(ApiError::bad_request("Build produced no app")),
+            )
+        })?;
+        let bundle_id = artifacts.bundle_id.clone().ok_or_else(|| {
+            (
+                StatusCode::BAD_REQUEST,
+                Json(ApiError::bad_request("Build has no bundle ID")),
+            )
+        })?;
+
+        let installed = simctl::is_app_installed(&udid, &bundle_id)
+            .await
+            .map_err(|e| {
+                (
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    Json(ApiError::internal(format!("Failed to check app: {}", e))),
+                )
+            })?;
+
+        if !installed {
+            info!("{} not installed on {}, launching build {}", bundle_id, udid, build_id);
+            simctl::install_app(&udid, &app_path)
+                .await
+                .map_err(|e| {
+                    error!("Failed to install app: {}", e);
+                    (
+                        StatusCode::INTERNAL_SERVER_ERROR,
+                        Json(ApiError::internal(format!("Failed to install app: {}", e))),
+                    )
+                })?;
+            simctl::launch_app(&udid, &bundle_id, &[], &HashMap::new())
+                .await
+                .map_err(|e| {
+                    error!("Failed to launch app: {}", e);
+                    (
+                        StatusCode::INTERNAL_SERVER_ERROR,
+                        Json(ApiError::internal(format!("Failed to launch app: {}", e))),
+                    )
+                })?;
+            launched_app = true;
+        }
+    }
+
+    simctl::open_url(&udid, &request.url).await.map_err(|e| {
+        error!("Failed to open URL: {}", e);
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ApiError::internal(format!("Failed to open URL: {}", e))),
+        )
+    })?;
+    state.activity.touch(&udid);
+
+    Ok(Json(OpenUrlResponse {
+        device_udid: udid,
+        url: request.url,
+        launched_app,
+    }))
+}
+
+/// Check for an RFC 3986 scheme (`ALPHA *( ALPHA / DIGIT / "+" / "-" / "." ) ":"`)
+fn has_scheme(url: &str) -> bool {
+    match url.split_once(':') {
+        Some((scheme, rest)) => {
+            !rest.is_empty()
+                && scheme.chars().next().is_some_and(|c| c.is_ascii_alphabetic())
+                && scheme
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || matches!(c, '+' | '-' | '.'))
+        }
+        None => false,
+    }
+}
@@ -0,0 +1,54 @@
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    Json,
+};
+use xscape_common::{ApiError, PrivacyAction, PrivacyRequest, PrivacyResponse};
+use std::sync::Arc;
+use tracing::error;
+
+use crate::server::AppState;
+use crate::simctl;
+
+/// POST /simulator/{udid}/privacy - Grant, revoke or reset a privacy permission
+pub async fn set_privacy(
+    State(state): State<Arc<AppState>>,
+    Path(udid): Path<String>,
+    Json(request): Json<PrivacyRequest>,
+) -> Result<Json<PrivacyResponse>, (StatusCode, Json<ApiError>)> {
+    if request.bundle_id.is_none() && request.action != PrivacyAction::Reset {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            Json(ApiError::bad_request(format!(
+                "bundle_id is required to {} a permission",
+                request.action
+            ))),
+        ));
+    }
+
+    simctl::set_privacy(
+        &udid,
+        request.action,
+        request.service,
+        request.bundle_id.as_deref(),
+    )
+    .await
+    .map_err(|e| {
+        error!("Failed to change privacy permission: {}", e);
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ApiError::internal(format!(
+                "Failed to change privacy permission: {}",
+                e
+            ))),
+        )
+    })?;
+    state.activity.touch(&udid);
+
+    Ok(Json(PrivacyResponse {
+        device_udid: udid,
+        action: request.action,
+        service: request.service,
+        bundle_id: request.bundle_id,
+    }))
+}
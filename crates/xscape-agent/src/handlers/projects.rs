@@ -0,0 +1,49 @@
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    Json,
+};
+use xscape_common::{ApiError, ProjectInfoQuery, ProjectInfoResponse};
+use std::sync::Arc;
+use tracing::{error, info};
+use uuid::Uuid;
+
+use crate::server::AppState;
+use crate::xcode;
+
+/// GET /projects/{id}/info - Schemes, targets and configurations of a synced project
+pub async fn get_project_info(
+    State(state): State<Arc<AppState>>,
+    Path(project_id): Path<Uuid>,
+    Query(query): Query<ProjectInfoQuery>,
+) -> Result<Json<ProjectInfoResponse>, (StatusCode, Json<ApiError>)> {
+    let project = state.get_project(&project_id).await.ok_or_else(|| {
+        (
+            StatusCode::NOT_FOUND,
+            Json(ApiError::not_found("Project", &project_id.to_string())),
+        )
+    })?;
+
+    info!("Listing schemes of project '{}'", project.project_name);
+
+    let cache_dir = &state.config.storage.dependencies_dir;
+    let listing = xcode::list_project(&project.path, &query.project_file, cache_dir)
+        .await
+        .map_err(|e| {
+            error!("Failed to list project {}: {:#}", project_id, e);
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ApiError::internal(format!("Failed to list project: {:#}", e))),
+            )
+        })?;
+
+    Ok(Json(ProjectInfoResponse {
+        project_id,
+        project_file: listing.project_file,
+        is_workspace: listing.is_workspace,
+        is_package: listing.is_package,
+        schemes: listing.schemes,
+        targets: listing.targets,
+        configurations: listing.configurations,
+    }))
+}
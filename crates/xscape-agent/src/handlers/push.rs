@@ -0,0 +1,60 @@
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    Json,
+};
+use xscape_common::{
+    validate_push_payload, ApiError, PushNotificationRequest, PushNotificationResponse,
+};
+use std::sync::Arc;
+use tracing::error;
+
+use crate::server::AppState;
+use crate::simctl;
+
+/// POST /simulator/{udid}/push - Deliver a push notification
+pub async fn send_push(
+    State(state): State<Arc<AppState>>,
+    Path(udid): Path<String>,
+    Json(request): Json<PushNotificationRequest>,
+) -> Result<Json<PushNotificationResponse>, (StatusCode, Json<ApiError>)> {
+    if request.bundle_id.trim().is_empty() {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            Json(ApiError::bad_request("Missing bundle_id")),
+        ));
+    }
+
+    validate_push_payload(&request.payload).map_err(|e| {
+        (
+            StatusCode::BAD_REQUEST,
+            Json(ApiError::new("INVALID_PUSH_PAYLOAD", e.to_string())),
+        )
+    })?;
+
+    let payload = serde_json::to_vec(&request.payload).map_err(|e| {
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ApiError::internal(format!("Failed to encode payload: {}", e))),
+        )
+    })?;
+
+    simctl::send_push(&udid, &request.bundle_id, &payload)
+        .await
+        .map_err(|e| {
+            error!("Failed to send push notification: {}", e);
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ApiError::internal(format!(
+                    "Failed to send push notification: {}",
+                    e
+                ))),
+            )
+        })?;
+    state.activity.touch(&udid);
+
+    Ok(Json(PushNotificationResponse {
+        device_udid: udid,
+        bundle_id: request.bundle_id,
+    }))
+}
@@ -0,0 +1,97 @@
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    Json,
+};
+use xscape_common::{
+    ApiError, CrashReport, CrashReportResponse, ListSessionsResponse, SessionInfo, SymbolicateQuery,
+};
+use std::sync::Arc;
+use tracing::error;
+use uuid::Uuid;
+
+use crate::crash;
+use crate::server::AppState;
+
+/// GET /sessions - List app run sessions, most recent first
+pub async fn list_sessions(State(state): State<Arc<AppState>>) -> Json<ListSessionsResponse> {
+    let mut sessions: Vec<SessionInfo> = state
+        .sessions
+        .read()
+        .await
+        .iter()
+        .map(|(session_id, session)| SessionInfo {
+            session_id: *session_id,
+            build_id: session.build_id,
+            device_udid: session.device_udid.clone(),
+            bundle_id: session.bundle_id.clone(),
+            pid: session.pid,
+            started_at: session.started_at,
+        })
+        .collect();
+    sessions.sort_by_key(|s| std::cmp::Reverse(s.started_at));
+
+    Json(ListSessionsResponse { sessions })
+}
+
+/// GET /sessions/{id}/crash - Crash report of a session whose app crashed,
+/// symbolicated with the build's debug symbols when available
+pub async fn get_crash_report(
+    State(state): State<Arc<AppState>>,
+    Path(session_id): Path<Uuid>,
+    Query(query): Query<SymbolicateQuery>,
+) -> Result<Json<CrashReportResponse>, (StatusCode, Json<ApiError>)> {
+    let session = state
+        .sessions
+        .read()
+        .await
+        .get(&session_id)
+        .cloned()
+        .ok_or_else(|| {
+            (
+                StatusCode::NOT_FOUND,
+                Json(ApiError::not_found("Session", &session_id.to_string())),
+            )
+        })?;
+
+    let report_path = session.crash_report.ok_or_else(|| {
+        (
+            StatusCode::NOT_FOUND,
+            Json(ApiError::not_found("Crash report", &session_id.to_string())),
+        )
+    })?;
+
+    let (mut report, mut parsed) = crash::load_report(&report_path).await.map_err(|e| {
+        error!("Failed to load crash report: {}", e);
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ApiError::internal(format!("Failed to load crash report: {}", e))),
+        )
+    })?;
+
+    let mut symbolicated_images = Vec::new();
+    let symbolicated = crash::symbolicate_with_build(
+        &state,
+        &session.build_id,
+        &report,
+        query.source_root.as_deref(),
+    )
+    .await;
+    if let Some(symbolicated) = symbolicated {
+        if let Ok(reparsed) = CrashReport::parse(&symbolicated.report) {
+            parsed = reparsed;
+            report = symbolicated.report;
+            symbolicated_images = symbolicated.images;
+        }
+    }
+
+    Ok(Json(CrashReportResponse {
+        session_id,
+        bundle_id: session.bundle_id,
+        device_udid: session.device_udid,
+        report_path: report_path.to_string_lossy().into_owned(),
+        summary: parsed.summary(),
+        report,
+        symbolicated_images,
+    }))
+}
@@ -1,22 +1,29 @@
 use axum::{
-    extract::State,
+    extract::{Query, State},
     http::StatusCode,
     Json,
 };
+use chrono::Utc;
 use xscape_common::{
-    ApiError, BootSimulatorRequest, BootSimulatorResponse, ListSimulatorsResponse,
-    RunAppRequest, RunAppResponse, ShutdownSimulatorRequest, SimulatorState,
+    ApiError, BootSimulatorRequest, BootSimulatorResponse, InstallAppRequest, InstallAppResponse,
+    IosSimError, ListSimulatorsQuery, ListSimulatorsResponse, PrivacyService, RunAppRequest,
+    RunAppResponse, ShutdownSimulatorRequest, SimulatorState, locale_launch_args, validate_locale,
 };
 use std::sync::Arc;
 use tracing::{error, info};
 use uuid::Uuid;
 
+use crate::crash;
+use crate::handlers::keychain;
+use crate::idle;
 use crate::server::AppState;
 use crate::simctl;
+use crate::storage::RunSession;
 
-/// GET /simulator/list - List available simulators
+/// GET /simulator/list - List available simulators, optionally of one platform
 pub async fn list_simulators(
     State(_state): State<Arc<AppState>>,
+    Query(query): Query<ListSimulatorsQuery>,
 ) -> Result<Json<ListSimulatorsResponse>, (StatusCode, Json<ApiError>)> {
     let devices = simctl::list_devices().await.map_err(|e| {
         error!("Failed to list devices: {}", e);
@@ -34,16 +41,24 @@ pub async fn list_simulators(
         )
     })?;
 
-    Ok(Json(ListSimulatorsResponse { devices, runtimes }))
+    let mut response = ListSimulatorsResponse { devices, runtimes };
+    if let Some(platform) = query.platform {
+        response.retain_platform(platform);
+    }
+
+    Ok(Json(response))
 }
 
 /// POST /simulator/boot - Boot a simulator
 pub async fn boot_simulator(
-    State(_state): State<Arc<AppState>>,
+    State(state): State<Arc<AppState>>,
     Json(request): Json<BootSimulatorRequest>,
 ) -> Result<Json<BootSimulatorResponse>, (StatusCode, Json<ApiError>)> {
     info!("Booting simulator: {}", request.device_udid);
 
+    let root_certificates = keychain::parse_pem_certificates(&request.root_certificates)?;
+    make_room(&state, &request.device_udid).await?;
+
     simctl::boot_device(&request.device_udid).await.map_err(|e| {
         error!("Failed to boot simulator: {}", e);
         (
@@ -51,6 +66,9 @@ pub async fn boot_simulator(
             Json(ApiError::internal(format!("Failed to boot simulator: {}", e))),
         )
     })?;
+    state.activity.touch(&request.device_udid);
+
+    keychain::install_root_certificates(&request.device_udid, &root_certificates).await?;
 
     Ok(Json(BootSimulatorResponse {
         device_udid: request.device_udid,
@@ -71,6 +89,13 @@ pub async fn run_app(
         )
     })?;
 
+    // Swift package builds have no app to install
+    let app_path = artifacts.app_path.clone().ok_or_else(|| {
+        (
+            StatusCode::BAD_REQUEST,
+            Json(ApiError::bad_request("Build produced no app")),
+        )
+    })?;
     let bundle_id = artifacts.bundle_id.clone().ok_or_else(|| {
         (
             StatusCode::BAD_REQUEST,
@@ -83,6 +108,16 @@ pub async fn run_app(
         bundle_id, request.device_udid
     );
 
+    if let Some(ref locale) = request.locale {
+        validate_locale(locale).map_err(|e| {
+            (
+                StatusCode::BAD_REQUEST,
+                Json(ApiError::bad_request(e)),
+            )
+        })?;
+    }
+    let root_certificates = keychain::parse_pem_certificates(&request.root_certificates)?;
+
     // Ensure simulator is booted
     let devices = simctl::list_devices().await.map_err(|e| {
         (
@@ -102,16 +137,28 @@ pub async fn run_app(
         })?;
 
     if device.state != SimulatorState::Booted {
+        if !state.config.simulator.auto_boot {
+            return Err((
+                StatusCode::CONFLICT,
+                Json(ApiError::from(IosSimError::SimulatorNotBooted(
+                    request.device_udid.clone(),
+                ))),
+            ));
+        }
+
+        make_room(&state, &request.device_udid).await?;
         simctl::boot_device(&request.device_udid).await.map_err(|e| {
             (
                 StatusCode::INTERNAL_SERVER_ERROR,
                 Json(ApiError::internal(format!("Failed to boot simulator: {}", e))),
             )
         })?;
+        keychain::install_root_certificates(&request.device_udid, &root_certificates).await?;
     }
+    state.activity.touch(&request.device_udid);
 
     // Install app
-    simctl::install_app(&request.device_udid, &artifacts.app_path)
+    simctl::install_app(&request.device_udid, &app_path)
         .await
         .map_err(|e| {
             error!("Failed to install app: {}", e);
@@ -120,12 +167,38 @@ pub async fn run_app(
                 Json(ApiError::internal(format!("Failed to install app: {}", e))),
             )
         })?;
+    state.activity.touch(&request.device_udid);
+
+    // Apply privacy permissions before launch, `all` first so it doesn't
+    // undo the entries for single services
+    let mut permissions: Vec<_> = request.permissions.iter().collect();
+    permissions.sort_by_key(|(service, _)| **service != PrivacyService::All);
+    for (service, action) in permissions {
+        simctl::set_privacy(&request.device_udid, *action, *service, Some(&bundle_id))
+            .await
+            .map_err(|e| {
+                error!("Failed to apply {} permission: {}", service, e);
+                (
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    Json(ApiError::internal(format!(
+                        "Failed to apply {} permission: {}",
+                        service, e
+                    ))),
+                )
+            })?;
+    }
 
     // Launch app
+    let mut launch_args = request.launch_args.clone();
+    launch_args.extend(locale_launch_args(
+        request.locale.as_deref(),
+        &request.languages,
+    ));
+
     let pid = simctl::launch_app(
         &request.device_udid,
         &bundle_id,
-        &request.launch_args,
+        &launch_args,
         &request.environment,
     )
     .await
@@ -137,7 +210,26 @@ pub async fn run_app(
         )
     })?;
 
+    state.activity.touch(&request.device_udid);
+
     let session_id = Uuid::new_v4();
+    state
+        .store_session(
+            session_id,
+            RunSession {
+                build_id: request.build_id,
+                device_udid: request.device_udid.clone(),
+                bundle_id: bundle_id.clone(),
+                pid,
+                started_at: Utc::now(),
+                ended_at: None,
+                crash_report: None,
+            },
+        )
+        .await;
+    if pid.is_some() {
+        crash::spawn_monitor(state.clone(), session_id);
+    }
 
     Ok(Json(RunAppResponse {
         session_id,
@@ -147,9 +239,55 @@ pub async fn run_app(
     }))
 }
 
+/// POST /simulator/install - Install a build's app without launching it
+pub async fn install_app(
+    State(state): State<Arc<AppState>>,
+    Json(request): Json<InstallAppRequest>,
+) -> Result<Json<InstallAppResponse>, (StatusCode, Json<ApiError>)> {
+    let artifacts = state.get_artifacts(&request.build_id).await.ok_or_else(|| {
+        (
+            StatusCode::NOT_FOUND,
+            Json(ApiError::not_found("Build", &request.build_id.to_string())),
+        )
+    })?;
+
+    // Swift package builds have no app to install
+    let app_path = artifacts.app_path.clone().ok_or_else(|| {
+        (
+            StatusCode::BAD_REQUEST,
+            Json(ApiError::bad_request("Build produced no app")),
+        )
+    })?;
+    let bundle_id = artifacts.bundle_id.clone().ok_or_else(|| {
+        (
+            StatusCode::BAD_REQUEST,
+            Json(ApiError::bad_request("Build has no bundle ID")),
+        )
+    })?;
+
+    info!("Installing {} on simulator {}", bundle_id, request.device_udid);
+
+    simctl::install_app(&request.device_udid, &app_path)
+        .await
+        .map_err(|e| {
+            error!("Failed to install app: {}", e);
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ApiError::internal(format!("Failed to install app: {}", e))),
+            )
+        })?;
+    state.activity.touch(&request.device_udid);
+
+    Ok(Json(InstallAppResponse {
+        build_id: request.build_id,
+        bundle_id,
+        device_udid: request.device_udid,
+    }))
+}
+
 /// POST /simulator/shutdown - Shutdown a simulator
 pub async fn shutdown_simulator(
-    State(_state): State<Arc<AppState>>,
+    State(state): State<Arc<AppState>>,
     Json(request): Json<ShutdownSimulatorRequest>,
 ) -> Result<Json<BootSimulatorResponse>, (StatusCode, Json<ApiError>)> {
     info!("Shutting down simulator: {}", request.device_udid);
@@ -166,9 +304,26 @@ pub async fn shutdown_simulator(
                 ))),
             )
         })?;
+    state.activity.forget(&request.device_udid);
 
     Ok(Json(BootSimulatorResponse {
         device_udid: request.device_udid,
         state: SimulatorState::Shutdown,
     }))
 }
+
+/// Shut down least recently used simulators if booting `udid` would exceed the limit
+pub(crate) async fn make_room(state: &AppState, udid: &str) -> Result<(), (StatusCode, Json<ApiError>)> {
+    idle::make_room(&state.activity, &state.config.simulator, udid)
+        .await
+        .map_err(|e| {
+            error!("Failed to enforce simulator limit: {}", e);
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ApiError::internal(format!(
+                    "Failed to enforce simulator limit: {}",
+                    e
+                ))),
+            )
+        })
+}
@@ -0,0 +1,223 @@
+use anyhow::Result;
+use xscape_common::{AgentSimulatorConfig, SimulatorState};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tracing::{debug, error, info};
+
+use crate::server::AppState;
+use crate::simctl;
+
+/// How often the idle sweep runs
+const SWEEP_INTERVAL: Duration = Duration::from_secs(60);
+
+#[derive(Debug)]
+struct DeviceActivity {
+    last_active: Instant,
+    /// Number of live holds (e.g. log subscriptions) keeping the device busy
+    holds: usize,
+}
+
+/// Tracks the last activity of each simulator device
+#[derive(Debug, Default)]
+pub struct ActivityTracker {
+    devices: Mutex<HashMap<String, DeviceActivity>>,
+}
+
+impl ActivityTracker {
+    /// Record activity on a device
+    pub fn touch(&self, udid: &str) {
+        let mut devices = self.devices.lock().unwrap();
+        devices
+            .entry(udid.to_string())
+            .and_modify(|d| d.last_active = Instant::now())
+            .or_insert_with(|| DeviceActivity {
+                last_active: Instant::now(),
+                holds: 0,
+            });
+    }
+
+    /// Keep a device busy until the returned guard is dropped
+    pub fn hold(self: &Arc<Self>, udid: &str) -> ActivityHold {
+        self.touch(udid);
+        if let Some(device) = self.devices.lock().unwrap().get_mut(udid) {
+            device.holds += 1;
+        }
+        ActivityHold {
+            tracker: self.clone(),
+            udid: udid.to_string(),
+        }
+    }
+
+    /// Stop tracking a device (after it was shut down)
+    pub fn forget(&self, udid: &str) {
+        let mut devices = self.devices.lock().unwrap();
+        if devices.get(udid).is_some_and(|d| d.holds == 0) {
+            devices.remove(udid);
+        }
+    }
+
+    /// Time since last activity, or None if the device is held or unknown
+    fn idle_for(&self, udid: &str) -> Option<Duration> {
+        self.devices
+            .lock()
+            .unwrap()
+            .get(udid)
+            .filter(|d| d.holds == 0)
+            .map(|d| d.last_active.elapsed())
+    }
+
+    /// Match tracked devices to the currently booted ones. Devices booted
+    /// outside the agent start their idle clock now; devices no longer booted
+    /// are dropped unless held.
+    fn sync(&self, booted: &[String]) {
+        let mut devices = self.devices.lock().unwrap();
+        devices.retain(|udid, d| d.holds > 0 || booted.contains(udid));
+        for udid in booted {
+            devices.entry(udid.clone()).or_insert_with(|| DeviceActivity {
+                last_active: Instant::now(),
+                holds: 0,
+            });
+        }
+    }
+}
+
+/// Guard returned by [`ActivityTracker::hold`]
+pub struct ActivityHold {
+    tracker: Arc<ActivityTracker>,
+    udid: String,
+}
+
+impl Drop for ActivityHold {
+    fn drop(&mut self) {
+        let mut devices = self.tracker.devices.lock().unwrap();
+        if let Some(device) = devices.get_mut(&self.udid) {
+            device.holds = device.holds.saturating_sub(1);
+            device.last_active = Instant::now();
+        }
+    }
+}
+
+/// Spawn the background task that shuts down idle simulators
+pub fn spawn(state: Arc<AppState>) {
+    let config = state.config.simulator.clone();
+    if config.shutdown_idle_after_minutes == 0 && config.max_booted_devices == 0 {
+        info!("Idle simulator shutdown disabled");
+        return;
+    }
+
+    info!(
+        "Idle simulator shutdown enabled (idle: {} min, max booted: {})",
+        config.shutdown_idle_after_minutes, config.max_booted_devices
+    );
+
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(SWEEP_INTERVAL);
+        loop {
+            interval.tick().await;
+            if let Err(e) = sweep(&state.activity, &config).await {
+                error!("Idle simulator sweep failed: {}", e);
+            }
+        }
+    });
+}
+
+/// Shut down devices idle beyond the threshold, then enforce the boot limit
+async fn sweep(tracker: &ActivityTracker, config: &AgentSimulatorConfig) -> Result<()> {
+    let mut booted = booted_devices().await?;
+    tracker.sync(&booted);
+
+    if config.shutdown_idle_after_minutes > 0 {
+        let threshold = Duration::from_secs(config.shutdown_idle_after_minutes as u64 * 60);
+        let mut remaining = Vec::new();
+        for udid in booted {
+            match tracker.idle_for(&udid) {
+                Some(idle) if idle >= threshold => {
+                    info!(
+                        "Shutting down simulator {} (idle for {} min)",
+                        udid,
+                        idle.as_secs() / 60
+                    );
+                    shutdown(tracker, &udid).await;
+                }
+                _ => remaining.push(udid),
+            }
+        }
+        booted = remaining;
+    }
+
+    shutdown_least_recently_used(tracker, config, booted, 0, None).await;
+    Ok(())
+}
+
+/// Make room for a device that is about to be booted
+pub async fn make_room(
+    tracker: &ActivityTracker,
+    config: &AgentSimulatorConfig,
+    booting_udid: &str,
+) -> Result<()> {
+    if config.max_booted_devices == 0 {
+        return Ok(());
+    }
+
+    let booted = booted_devices().await?;
+    if booted.iter().any(|udid| udid == booting_udid) {
+        return Ok(());
+    }
+    tracker.sync(&booted);
+
+    shutdown_least_recently_used(tracker, config, booted, 1, Some(booting_udid)).await;
+    Ok(())
+}
+
+/// Shut down least recently used devices until `booted` plus `reserve`
+/// fits within the limit. Held devices are never shut down.
+async fn shutdown_least_recently_used(
+    tracker: &ActivityTracker,
+    config: &AgentSimulatorConfig,
+    booted: Vec<String>,
+    reserve: usize,
+    keep: Option<&str>,
+) {
+    let limit = config.max_booted_devices as usize;
+    if limit == 0 || booted.len() + reserve <= limit {
+        return;
+    }
+
+    let mut candidates: Vec<(String, Duration)> = booted
+        .iter()
+        .filter(|udid| Some(udid.as_str()) != keep)
+        .filter_map(|udid| tracker.idle_for(udid).map(|idle| (udid.clone(), idle)))
+        .collect();
+    // Longest idle first
+    candidates.sort_by_key(|(_, idle)| std::cmp::Reverse(*idle));
+
+    let excess = booted.len() + reserve - limit;
+    for (udid, _) in candidates.into_iter().take(excess) {
+        info!(
+            "Shutting down simulator {} (limit of {} booted devices reached)",
+            udid, limit
+        );
+        shutdown(tracker, &udid).await;
+    }
+}
+
+async fn shutdown(tracker: &ActivityTracker, udid: &str) {
+    match simctl::shutdown_device(udid).await {
+        Ok(()) => tracker.forget(udid),
+        Err(e) => error!("Failed to shut down idle simulator {}: {}", udid, e),
+    }
+}
+
+async fn booted_devices() -> Result<Vec<String>> {
+    let devices = simctl::list_devices().await?;
+    let booted: Vec<String> = devices
+        .into_iter()
+        .filter(|d| d.state == SimulatorState::Booted)
+        .map(|d| d.udid)
+        .collect();
+
+    debug!("{} booted simulators", booted.len());
+
+    Ok(booted)
+}
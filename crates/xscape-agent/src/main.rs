@@ -4,7 +4,9 @@ use std::path::PathBuf;
 use tracing::{info, Level};
 use tracing_subscriber::FmtSubscriber;
 
+mod crash;
 mod handlers;
+mod idle;
 mod server;
 mod simctl;
 mod storage;
@@ -10,12 +10,20 @@ use xscape_common::AgentServerConfig;
 use std::sync::Arc;
 use tokio::net::TcpListener;
 use tower_http::trace::TraceLayer;
-use tracing::info;
+use tracing::{info, warn};
 
 pub async fn run(config: AgentServerConfig) -> Result<()> {
     let state = AppState::new(config.clone()).await?;
     let state = Arc::new(state);
 
+    crate::idle::spawn(state.clone());
+    crate::storage::spawn_dependency_cleanup(config.storage.clone());
+    if let Err(e) = crate::storage::remove_orphaned_build_outputs(&config.storage).await {
+        warn!("Failed to remove old build outputs: {}", e);
+    }
+    // Before serving, so no archive of this run can be mistaken for a stale one
+    crate::xcode::remove_stale_sessions().await;
+
     let app = create_router(state).layer(TraceLayer::new_for_http());
 
     let addr = format!("{}:{}", config.host, config.port);
@@ -1,5 +1,6 @@
 use axum::{
-    routing::{get, post},
+    extract::DefaultBodyLimit,
+    routing::{get, post, put},
     Router,
 };
 use std::sync::Arc;
@@ -13,17 +14,97 @@ pub fn create_router(state: Arc<AppState>) -> Router {
         .route("/health", get(handlers::health::health_check))
         // Project sync
         .route("/sync-project", post(handlers::sync::sync_project))
+        .route(
+            "/projects/{project_id}/info",
+            get(handlers::projects::get_project_info),
+        )
         // Build
         .route("/build", post(handlers::build::start_build))
+        .route("/build/latest", get(handlers::build::get_latest_build))
         .route("/build/{build_id}", get(handlers::build::get_build_status))
+        .route(
+            "/build/{build_id}/url-schemes",
+            get(handlers::build::get_url_schemes),
+        )
+        .route(
+            "/build/{build_id}/symbolicate",
+            post(handlers::build::symbolicate_report),
+        )
+        .route(
+            "/build/{build_id}/artifacts",
+            get(handlers::artifacts::list_artifacts),
+        )
+        .route(
+            "/build/{build_id}/artifacts/{name}",
+            get(handlers::artifacts::download_artifact),
+        )
+        // Device archives
+        .route("/archive", post(handlers::archive::start_archive))
+        // Prebuilt apps
+        .route("/apps/upload", post(handlers::apps::upload_app))
         // Simulator
         .route("/simulator/list", get(handlers::simulator::list_simulators))
         .route("/simulator/boot", post(handlers::simulator::boot_simulator))
         .route("/simulator/run", post(handlers::simulator::run_app))
+        .route("/simulator/install", post(handlers::simulator::install_app))
         .route(
             "/simulator/shutdown",
             post(handlers::simulator::shutdown_simulator),
         )
+        // Simulated location
+        .route(
+            "/simulator/{udid}/location",
+            post(handlers::location::set_location).delete(handlers::location::clear_location),
+        )
+        .route(
+            "/simulator/{udid}/location/route",
+            post(handlers::location::play_route),
+        )
+        // Push notifications
+        .route("/simulator/{udid}/push", post(handlers::push::send_push))
+        // Deep links
+        .route("/simulator/{udid}/openurl", post(handlers::openurl::open_url))
+        // Privacy permissions
+        .route("/simulator/{udid}/privacy", post(handlers::privacy::set_privacy))
+        // Keychain and certificates
+        .route(
+            "/simulator/{udid}/keychain",
+            post(handlers::keychain::update_keychain),
+        )
+        // Photos, videos and contacts
+        .route(
+            "/simulator/{udid}/media",
+            post(handlers::media::add_media)
+                .layer(DefaultBodyLimit::max(handlers::media::MAX_MEDIA_UPLOAD_BYTES)),
+        )
+        // App container files
+        .route(
+            "/simulator/{udid}/apps/{bundle_id}/container",
+            get(handlers::container::list_container),
+        )
+        .route(
+            "/simulator/{udid}/apps/{bundle_id}/container/download",
+            get(handlers::container::download_container),
+        )
+        .route(
+            "/simulator/{udid}/apps/{bundle_id}/container/upload",
+            put(handlers::container::upload_container),
+        )
+        // Clipboard
+        .route(
+            "/simulator/{udid}/pasteboard",
+            get(handlers::clipboard::get_pasteboard).post(handlers::clipboard::set_pasteboard),
+        )
+        // Appearance and status bar
+        .route("/simulator/{udid}/ui", post(handlers::appearance::set_ui))
+        .route(
+            "/simulator/{udid}/status-bar",
+            post(handlers::appearance::override_status_bar)
+                .delete(handlers::appearance::clear_status_bar),
+        )
+        // Run sessions
+        .route("/sessions", get(handlers::sessions::list_sessions))
+        .route("/sessions/{id}/crash", get(handlers::sessions::get_crash_report))
         // Logs (WebSocket)
         .route("/logs/{build_id}", get(handlers::logs::logs_websocket))
         // State
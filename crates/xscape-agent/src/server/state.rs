@@ -1,11 +1,16 @@
 use anyhow::Result;
-use xscape_common::{AgentServerConfig, BuildStatusResponse, ProjectInfo};
+use chrono::{DateTime, Utc};
+use xscape_common::{AgentServerConfig, BuildStatus, BuildStatusResponse, ProjectInfo};
 use std::collections::HashMap;
 use std::sync::Arc;
 use tokio::sync::RwLock;
 use uuid::Uuid;
 
-use crate::storage::BuildArtifacts;
+use crate::idle::ActivityTracker;
+use crate::storage::{BuildArtifacts, RunSession};
+
+/// Run sessions kept before the oldest finished ones are forgotten
+const MAX_SESSIONS: usize = 100;
 
 /// Shared application state
 pub struct AppState {
@@ -18,6 +23,10 @@ pub struct AppState {
     pub artifacts: RwLock<HashMap<Uuid, BuildArtifacts>>,
     /// Log subscribers per build (for streaming)
     pub log_subscribers: RwLock<HashMap<Uuid, Vec<tokio::sync::broadcast::Sender<String>>>>,
+    /// App run sessions
+    pub sessions: RwLock<HashMap<Uuid, RunSession>>,
+    /// Last activity per simulator device (for idle shutdown)
+    pub activity: Arc<ActivityTracker>,
 }
 
 impl AppState {
@@ -28,6 +37,8 @@ impl AppState {
             builds: RwLock::new(HashMap::new()),
             artifacts: RwLock::new(HashMap::new()),
             log_subscribers: RwLock::new(HashMap::new()),
+            sessions: RwLock::new(HashMap::new()),
+            activity: Arc::new(ActivityTracker::default()),
         })
     }
 
@@ -46,9 +57,52 @@ impl AppState {
         self.builds.read().await.get(id).cloned()
     }
 
-    /// Store build status
+    /// Store build status. Past `max_builds`, the oldest finished builds are
+    /// forgotten along with their artifacts, and their outputs deleted.
     pub async fn store_build(&self, build: BuildStatusResponse) {
-        self.builds.write().await.insert(build.build_id, build);
+        let mut builds = self.builds.write().await;
+        builds.insert(build.build_id, build);
+        let max_builds = self.config.storage.max_builds;
+        if max_builds == 0 || builds.len() <= max_builds {
+            return;
+        }
+
+        let mut finished: Vec<(DateTime<Utc>, Uuid)> = builds
+            .values()
+            .filter(|b| !matches!(b.status, BuildStatus::Queued | BuildStatus::Building))
+            .map(|b| (b.started_at, b.build_id))
+            .collect();
+        finished.sort();
+        let excess = builds.len() - max_builds;
+        let evicted: Vec<Uuid> = finished.into_iter().take(excess).map(|(_, id)| id).collect();
+        for id in &evicted {
+            builds.remove(id);
+        }
+        drop(builds);
+
+        {
+            let mut artifacts = self.artifacts.write().await;
+            let mut log_subscribers = self.log_subscribers.write().await;
+            for id in &evicted {
+                artifacts.remove(id);
+                log_subscribers.remove(id);
+            }
+        }
+
+        for id in evicted {
+            crate::storage::remove_build_output(&self.config.storage, id).await;
+        }
+    }
+
+    /// Most recent successful build
+    pub async fn latest_build(&self) -> Option<BuildStatusResponse> {
+        self.builds
+            .read()
+            .await
+            .values()
+            .filter(|b| b.status == BuildStatus::Succeeded)
+            .max_by_key(|b| b.started_at)
+            .cloned()
     }
 
     /// Get build artifacts
@@ -61,6 +115,43 @@ impl AppState {
         self.artifacts.write().await.insert(build_id, artifacts);
     }
 
+    /// Store run session, forgetting the oldest sessions whose app is no
+    /// longer running once there are more than `MAX_SESSIONS`
+    pub async fn store_session(&self, session_id: Uuid, session: RunSession) {
+        let mut sessions = self.sessions.write().await;
+        sessions.insert(session_id, session);
+        if sessions.len() <= MAX_SESSIONS {
+            return;
+        }
+
+        // Sessions without a PID were never watched, so can't be told apart from ended ones
+        let mut finished: Vec<(DateTime<Utc>, Uuid)> = sessions
+            .iter()
+            .filter(|(_, s)| s.ended_at.is_some() || s.pid.is_none())
+            .map(|(id, s)| (s.started_at, *id))
+            .collect();
+        finished.sort();
+        let excess = sessions.len() - MAX_SESSIONS;
+        for (_, id) in finished.into_iter().take(excess) {
+            sessions.remove(&id);
+        }
+    }
+
+    /// Devices running apps from a build
+    pub async fn session_devices(&self, build_id: &Uuid) -> Vec<String> {
+        let mut devices: Vec<String> = self
+            .sessions
+            .read()
+            .await
+            .values()
+            .filter(|s| &s.build_id == build_id)
+            .map(|s| s.device_udid.clone())
+            .collect();
+        devices.sort();
+        devices.dedup();
+        devices
+    }
+
     /// Create a log broadcast channel for a build
     pub async fn create_log_channel(&self, build_id: Uuid) -> tokio::sync::broadcast::Sender<String> {
         let (tx, _) = tokio::sync::broadcast::channel(1000);
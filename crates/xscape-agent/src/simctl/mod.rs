@@ -1,8 +1,13 @@
 use anyhow::{anyhow, Context, Result};
-use xscape_common::{SimulatorDevice, SimulatorRuntime, SimulatorState};
+use xscape_common::{
+    Appearance, ContainerType, ContentSize, Coordinate, KeychainAction, PrivacyAction,
+    PrivacyService, SimulatorDevice, SimulatorRuntime, SimulatorState, StatusBarOverride,
+};
 use serde::Deserialize;
 use std::collections::HashMap;
+use std::path::{Path, PathBuf};
 use std::process::Stdio;
+use tokio::io::AsyncWriteExt;
 use tokio::process::Command;
 use tracing::{debug, info};
 
@@ -279,6 +284,391 @@ pub async fn uninstall_app(udid: &str, bundle_id: &str) -> Result<()> {
     Ok(())
 }
 
+/// Set a fixed simulated location
+pub async fn set_location(udid: &str, location: &Coordinate) -> Result<()> {
+    info!("Setting location of simulator {} to {}", udid, location);
+
+    let output = Command::new("xcrun")
+        .args(["simctl", "location", udid, "set", &location.to_string()])
+        .output()
+        .await
+        .context("Failed to run simctl location set")?;
+
+    if !output.status.success() {
+        return Err(anyhow!(
+            "simctl location set failed: {}",
+            String::from_utf8_lossy(&output.stderr)
+        ));
+    }
+
+    Ok(())
+}
+
+/// Clear the simulated location (also stops route playback)
+pub async fn clear_location(udid: &str) -> Result<()> {
+    info!("Clearing location of simulator {}", udid);
+
+    let output = Command::new("xcrun")
+        .args(["simctl", "location", udid, "clear"])
+        .output()
+        .await
+        .context("Failed to run simctl location clear")?;
+
+    if !output.status.success() {
+        return Err(anyhow!(
+            "simctl location clear failed: {}",
+            String::from_utf8_lossy(&output.stderr)
+        ));
+    }
+
+    Ok(())
+}
+
+/// Start moving the simulated location along a route
+pub async fn start_route(udid: &str, waypoints: &[Coordinate], speed_mps: f64) -> Result<()> {
+    info!(
+        "Playing route with {} waypoints on simulator {} at {} m/s",
+        waypoints.len(),
+        udid,
+        speed_mps
+    );
+
+    // Waypoints are read from stdin ("-") so long routes don't hit argv limits
+    let mut child = Command::new("xcrun")
+        .args(["simctl", "location", udid, "start"])
+        .arg(format!("--speed={}", speed_mps))
+        .arg("-")
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .context("Failed to run simctl location start")?;
+
+    let mut stdin = child.stdin.take().context("Failed to open simctl stdin")?;
+    let input: String = waypoints.iter().map(|w| format!("{}\n", w)).collect();
+    stdin.write_all(input.as_bytes()).await?;
+    drop(stdin);
+
+    let output = child
+        .wait_with_output()
+        .await
+        .context("Failed to wait for simctl location start")?;
+
+    if !output.status.success() {
+        return Err(anyhow!(
+            "simctl location start failed: {}",
+            String::from_utf8_lossy(&output.stderr)
+        ));
+    }
+
+    Ok(())
+}
+
+/// Deliver a push notification payload to an app
+pub async fn send_push(udid: &str, bundle_id: &str, payload: &[u8]) -> Result<()> {
+    info!("Sending push notification to {} on simulator {}", bundle_id, udid);
+
+    let mut child = Command::new("xcrun")
+        .args(["simctl", "push", udid, bundle_id, "-"])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .context("Failed to run simctl push")?;
+
+    let mut stdin = child.stdin.take().context("Failed to open simctl stdin")?;
+    stdin.write_all(payload).await?;
+    drop(stdin);
+
+    let output = child
+        .wait_with_output()
+        .await
+        .context("Failed to wait for simctl push")?;
+
+    if !output.status.success() {
+        return Err(anyhow!(
+            "simctl push failed: {}",
+            String::from_utf8_lossy(&output.stderr)
+        ));
+    }
+
+    Ok(())
+}
+
+/// Replace the simulator pasteboard contents with text
+pub async fn pbcopy(udid: &str, text: &str) -> Result<()> {
+    debug!("Writing {} bytes to pasteboard of simulator {}", text.len(), udid);
+
+    let mut child = Command::new("xcrun")
+        .args(["simctl", "pbcopy", udid])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .context("Failed to run simctl pbcopy")?;
+
+    let mut stdin = child.stdin.take().context("Failed to open simctl stdin")?;
+    stdin.write_all(text.as_bytes()).await?;
+    drop(stdin);
+
+    let output = child
+        .wait_with_output()
+        .await
+        .context("Failed to wait for simctl pbcopy")?;
+
+    if !output.status.success() {
+        return Err(anyhow!(
+            "simctl pbcopy failed: {}",
+            String::from_utf8_lossy(&output.stderr)
+        ));
+    }
+
+    Ok(())
+}
+
+/// Read the simulator pasteboard as text
+pub async fn pbpaste(udid: &str) -> Result<String> {
+    let output = Command::new("xcrun")
+        .args(["simctl", "pbpaste", udid])
+        .output()
+        .await
+        .context("Failed to run simctl pbpaste")?;
+
+    if !output.status.success() {
+        return Err(anyhow!(
+            "simctl pbpaste failed: {}",
+            String::from_utf8_lossy(&output.stderr)
+        ));
+    }
+
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+/// Add photos, videos or vCard contacts to a simulator
+pub async fn add_media(udid: &str, paths: &[PathBuf]) -> Result<()> {
+    info!("Adding {} media files to simulator {}", paths.len(), udid);
+
+    let output = Command::new("xcrun")
+        .args(["simctl", "addmedia", udid])
+        .args(paths)
+        .output()
+        .await
+        .context("Failed to run simctl addmedia")?;
+
+    if !output.status.success() {
+        return Err(anyhow!(
+            "simctl addmedia failed: {}",
+            String::from_utf8_lossy(&output.stderr)
+        ));
+    }
+
+    Ok(())
+}
+
+/// Run a keychain operation; certificate actions need a certificate file
+pub async fn keychain(udid: &str, action: KeychainAction, certificate: Option<&Path>) -> Result<()> {
+    info!("Running keychain {} on simulator {}", action, udid);
+
+    let mut cmd = Command::new("xcrun");
+    cmd.args(["simctl", "keychain", udid, action.as_str()]);
+    if let Some(path) = certificate {
+        cmd.arg(path);
+    }
+
+    let output = cmd
+        .output()
+        .await
+        .context("Failed to run simctl keychain")?;
+
+    if !output.status.success() {
+        return Err(anyhow!(
+            "simctl keychain {} failed: {}",
+            action,
+            String::from_utf8_lossy(&output.stderr)
+        ));
+    }
+
+    Ok(())
+}
+
+/// Open a URL on a simulator
+pub async fn open_url(udid: &str, url: &str) -> Result<()> {
+    info!("Opening URL {} on simulator {}", url, udid);
+
+    let output = Command::new("xcrun")
+        .args(["simctl", "openurl", udid, url])
+        .output()
+        .await
+        .context("Failed to run simctl openurl")?;
+
+    if !output.status.success() {
+        return Err(anyhow!(
+            "simctl openurl failed: {}",
+            String::from_utf8_lossy(&output.stderr)
+        ));
+    }
+
+    Ok(())
+}
+
+/// Check whether an app is installed on a simulator
+pub async fn is_app_installed(udid: &str, bundle_id: &str) -> Result<bool> {
+    let output = Command::new("xcrun")
+        .args(["simctl", "get_app_container", udid, bundle_id])
+        .output()
+        .await
+        .context("Failed to run simctl get_app_container")?;
+
+    Ok(output.status.success())
+}
+
+/// Resolve the path of an app's bundle, data or app group container
+pub async fn get_app_container(
+    udid: &str,
+    bundle_id: &str,
+    container: &ContainerType,
+) -> Result<PathBuf> {
+    let output = Command::new("xcrun")
+        .args([
+            "simctl",
+            "get_app_container",
+            udid,
+            bundle_id,
+            container.as_str(),
+        ])
+        .output()
+        .await
+        .context("Failed to run simctl get_app_container")?;
+
+    if !output.status.success() {
+        return Err(anyhow!(
+            "simctl get_app_container failed: {}",
+            String::from_utf8_lossy(&output.stderr)
+        ));
+    }
+
+    let path = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    if path.is_empty() {
+        return Err(anyhow!("simctl get_app_container returned no path"));
+    }
+
+    Ok(PathBuf::from(path))
+}
+
+/// Grant, revoke or reset access to a privacy-protected service
+pub async fn set_privacy(
+    udid: &str,
+    action: PrivacyAction,
+    service: PrivacyService,
+    bundle_id: Option<&str>,
+) -> Result<()> {
+    info!(
+        "Privacy {} {} for {} on simulator {}",
+        action,
+        service,
+        bundle_id.unwrap_or("all apps"),
+        udid
+    );
+
+    let mut cmd = Command::new("xcrun");
+    cmd.args(["simctl", "privacy", udid, &action.to_string(), service.as_str()]);
+    if let Some(bundle_id) = bundle_id {
+        cmd.arg(bundle_id);
+    }
+
+    let output = cmd.output().await.context("Failed to run simctl privacy")?;
+
+    if !output.status.success() {
+        return Err(anyhow!(
+            "simctl privacy failed: {}",
+            String::from_utf8_lossy(&output.stderr)
+        ));
+    }
+
+    Ok(())
+}
+
+/// Switch between light and dark mode
+pub async fn set_appearance(udid: &str, appearance: Appearance) -> Result<()> {
+    info!("Setting appearance of simulator {} to {}", udid, appearance);
+
+    let output = Command::new("xcrun")
+        .args(["simctl", "ui", udid, "appearance", appearance.as_str()])
+        .output()
+        .await
+        .context("Failed to run simctl ui appearance")?;
+
+    if !output.status.success() {
+        return Err(anyhow!(
+            "simctl ui appearance failed: {}",
+            String::from_utf8_lossy(&output.stderr)
+        ));
+    }
+
+    Ok(())
+}
+
+/// Set the Dynamic Type content size
+pub async fn set_content_size(udid: &str, size: ContentSize) -> Result<()> {
+    info!("Setting content size of simulator {} to {}", udid, size);
+
+    let output = Command::new("xcrun")
+        .args(["simctl", "ui", udid, "content_size", size.as_str()])
+        .output()
+        .await
+        .context("Failed to run simctl ui content_size")?;
+
+    if !output.status.success() {
+        return Err(anyhow!(
+            "simctl ui content_size failed: {}",
+            String::from_utf8_lossy(&output.stderr)
+        ));
+    }
+
+    Ok(())
+}
+
+/// Override status bar values
+pub async fn override_status_bar(udid: &str, status_bar: &StatusBarOverride) -> Result<()> {
+    info!("Overriding status bar of simulator {}", udid);
+
+    let output = Command::new("xcrun")
+        .args(["simctl", "status_bar", udid, "override"])
+        .args(status_bar.to_simctl_args())
+        .output()
+        .await
+        .context("Failed to run simctl status_bar override")?;
+
+    if !output.status.success() {
+        return Err(anyhow!(
+            "simctl status_bar override failed: {}",
+            String::from_utf8_lossy(&output.stderr)
+        ));
+    }
+
+    Ok(())
+}
+
+/// Remove all status bar overrides
+pub async fn clear_status_bar(udid: &str) -> Result<()> {
+    info!("Clearing status bar overrides of simulator {}", udid);
+
+    let output = Command::new("xcrun")
+        .args(["simctl", "status_bar", udid, "clear"])
+        .output()
+        .await
+        .context("Failed to run simctl status_bar clear")?;
+
+    if !output.status.success() {
+        return Err(anyhow!(
+            "simctl status_bar clear failed: {}",
+            String::from_utf8_lossy(&output.stderr)
+        ));
+    }
+
+    Ok(())
+}
+
 /// Get device by name (finds first matching available device)
 pub async fn find_device_by_name(name: &str) -> Result<SimulatorDevice> {
     let devices = list_devices().await?;
@@ -1,21 +1,83 @@
 use anyhow::{Context, Result};
 use flate2::read::GzDecoder;
-use xscape_common::StorageConfig;
+use xscape_common::{AppInfo, ArtifactKind, StorageConfig};
 use std::io::Cursor;
-use std::path::Path;
+use std::path::PathBuf;
+use std::time::{Duration, SystemTime};
 use tar::Archive;
-use tracing::{debug, info};
+use tracing::{debug, error, info};
 use uuid::Uuid;
 
+/// How often unused dependency cache entries are evicted
+const DEPENDENCY_CLEANUP_INTERVAL: Duration = Duration::from_secs(60 * 60);
+
 /// Build artifacts stored after successful build
 #[derive(Debug, Clone)]
 pub struct BuildArtifacts {
-    /// Path to built .app bundle
-    pub app_path: String,
+    /// The build's copy of the .app bundle (none for Swift packages)
+    pub app_path: Option<String>,
     /// Bundle identifier
     pub bundle_id: Option<String>,
+    /// Metadata from the app's Info.plist
+    pub app_info: Option<AppInfo>,
     /// Build warnings
     pub warnings: Vec<String>,
+    /// Copies of the dSYM bundles produced by the build (used for symbolication)
+    pub dsym_paths: Vec<PathBuf>,
+    /// xcodebuild result bundle
+    pub result_bundle_path: Option<PathBuf>,
+    /// Full xcodebuild output
+    pub log_path: Option<PathBuf>,
+    /// Device archive (archive builds only)
+    pub archive_path: Option<PathBuf>,
+    /// Exported .ipa (archive builds only)
+    pub ipa_path: Option<PathBuf>,
+}
+
+impl BuildArtifacts {
+    /// Downloadable products of the build that still exist on disk
+    pub fn products(&self) -> Vec<(ArtifactKind, PathBuf)> {
+        let mut products: Vec<_> = self
+            .app_path
+            .iter()
+            .map(|p| (ArtifactKind::App, PathBuf::from(p)))
+            .collect();
+        products.extend(self.dsym_paths.iter().map(|p| (ArtifactKind::Dsym, p.clone())));
+        if let Some(path) = &self.result_bundle_path {
+            products.push((ArtifactKind::ResultBundle, path.clone()));
+        }
+        if let Some(path) = &self.log_path {
+            products.push((ArtifactKind::Log, path.clone()));
+        }
+        if let Some(path) = &self.archive_path {
+            products.push((ArtifactKind::Archive, path.clone()));
+        }
+        if let Some(path) = &self.ipa_path {
+            products.push((ArtifactKind::Ipa, path.clone()));
+        }
+        products.retain(|(_, path)| path.exists());
+        products
+    }
+}
+
+/// App launched on a simulator via /simulator/run
+#[derive(Debug, Clone)]
+pub struct RunSession {
+    /// Build the app came from
+    pub build_id: Uuid,
+    /// Device UDID the app is running on
+    pub device_udid: String,
+    /// Bundle identifier of the launched app
+    pub bundle_id: String,
+    /// Process ID (if available)
+    pub pid: Option<u32>,
+    /// When the app was launched
+    pub started_at: chrono::DateTime<chrono::Utc>,
+    /// When the app exited or crashed (None while running, or if it has no
+    /// PID to watch)
+    pub ended_at: Option<chrono::DateTime<chrono::Utc>>,
+    /// Crash report (.ips) if the app crashed
+    pub crash_report: Option<PathBuf>,
 }
 
 /// Initialize storage directories
@@ -30,6 +92,10 @@ pub async fn init(config: &StorageConfig) -> Result<()> {
         .await
         .context("Failed to create logs directory")?;
 
+    tokio::fs::create_dir_all(&config.dependencies_dir)
+        .await
+        .context("Failed to create dependencies directory")?;
+
     Ok(())
 }
 
@@ -132,6 +198,94 @@ pub async fn cleanup_old_projects(config: &StorageConfig) -> Result<u32> {
     Ok(removed)
 }
 
+/// Delete a build's output directory in `logs_dir`
+pub async fn remove_build_output(config: &StorageConfig, build_id: Uuid) {
+    let dir = config.logs_dir.join(build_id.to_string());
+    match tokio::fs::remove_dir_all(&dir).await {
+        Ok(()) => debug!("Removed outputs of build {}", build_id),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+        Err(e) => error!("Failed to remove {:?}: {}", dir, e),
+    }
+}
+
+/// Delete the build output directories a previous run of the agent left in
+/// `logs_dir`. Builds are only tracked in memory, so nothing can reach them.
+pub async fn remove_orphaned_build_outputs(config: &StorageConfig) -> Result<u32> {
+    let mut removed = 0u32;
+
+    let mut entries = tokio::fs::read_dir(&config.logs_dir).await?;
+    while let Some(entry) = entries.next_entry().await? {
+        // Other files, like the agent's own logs, are left alone
+        let is_build = entry.file_name().to_str().is_some_and(|name| Uuid::parse_str(name).is_ok());
+        if !is_build || !entry.file_type().await?.is_dir() {
+            continue;
+        }
+        if let Err(e) = tokio::fs::remove_dir_all(entry.path()).await {
+            debug!("Failed to remove old build output {:?}: {}", entry.path(), e);
+        } else {
+            removed += 1;
+        }
+    }
+
+    if removed > 0 {
+        info!("Removed outputs of {} builds from a previous run", removed);
+    }
+
+    Ok(removed)
+}
+
+/// Evict dependency cache entries (pods, package checkouts, generated
+/// projects) that no build has used within `dependencies_max_age_days`.
+/// Builds touch an entry whenever they use it.
+pub async fn cleanup_dependencies(config: &StorageConfig) -> Result<u32> {
+    let cutoff = SystemTime::now() - Duration::from_secs(config.dependencies_max_age_days as u64 * 24 * 60 * 60);
+
+    let mut removed = 0u32;
+
+    // One directory per kind of dependency, each holding the entries
+    let mut kinds = tokio::fs::read_dir(&config.dependencies_dir).await?;
+    while let Some(kind) = kinds.next_entry().await? {
+        if !kind.file_type().await?.is_dir() {
+            continue;
+        }
+        let mut entries = tokio::fs::read_dir(kind.path()).await?;
+        while let Some(entry) = entries.next_entry().await? {
+            let modified = entry.metadata().await?.modified();
+            if modified.is_ok_and(|modified| modified < cutoff) {
+                if let Err(e) = tokio::fs::remove_dir_all(entry.path()).await {
+                    debug!("Failed to remove cached dependency {:?}: {}", entry.path(), e);
+                } else {
+                    removed += 1;
+                }
+            }
+        }
+    }
+
+    if removed > 0 {
+        info!("Evicted {} unused dependency cache entries", removed);
+    }
+
+    Ok(removed)
+}
+
+/// Periodically run `cleanup_dependencies`
+pub fn spawn_dependency_cleanup(config: StorageConfig) {
+    if config.dependencies_max_age_days == 0 {
+        info!("Dependency cache eviction disabled");
+        return;
+    }
+
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(DEPENDENCY_CLEANUP_INTERVAL);
+        loop {
+            interval.tick().await;
+            if let Err(e) = cleanup_dependencies(&config).await {
+                error!("Dependency cache cleanup failed: {}", e);
+            }
+        }
+    });
+}
+
 /// Get total size of projects directory
 pub async fn get_storage_size(config: &StorageConfig) -> Result<u64> {
     let path = config.projects_dir.clone();
@@ -0,0 +1,282 @@
+use anyhow::{anyhow, Context, Result};
+use xscape_common::{ArchiveRequest, LogMessage, PlistValue, SystemEventType};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use tokio::process::Command;
+use tokio::sync::{broadcast, mpsc};
+use tracing::{debug, error, info};
+
+use super::signing::{SigningAssets, SigningSession};
+use super::{
+    dependencies, find_xcode_project, generate, read_app_info, stream_xcodebuild, write_log_file, Generator,
+    LogOutput, XcodeContainer, XcodebuildOutput,
+};
+use crate::storage::BuildArtifacts;
+
+/// Archive a scheme for devices and export it as an .ipa.
+///
+/// Signing assets live in an ephemeral keychain for the duration of the
+/// archive; their passwords are masked in the streamed and stored log.
+pub async fn run_archive(
+    project_path: &str,
+    request: &ArchiveRequest,
+    export_options: Option<Vec<u8>>,
+    signing: &SigningAssets,
+    output_dir: &Path,
+    cache_dir: &Path,
+    log_sender: broadcast::Sender<String>,
+) -> Result<BuildArtifacts> {
+    tokio::fs::create_dir_all(output_dir)
+        .await
+        .with_context(|| format!("Failed to create {:?}", output_dir))?;
+    let log_path = output_dir.join("build.log");
+
+    let _ = log_sender.send(serde_json::to_string(&LogMessage::system_event(
+        SystemEventType::BuildStarted,
+        format!("Archiving scheme '{}'", request.scheme),
+    ))?);
+
+    let result = match SigningSession::install(signing).await {
+        Ok(session) => {
+            let mut secrets = session.secrets();
+            secrets.extend(signing.certificate_password.clone());
+
+            // Keep the output of both steps alongside the products
+            let (log_file_tx, log_file_rx) = mpsc::unbounded_channel();
+            let log_file_task = tokio::spawn(write_log_file(log_path.clone(), log_file_rx));
+
+            let log = LogOutput {
+                sender: &log_sender,
+                file: log_file_tx,
+                secrets: &secrets,
+            };
+            let result = archive_and_export(
+                project_path,
+                request,
+                export_options,
+                &session,
+                output_dir,
+                cache_dir,
+                log,
+            )
+            .await;
+
+            match log_file_task.await {
+                Ok(Err(e)) => error!("Failed to write build log: {}", e),
+                Err(e) => error!("Build log task failed: {}", e),
+                Ok(Ok(())) => {}
+            }
+            session.remove().await;
+            result
+        }
+        Err(e) => Err(e.context("Failed to set up code signing")),
+    };
+
+    match result {
+        Ok(mut artifacts) => {
+            artifacts.log_path = Some(log_path);
+            let ipa_name = artifacts
+                .ipa_path
+                .as_ref()
+                .and_then(|p| p.file_name())
+                .map(|n| n.to_string_lossy().into_owned())
+                .unwrap_or_default();
+            let _ = log_sender.send(serde_json::to_string(&LogMessage::system_event(
+                SystemEventType::BuildSucceeded,
+                format!("Exported {}", ipa_name),
+            ))?);
+            Ok(artifacts)
+        }
+        Err(e) => {
+            let _ = log_sender.send(serde_json::to_string(&LogMessage::system_event(
+                SystemEventType::BuildFailed,
+                format!("{:#}", e),
+            ))?);
+            Err(e)
+        }
+    }
+}
+
+async fn archive_and_export(
+    project_path: &str,
+    request: &ArchiveRequest,
+    export_options: Option<Vec<u8>>,
+    session: &SigningSession,
+    output_dir: &Path,
+    cache_dir: &Path,
+    log: LogOutput<'_>,
+) -> Result<BuildArtifacts> {
+    let project_dir = Path::new(project_path);
+    let archive_path = output_dir.join(format!("{}.xcarchive", request.scheme.replace('/', "_")));
+    let export_dir = output_dir.join("export");
+
+    // Generated project and dependencies; pod install may create the
+    // workspace to archive
+    if let Some(generator) = Generator::detect(project_dir) {
+        generate::generate_project(generator, project_dir, cache_dir, &log).await?;
+    }
+    dependencies::install_pods(project_dir, cache_dir, request.offline, &log).await?;
+    let container = find_xcode_project(project_dir, &request.project_file)?;
+    if let XcodeContainer::Package(_) = container {
+        return Err(anyhow!("Swift packages have no app to archive"));
+    }
+    let packages_dir = dependencies::resolve_packages(
+        &container,
+        project_dir,
+        &request.scheme,
+        false,
+        cache_dir,
+        request.offline,
+        &log,
+    )
+    .await?;
+
+    // Archive
+    info!("Archiving {} (scheme: {})", container.path().display(), request.scheme);
+    let _ = log.sender.send(serde_json::to_string(&LogMessage::build_progress(
+        "Archiving",
+        Some(request.scheme.clone()),
+        None,
+    ))?);
+
+    let mut cmd = Command::new("xcodebuild");
+    container.add_args(&mut cmd);
+    cmd.arg("-scheme")
+        .arg(&request.scheme)
+        .arg("-configuration")
+        .arg(request.configuration.to_string())
+        .arg("-destination")
+        .arg("generic/platform=iOS")
+        .arg("-archivePath")
+        .arg(&archive_path);
+    if let Some(ref packages_dir) = packages_dir {
+        cmd.arg("-clonedSourcePackagesDirPath").arg(packages_dir);
+        if request.offline {
+            cmd.arg("-disableAutomaticPackageResolution");
+        }
+    }
+    cmd.arg("archive");
+    if let Some(keychain) = session.keychain() {
+        cmd.arg(format!("OTHER_CODE_SIGN_FLAGS=--keychain {}", keychain.display()));
+    }
+    for arg in &request.extra_args {
+        cmd.arg(arg);
+    }
+    cmd.current_dir(project_dir);
+    debug!("Running xcodebuild: {:?}", cmd);
+
+    let XcodebuildOutput { status, mut warnings } =
+        stream_xcodebuild(cmd, log.sender, log.file.clone(), log.secrets).await?;
+    if !status.success() {
+        return Err(anyhow!("Archive failed with exit code: {:?}", status.code()));
+    }
+
+    // Export
+    let options = match export_options {
+        Some(bytes) => {
+            PlistValue::parse(&bytes).context("Invalid ExportOptions plist")?;
+            bytes
+        }
+        None => generate_export_options(request, session)?.to_xml().into_bytes(),
+    };
+    let options_path = output_dir.join("ExportOptions.plist");
+    tokio::fs::write(&options_path, options)
+        .await
+        .with_context(|| format!("Failed to write {:?}", options_path))?;
+
+    let mut cmd = Command::new("xcodebuild");
+    cmd.arg("-exportArchive")
+        .arg("-archivePath")
+        .arg(&archive_path)
+        .arg("-exportPath")
+        .arg(&export_dir)
+        .arg("-exportOptionsPlist")
+        .arg(&options_path)
+        .current_dir(project_dir);
+    debug!("Running xcodebuild: {:?}", cmd);
+
+    let output = stream_xcodebuild(cmd, log.sender, log.file, log.secrets).await?;
+    warnings.extend(output.warnings);
+    if !output.status.success() {
+        return Err(anyhow!("Export failed with exit code: {:?}", output.status.code()));
+    }
+
+    let ipa_path = find_with_extension(&export_dir, "ipa")
+        .into_iter()
+        .next()
+        .ok_or_else(|| anyhow!("Export produced no .ipa in {:?}", export_dir))?;
+
+    // The archive keeps the signed app and its dSYMs
+    let app_path = find_with_extension(&archive_path.join("Products/Applications"), "app")
+        .into_iter()
+        .next()
+        .ok_or_else(|| anyhow!("Archive contains no app"))?
+        .to_string_lossy()
+        .into_owned();
+    let app_info = read_app_info(&app_path).await?;
+    let dsym_paths = find_with_extension(&archive_path.join("dSYMs"), "dSYM");
+
+    info!("Exported {:?}", ipa_path);
+
+    Ok(BuildArtifacts {
+        app_path: Some(app_path),
+        bundle_id: Some(app_info.bundle_id.clone()),
+        app_info: Some(app_info),
+        warnings,
+        dsym_paths,
+        result_bundle_path: None,
+        log_path: None,
+        archive_path: Some(archive_path),
+        ipa_path: Some(ipa_path),
+    })
+}
+
+/// ExportOptions for requests that don't upload their own
+fn generate_export_options(request: &ArchiveRequest, session: &SigningSession) -> Result<PlistValue> {
+    let method = request
+        .export_method
+        .ok_or_else(|| anyhow!("An export method or an ExportOptions plist is required"))?;
+
+    let mut options = BTreeMap::new();
+    options.insert("method".to_string(), PlistValue::String(method.to_string()));
+
+    let team_id = request
+        .team_id
+        .clone()
+        .or_else(|| session.profiles.iter().find_map(|p| p.team_id.clone()));
+    if let Some(team_id) = team_id {
+        options.insert("teamID".to_string(), PlistValue::String(team_id));
+    }
+
+    // Uploaded profiles mean manual signing with exactly those profiles
+    let profiles: BTreeMap<String, PlistValue> = session
+        .profiles
+        .iter()
+        .filter_map(|p| {
+            p.bundle_id
+                .clone()
+                .map(|bundle_id| (bundle_id, PlistValue::String(p.uuid.clone())))
+        })
+        .collect();
+    if !profiles.is_empty() {
+        options.insert("signingStyle".to_string(), PlistValue::String("manual".to_string()));
+        options.insert("provisioningProfiles".to_string(), PlistValue::Dictionary(profiles));
+    }
+
+    Ok(PlistValue::Dictionary(options))
+}
+
+/// Entries of a directory with the given extension, sorted
+fn find_with_extension(dir: &Path, extension: &str) -> Vec<PathBuf> {
+    let mut paths: Vec<PathBuf> = std::fs::read_dir(dir)
+        .map(|entries| {
+            entries
+                .filter_map(|e| e.ok())
+                .map(|e| e.path())
+                .filter(|p| p.extension().is_some_and(|e| e == extension))
+                .collect()
+        })
+        .unwrap_or_default();
+    paths.sort();
+    paths
+}
@@ -0,0 +1,257 @@
+use anyhow::{anyhow, Context, Result};
+use xscape_common::LogMessage;
+use sha2::{Digest, Sha256};
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+use tokio::process::Command;
+use tracing::{debug, info, warn};
+use uuid::Uuid;
+use walkdir::WalkDir;
+
+use super::{stream_xcodebuild, LogOutput, XcodeContainer};
+
+/// `BuildProgress` phase of the dependency step
+const PHASE: &str = "Resolving dependencies";
+
+/// Restore Pods/ for a Podfile.lock: keep an install that already matches,
+/// copy a cached one, or run `pod install` and cache its result. Offline
+/// builds only use the cache. Returns whether the project uses CocoaPods.
+pub async fn install_pods(project_dir: &Path, cache_dir: &Path, offline: bool, log: &LogOutput<'_>) -> Result<bool> {
+    let lockfile = project_dir.join("Podfile.lock");
+    if !lockfile.is_file() {
+        return Ok(false);
+    }
+    progress(log, "CocoaPods", None);
+
+    // Pods/ has support files for the Podfile's targets, so the Podfile is
+    // part of the key
+    let pods_dir = project_dir.join("Pods");
+    let lock = std::fs::read(&lockfile).with_context(|| format!("Failed to read {:?}", lockfile))?;
+    let podfile = std::fs::read(project_dir.join("Podfile")).unwrap_or_default();
+    let hash = hash_hex(&[podfile.as_slice(), b"\0", lock.as_slice()].concat());
+    let cached = cache_dir.join("pods").join(&hash);
+
+    // CocoaPods copies Podfile.lock to Pods/Manifest.lock after installing
+    let installed = if std::fs::read(pods_dir.join("Manifest.lock")).is_ok_and(|manifest| manifest == lock) {
+        log_line(log, "Pods are up to date");
+        true
+    } else if cached.is_dir() {
+        log_line(log, &format!("Restoring Pods from the cache ({})", &hash[..12]));
+        touch(&cached);
+        if pods_dir.exists() {
+            tokio::fs::remove_dir_all(&pods_dir)
+                .await
+                .with_context(|| format!("Failed to remove {:?}", pods_dir))?;
+        }
+        copy_dir_blocking(cached.clone(), pods_dir.clone()).await?;
+        true
+    } else if offline {
+        return Err(anyhow!(
+            "Offline build, but no cached pods match the Podfile and Podfile.lock ({}); build once online first",
+            &hash[..12]
+        ));
+    } else {
+        false
+    };
+
+    // pod install also creates the workspace and integrates the project,
+    // which restored Pods/ lack when the workspace isn't committed. With
+    // Pods/ in place it only does that integration.
+    if installed && has_workspace(project_dir) {
+        progress(log, "CocoaPods", Some(100));
+        return Ok(true);
+    }
+
+    let mut cmd = Command::new("pod");
+    cmd.arg("install").current_dir(project_dir);
+    debug!("Running pod: {:?}", cmd);
+    let output = stream_xcodebuild(cmd, log.sender, log.file.clone(), log.secrets).await?;
+    if !output.status.success() {
+        return Err(anyhow!("pod install failed with exit code: {:?}", output.status.code()));
+    }
+
+    if !installed {
+        if let Err(e) = store(&pods_dir, &cached).await {
+            warn!("Failed to cache Pods for {}: {:#}", &hash[..12], e);
+        }
+    }
+    progress(log, "CocoaPods", Some(100));
+    Ok(true)
+}
+
+fn has_workspace(project_dir: &Path) -> bool {
+    std::fs::read_dir(project_dir).is_ok_and(|entries| {
+        entries
+            .filter_map(|e| e.ok())
+            .any(|e| e.path().extension().is_some_and(|ext| ext == "xcworkspace"))
+    })
+}
+
+/// Resolve the Swift packages pinned by the container's Package.resolved
+/// into a cache entry shared by every project with the same pins, and give
+/// the project its own copy of it. Returns the directory to build with, or
+/// nothing when no packages are pinned. Offline builds only use the cache.
+pub async fn resolve_packages(
+    container: &XcodeContainer,
+    project_dir: &Path,
+    scheme: &str,
+    host_only: bool,
+    cache_dir: &Path,
+    offline: bool,
+    log: &LogOutput<'_>,
+) -> Result<Option<PathBuf>> {
+    let resolved = container.package_resolved();
+    let Ok(pins) = std::fs::read(&resolved) else {
+        debug!("No Package.resolved at {:?}", resolved);
+        return Ok(None);
+    };
+    progress(log, "Swift packages", None);
+
+    // swift package resolve writes a SwiftPM repository cache, xcodebuild a
+    // checkouts directory, so the two never share an entry
+    let hash = hash_hex(&pins);
+    let key = format!("{}-{}", if host_only { "swiftpm" } else { "xcode" }, hash);
+    let cached = cache_dir.join("packages").join(&key);
+    if cached.is_dir() {
+        log_line(log, &format!("Using cached Swift packages ({})", &hash[..12]));
+        touch(&cached);
+    } else if offline {
+        return Err(anyhow!(
+            "Offline build, but no cached Swift packages match Package.resolved ({}); build once online first",
+            &hash[..12]
+        ));
+    } else {
+        resolve_into(&cached, container, project_dir, scheme, host_only, log).await?;
+        info!("Cached Swift packages for {}", &hash[..12]);
+    }
+
+    // Builds write to the checkout directory (workspace-state.json,
+    // artifacts), so they never get the shared entry itself
+    let local = project_dir.join(".xscape/packages").join(&key);
+    if !local.is_dir() {
+        store(&cached, &local).await?;
+    }
+    progress(log, "Swift packages", Some(100));
+    Ok(Some(local))
+}
+
+/// Run the resolver into a new cache entry
+async fn resolve_into(
+    cached: &Path,
+    container: &XcodeContainer,
+    project_dir: &Path,
+    scheme: &str,
+    host_only: bool,
+    log: &LogOutput<'_>,
+) -> Result<()> {
+    // Resolve into a scratch directory so a failed or concurrent resolution
+    // never leaves a half-filled cache entry
+    let scratch = scratch_path(cached);
+    tokio::fs::create_dir_all(&scratch)
+        .await
+        .with_context(|| format!("Failed to create {:?}", scratch))?;
+    let mut cmd = if host_only {
+        let mut cmd = Command::new("swift");
+        cmd.args(["package", "resolve", "--cache-path"]).arg(&scratch);
+        cmd
+    } else {
+        let mut cmd = Command::new("xcodebuild");
+        cmd.arg("-resolvePackageDependencies");
+        container.add_args(&mut cmd);
+        cmd.arg("-scheme")
+            .arg(scheme)
+            .arg("-clonedSourcePackagesDirPath")
+            .arg(&scratch);
+        cmd
+    };
+    cmd.current_dir(project_dir);
+    debug!("Resolving packages: {:?}", cmd);
+
+    let output = stream_xcodebuild(cmd, log.sender, log.file.clone(), log.secrets).await;
+    let failed = match output {
+        Ok(ref output) if output.status.success() => None,
+        Ok(ref output) => Some(anyhow!("Package resolution failed with exit code: {:?}", output.status.code())),
+        Err(e) => Some(e),
+    };
+    if let Some(e) = failed {
+        let _ = tokio::fs::remove_dir_all(&scratch).await;
+        return Err(e);
+    }
+
+    if let Err(e) = tokio::fs::rename(&scratch, cached).await {
+        // Another build with the same pins got there first
+        debug!("Keeping existing cache entry {:?}: {}", cached, e);
+        let _ = tokio::fs::remove_dir_all(&scratch).await;
+    }
+    Ok(())
+}
+
+/// Copy a directory to `entry` (a cache entry or a project's copy of one),
+/// through a scratch directory so it never shows up half-copied
+async fn store(source: &Path, entry: &Path) -> Result<()> {
+    let scratch = scratch_path(entry);
+    copy_dir_blocking(source.to_path_buf(), scratch.clone()).await?;
+    if tokio::fs::rename(&scratch, entry).await.is_err() {
+        let _ = tokio::fs::remove_dir_all(&scratch).await;
+    }
+    Ok(())
+}
+
+/// A unique sibling of a cache entry to fill before renaming it into place
+pub(super) fn scratch_path(entry: &Path) -> PathBuf {
+    let name = entry
+        .file_name()
+        .map(|n| n.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    entry.with_file_name(format!(".{}.{}", name, Uuid::new_v4()))
+}
+
+/// Mark a cache entry as used, so eviction keeps it
+pub(super) fn touch(entry: &Path) {
+    let touched = std::fs::File::open(entry).and_then(|dir| dir.set_modified(SystemTime::now()));
+    if let Err(e) = touched {
+        debug!("Failed to touch {:?}: {}", entry, e);
+    }
+}
+
+pub(super) async fn copy_dir_blocking(source: PathBuf, dest: PathBuf) -> Result<()> {
+    tokio::task::spawn_blocking(move || copy_dir(&source, &dest))
+        .await
+        .context("Copy task failed")?
+}
+
+/// Copy a directory tree, keeping symlinks (Pods/Headers is made of them)
+fn copy_dir(source: &Path, dest: &Path) -> Result<()> {
+    for entry in WalkDir::new(source) {
+        let entry = entry?;
+        let relative = entry.path().strip_prefix(source)?;
+        let target = dest.join(relative);
+        let file_type = entry.file_type();
+        if file_type.is_dir() {
+            std::fs::create_dir_all(&target).with_context(|| format!("Failed to create {:?}", target))?;
+        } else if file_type.is_symlink() {
+            let link = std::fs::read_link(entry.path())?;
+            std::os::unix::fs::symlink(&link, &target)
+                .with_context(|| format!("Failed to link {:?}", target))?;
+        } else {
+            std::fs::copy(entry.path(), &target).with_context(|| format!("Failed to copy {:?}", target))?;
+        }
+    }
+    Ok(())
+}
+
+fn hash_hex(bytes: &[u8]) -> String {
+    format!("{:x}", Sha256::digest(bytes))
+}
+
+fn progress(log: &LogOutput<'_>, manager: &str, percent: Option<u8>) {
+    let message = LogMessage::build_progress(PHASE, Some(manager.to_string()), percent);
+    let _ = log.sender.send(serde_json::to_string(&message).unwrap_or_default());
+}
+
+/// Report what the phase did like a line of tool output
+pub(super) fn log_line(log: &LogOutput<'_>, line: &str) {
+    let message = LogMessage::build_output(xscape_common::LogLevel::Info, line);
+    let _ = log.sender.send(serde_json::to_string(&message).unwrap_or_default());
+    let _ = log.file.send(line.to_string());
+}
@@ -0,0 +1,236 @@
+use anyhow::{anyhow, Context, Result};
+use xscape_common::LogMessage;
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+use tokio::process::Command;
+use tracing::{debug, info, warn};
+use walkdir::{DirEntry, WalkDir};
+
+use super::dependencies::{copy_dir_blocking, log_line, scratch_path, touch};
+use super::{stream_commands, LogOutput};
+
+/// `BuildProgress` phase of the generation step
+const PHASE: &str = "Generating project";
+
+/// Directories no generator reads or writes, skipped when hashing the project
+/// and looking for generated files
+const SKIPPED_DIRS: &[&str] = &[".git", ".build", "build", "DerivedData", "Pods"];
+
+/// Tools that write the Xcode project from a manifest, for repos that don't
+/// commit their .xcodeproj
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Generator {
+    /// XcodeGen, from project.yml
+    XcodeGen,
+    /// Tuist, from Project.swift or Workspace.swift
+    Tuist,
+}
+
+impl Generator {
+    /// The generator to run before using `project_dir`: the one whose
+    /// manifest it has, as long as it has no .xcodeproj or .xcworkspace yet
+    pub fn detect(project_dir: &Path) -> Option<Self> {
+        let has_project = std::fs::read_dir(project_dir).ok()?.filter_map(|e| e.ok()).any(|e| {
+            e.path()
+                .extension()
+                .is_some_and(|ext| ext == "xcodeproj" || ext == "xcworkspace")
+        });
+        if has_project {
+            return None;
+        }
+
+        if xcodegen_spec(project_dir).is_some() {
+            Some(Self::XcodeGen)
+        } else if ["Project.swift", "Workspace.swift"]
+            .iter()
+            .any(|f| project_dir.join(f).is_file())
+        {
+            Some(Self::Tuist)
+        } else {
+            None
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::XcodeGen => "XcodeGen",
+            Self::Tuist => "Tuist",
+        }
+    }
+
+    fn commands(self, project_dir: &Path) -> Vec<Command> {
+        let mut commands = Vec::new();
+        match self {
+            Self::XcodeGen => {
+                let mut cmd = Command::new("xcodegen");
+                cmd.arg("generate");
+                if let Some(spec) = xcodegen_spec(project_dir) {
+                    cmd.arg("--spec").arg(spec);
+                }
+                commands.push(cmd);
+            }
+            Self::Tuist => {
+                // External dependencies have to be fetched before generating
+                if project_dir.join("Tuist/Package.swift").is_file() {
+                    let mut cmd = Command::new("tuist");
+                    cmd.arg("install");
+                    commands.push(cmd);
+                }
+                let mut cmd = Command::new("tuist");
+                cmd.args(["generate", "--no-open"]);
+                commands.push(cmd);
+            }
+        }
+        for cmd in &mut commands {
+            cmd.current_dir(project_dir);
+        }
+        commands
+    }
+
+    /// Whether the generator reads a file's contents, rather than at most
+    /// listing it as a source
+    fn reads(self, relative: &Path) -> bool {
+        let extension = relative.extension().and_then(|e| e.to_str());
+        match self {
+            // Specs can include other specs
+            Self::XcodeGen => matches!(extension, Some("yml" | "yaml")),
+            Self::Tuist => {
+                relative.starts_with("Tuist")
+                    || relative.file_name().and_then(|n| n.to_str()).is_some_and(|name| {
+                        matches!(
+                            name,
+                            "Project.swift" | "Workspace.swift" | "Tuist.swift" | "Package.swift" | "Package.resolved"
+                        )
+                    })
+            }
+        }
+    }
+}
+
+/// Generate the Xcode project of a manifest-only project. The generated files
+/// are cached under `cache_dir` by a hash of the manifests and the project's
+/// file list (generators glob for sources), so an unchanged project gets them
+/// back from the cache instead of running the generator again.
+pub async fn generate_project(
+    generator: Generator,
+    project_dir: &Path,
+    cache_dir: &Path,
+    log: &LogOutput<'_>,
+) -> Result<()> {
+    let name = generator.name();
+    progress(log, name, None);
+
+    let dir = project_dir.to_path_buf();
+    let (hash, before) = tokio::task::spawn_blocking(move || snapshot(generator, &dir))
+        .await
+        .context("Snapshot task failed")??;
+    let cached = cache_dir.join("generated").join(&hash);
+    if cached.is_dir() {
+        log_line(log, &format!("Restoring the generated project from the cache ({})", &hash[..12]));
+        touch(&cached);
+        copy_dir_blocking(cached, project_dir.to_path_buf()).await?;
+        progress(log, name, Some(100));
+        return Ok(());
+    }
+
+    info!("Generating {} with {}", project_dir.display(), name);
+    let output = stream_commands(generator.commands(project_dir), log).await?;
+    if !output.status.success() {
+        return Err(anyhow!("{} failed with exit code: {:?}", name, output.status.code()));
+    }
+
+    let dir = project_dir.to_path_buf();
+    let entry = cached.clone();
+    let stored = tokio::task::spawn_blocking(move || store(&dir, &before, &entry))
+        .await
+        .context("Cache task failed")?;
+    match stored {
+        Ok(()) => info!("Cached the generated project for {}", &hash[..12]),
+        Err(e) => warn!("Failed to cache the generated project for {}: {:#}", &hash[..12], e),
+    }
+    progress(log, name, Some(100));
+    Ok(())
+}
+
+/// The manifest XcodeGen reads by default, or its .yaml spelling
+fn xcodegen_spec(project_dir: &Path) -> Option<PathBuf> {
+    ["project.yml", "project.yaml"]
+        .iter()
+        .map(|f| project_dir.join(f))
+        .find(|path| path.is_file())
+}
+
+/// Hash what the generator's output depends on, and record when each file was
+/// last modified to tell the generated files apart afterwards
+fn snapshot(generator: Generator, project_dir: &Path) -> Result<(String, HashMap<PathBuf, Option<SystemTime>>)> {
+    let mut hasher = Sha256::new();
+    hasher.update(generator.name());
+    let mut files = HashMap::new();
+    for entry in walk(project_dir) {
+        let relative = entry.path().strip_prefix(project_dir)?;
+        // Adding, removing or renaming any file can change the project
+        hasher.update(relative.to_string_lossy().as_bytes());
+        hasher.update([0]);
+        if generator.reads(relative) {
+            let contents =
+                std::fs::read(entry.path()).with_context(|| format!("Failed to read {:?}", entry.path()))?;
+            hasher.update(&contents);
+            hasher.update([0]);
+        }
+        files.insert(relative.to_path_buf(), entry.metadata()?.modified().ok());
+    }
+    Ok((format!("{:x}", hasher.finalize()), files))
+}
+
+/// Copy the files the generator created or changed into the cache under
+/// `entry`, through a scratch directory like the dependency cache
+fn store(project_dir: &Path, before: &HashMap<PathBuf, Option<SystemTime>>, entry: &Path) -> Result<()> {
+    let scratch = scratch_path(entry);
+    std::fs::create_dir_all(&scratch).with_context(|| format!("Failed to create {:?}", scratch))?;
+    let copied = (|| -> Result<()> {
+        for file in walk(project_dir) {
+            let relative = file.path().strip_prefix(project_dir)?;
+            let modified = file.metadata()?.modified().ok();
+            if before.get(relative) == Some(&modified) {
+                continue;
+            }
+            debug!("Generated {:?}", relative);
+            let target = scratch.join(relative);
+            if let Some(parent) = target.parent() {
+                std::fs::create_dir_all(parent).with_context(|| format!("Failed to create {:?}", parent))?;
+            }
+            if file.file_type().is_symlink() {
+                std::os::unix::fs::symlink(std::fs::read_link(file.path())?, &target)
+                    .with_context(|| format!("Failed to link {:?}", target))?;
+            } else {
+                std::fs::copy(file.path(), &target).with_context(|| format!("Failed to copy {:?}", target))?;
+            }
+        }
+        Ok(())
+    })();
+    if copied.is_err() || std::fs::rename(&scratch, entry).is_err() {
+        let _ = std::fs::remove_dir_all(&scratch);
+    }
+    copied
+}
+
+/// Files and symlinks in the project, in a stable order
+fn walk(project_dir: &Path) -> impl Iterator<Item = DirEntry> {
+    WalkDir::new(project_dir)
+        .sort_by_file_name()
+        .into_iter()
+        .filter_entry(|e| {
+            !(e.depth() > 0
+                && e.file_type().is_dir()
+                && e.file_name().to_str().is_some_and(|n| SKIPPED_DIRS.contains(&n)))
+        })
+        .filter_map(|e| e.ok())
+        .filter(|e| !e.file_type().is_dir())
+}
+
+fn progress(log: &LogOutput<'_>, generator: &str, percent: Option<u8>) {
+    let message = LogMessage::build_progress(PHASE, Some(generator.to_string()), percent);
+    let _ = log.sender.send(serde_json::to_string(&message).unwrap_or_default());
+}
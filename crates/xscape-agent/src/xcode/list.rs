@@ -0,0 +1,226 @@
+use anyhow::{anyhow, Context, Result};
+use serde::Deserialize;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use tokio::process::Command;
+use tokio::sync::{broadcast, mpsc};
+use tracing::{debug, warn};
+
+use super::{find_xcode_project, generate, Generator, LogOutput, PackageManifest, XcodeContainer};
+
+/// `xcodebuild -list` resolves packages first, which can take a while
+const LIST_TIMEOUT: Duration = Duration::from_secs(300);
+
+/// Schemes, targets and configurations of a project, workspace or package
+pub struct ProjectListing {
+    /// The listed .xcodeproj, .xcworkspace or Package.swift, relative to the
+    /// project directory
+    pub project_file: String,
+    pub is_workspace: bool,
+    pub is_package: bool,
+    pub schemes: Vec<String>,
+    pub targets: Vec<String>,
+    pub configurations: Vec<String>,
+}
+
+/// Output of `xcodebuild -list -json`
+#[derive(Debug, Deserialize)]
+struct XcodebuildList {
+    project: Option<ListedContainer>,
+    workspace: Option<ListedContainer>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct ListedContainer {
+    #[serde(default)]
+    schemes: Vec<String>,
+    #[serde(default)]
+    targets: Vec<String>,
+    #[serde(default)]
+    configurations: Vec<String>,
+}
+
+/// List a project with `xcodebuild -list`. Workspaces only report schemes, so
+/// their member projects are listed too for targets and configurations;
+/// packages take their targets from the manifest. A manifest-only project is
+/// generated first (through the cache in `cache_dir`) and the result listed.
+pub async fn list_project(
+    project_path: &str,
+    project_file: &Option<String>,
+    cache_dir: &Path,
+) -> Result<ProjectListing> {
+    let project_dir = Path::new(project_path);
+    if let Some(generator) = Generator::detect(project_dir) {
+        // Nobody follows a listing's output
+        let (sender, _) = broadcast::channel(1);
+        let (file, _) = mpsc::unbounded_channel();
+        let log = LogOutput {
+            sender: &sender,
+            file,
+            secrets: &[],
+        };
+        generate::generate_project(generator, project_dir, cache_dir, &log).await?;
+    }
+    let container = find_xcode_project(project_dir, project_file)?;
+    let file = container.path().to_path_buf();
+    let relative = file
+        .strip_prefix(project_dir)
+        .unwrap_or(&file)
+        .to_string_lossy()
+        .into_owned();
+
+    match container {
+        XcodeContainer::Project(_) => {
+            let listed = xcodebuild_list(Some("-project"), &file, project_dir)
+                .await?
+                .project
+                .unwrap_or_default();
+            return Ok(ProjectListing {
+                project_file: relative,
+                is_workspace: false,
+                is_package: false,
+                schemes: listed.schemes,
+                targets: listed.targets,
+                configurations: listed.configurations,
+            });
+        }
+        XcodeContainer::Package(_) => {
+            // xcodebuild lists a package like a workspace of its schemes
+            let listed = xcodebuild_list(None, &file, project_dir)
+                .await?
+                .workspace
+                .unwrap_or_default();
+            let manifest = PackageManifest::load(project_dir).await?;
+            return Ok(ProjectListing {
+                project_file: relative,
+                is_workspace: false,
+                is_package: true,
+                schemes: listed.schemes,
+                targets: manifest.targets.into_iter().map(|t| t.name).collect(),
+                configurations: vec!["Debug".to_string(), "Release".to_string()],
+            });
+        }
+        XcodeContainer::Workspace(_) => {}
+    }
+
+    let workspace = xcodebuild_list(Some("-workspace"), &file, project_dir)
+        .await?
+        .workspace
+        .unwrap_or_default();
+    let mut listing = ProjectListing {
+        project_file: relative,
+        is_workspace: true,
+        is_package: false,
+        schemes: workspace.schemes,
+        targets: Vec::new(),
+        configurations: Vec::new(),
+    };
+
+    for member in workspace_projects(&file) {
+        debug!("Listing workspace member {:?}", member);
+        match xcodebuild_list(Some("-project"), &member, project_dir).await {
+            Ok(list) => {
+                let project = list.project.unwrap_or_default();
+                extend_unique(&mut listing.targets, project.targets);
+                extend_unique(&mut listing.configurations, project.configurations);
+            }
+            Err(e) => warn!("Failed to list {:?}: {:#}", member, e),
+        }
+    }
+
+    Ok(listing)
+}
+
+/// Run `xcodebuild -list` on `path`, passed with `flag`. Without a flag
+/// xcodebuild lists the package in `project_dir`.
+async fn xcodebuild_list(flag: Option<&str>, path: &Path, project_dir: &Path) -> Result<XcodebuildList> {
+    let mut cmd = Command::new("xcodebuild");
+    cmd.arg("-list").arg("-json");
+    if let Some(flag) = flag {
+        cmd.arg(flag).arg(path);
+    }
+    cmd.current_dir(project_dir).kill_on_drop(true);
+    debug!("Running xcodebuild: {:?}", cmd);
+
+    let output = tokio::time::timeout(LIST_TIMEOUT, cmd.output())
+        .await
+        .map_err(|_| anyhow!("xcodebuild -list timed out after {}s", LIST_TIMEOUT.as_secs()))?
+        .context("Failed to run xcodebuild -list")?;
+
+    if !output.status.success() {
+        return Err(anyhow!(
+            "xcodebuild -list failed: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+
+    // Package resolution messages can precede the JSON
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let json = stdout
+        .find('{')
+        .map(|start| &stdout[start..])
+        .ok_or_else(|| anyhow!("xcodebuild -list printed no JSON"))?;
+    serde_json::from_str(json).context("Failed to parse xcodebuild -list output")
+}
+
+/// Projects referenced by a workspace's contents.xcworkspacedata
+fn workspace_projects(workspace: &Path) -> Vec<PathBuf> {
+    let data_path = workspace.join("contents.xcworkspacedata");
+    let Ok(data) = std::fs::read_to_string(&data_path) else {
+        return Vec::new();
+    };
+    let doc = match roxmltree::Document::parse(&data) {
+        Ok(doc) => doc,
+        Err(e) => {
+            warn!("Failed to parse {:?}: {}", data_path, e);
+            return Vec::new();
+        }
+    };
+
+    let container = workspace.parent().unwrap_or(workspace);
+    let mut projects = Vec::new();
+    collect_file_refs(doc.root_element(), container, container, &mut projects);
+    projects.retain(|p| p.exists());
+    projects
+}
+
+fn collect_file_refs(node: roxmltree::Node, group: &Path, container: &Path, out: &mut Vec<PathBuf>) {
+    for child in node.children().filter(|n| n.is_element()) {
+        let location = child
+            .attribute("location")
+            .and_then(|l| resolve_location(l, group, container));
+        match child.tag_name().name() {
+            "Group" => {
+                let dir = location.unwrap_or_else(|| group.to_path_buf());
+                collect_file_refs(child, &dir, container, out);
+            }
+            "FileRef" => {
+                if let Some(path) = location.filter(|p| p.extension().is_some_and(|e| e == "xcodeproj")) {
+                    if !out.contains(&path) {
+                        out.push(path);
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Resolve a workspace location ("group:App/App.xcodeproj", "container:...")
+fn resolve_location(location: &str, group: &Path, container: &Path) -> Option<PathBuf> {
+    let (kind, path) = location.split_once(':')?;
+    match kind {
+        "group" => Some(group.join(path)),
+        "container" => Some(container.join(path)),
+        "absolute" => Some(PathBuf::from(path)),
+        _ => None,
+    }
+}
+
+fn extend_unique(list: &mut Vec<String>, items: Vec<String>) {
+    for item in items {
+        if !list.contains(&item) {
+            list.push(item);
+        }
+    }
+}
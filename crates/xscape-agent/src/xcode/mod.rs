@@ -1,15 +1,31 @@
 use anyhow::{anyhow, Context, Result};
-use xscape_common::{BuildConfiguration, BuildRequest, LogLevel, LogMessage};
-use std::path::Path;
+use xscape_common::{
+    validate_build_setting, AppExtensionInfo, AppInfo, BuildConfiguration, BuildRequest, LogLevel,
+    LogMessage, Platform, PlistValue, XcconfigContents,
+};
+use std::path::{Path, PathBuf};
 use std::process::Stdio;
-use tokio::io::{AsyncBufReadExt, BufReader};
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
 use tokio::process::Command;
-use tokio::sync::broadcast;
-use tracing::{debug, error, info};
+use tokio::sync::{broadcast, mpsc};
+use tracing::{debug, error, info, warn};
 use walkdir::WalkDir;
 
 use crate::storage::BuildArtifacts;
 
+mod archive;
+mod dependencies;
+mod generate;
+mod list;
+mod package;
+mod signing;
+
+pub use archive::run_archive;
+use generate::Generator;
+pub use list::list_project;
+use package::PackageManifest;
+pub use signing::{remove_stale_sessions, ProvisioningProfile, SigningAssets};
+
 /// Information about Xcode installation
 pub struct XcodeInfo {
     pub version: String,
@@ -53,76 +69,305 @@ pub async fn get_xcode_info() -> Result<XcodeInfo> {
     Ok(XcodeInfo { version, path })
 }
 
-/// Run xcodebuild for a project
+/// Build (or test) a project with xcodebuild, or a host-only Swift package
+/// with `swift build`/`swift test`, after generating a manifest-only project
+/// and resolving its dependencies through the shared cache in `cache_dir`.
+/// The result bundle and full log are written to `output_dir`.
 pub async fn run_build(
     project_path: &str,
     request: &BuildRequest,
+    output_dir: &Path,
+    cache_dir: &Path,
     log_sender: broadcast::Sender<String>,
 ) -> Result<BuildArtifacts> {
     let project_dir = Path::new(project_path);
+    tokio::fs::create_dir_all(output_dir)
+        .await
+        .with_context(|| format!("Failed to create {:?}", output_dir))?;
+    let result_bundle_path = output_dir.join(format!("{}.xcresult", request.scheme.replace('/', "_")));
+    let log_path = output_dir.join("build.log");
+    let action = if request.test { "Testing" } else { "Building" };
+
+    // Find .xcodeproj, .xcworkspace or Package.swift, unless XcodeGen or
+    // Tuist has to write the project first
+    let generator = Generator::detect(project_dir);
+    let container = match generator {
+        Some(_) => None,
+        None => Some(find_xcode_project(project_dir, &request.project_file)?),
+    };
+    let manifest = match container {
+        Some(XcodeContainer::Package(_)) => Some(PackageManifest::load(project_dir).await?),
+        _ => None,
+    };
+    let host_only = manifest.as_ref().is_some_and(|m| m.is_host_only());
 
-    // Find .xcodeproj or .xcworkspace
-    let (project_file, is_workspace) = find_xcode_project(project_dir, &request.project_file)?;
+    // Send build started event
+    let description = match manifest {
+        Some(ref manifest) if host_only => format!("{} package '{}' for this Mac", action, manifest.name),
+        _ => format!(
+            "{} scheme '{}' for {}",
+            action,
+            request.scheme,
+            request.destination.describe()
+        ),
+    };
+    let _ = log_sender.send(serde_json::to_string(&LogMessage::system_event(
+        xscape_common::SystemEventType::BuildStarted,
+        description,
+    ))?);
 
-    info!("Building {} (workspace: {})", project_file, is_workspace);
+    // Keep the full output alongside the build products
+    let (log_file_tx, log_file_rx) = mpsc::unbounded_channel();
+    let log_file_task = tokio::spawn(write_log_file(log_path.clone(), log_file_rx));
+    let log = LogOutput {
+        sender: &log_sender,
+        file: log_file_tx,
+        secrets: &[],
+    };
+
+    let output = async {
+        if let Some(generator) = generator {
+            generate::generate_project(generator, project_dir, cache_dir, &log).await?;
+        }
+        // pod install may create the workspace to build
+        let pods = dependencies::install_pods(project_dir, cache_dir, request.offline, &log).await?;
+        let container = match container {
+            Some(container) if !pods || request.project_file.is_some() => container,
+            _ => find_xcode_project(project_dir, &request.project_file)?,
+        };
+        let packages_dir = dependencies::resolve_packages(
+            &container,
+            project_dir,
+            &request.scheme,
+            host_only,
+            cache_dir,
+            request.offline,
+            &log,
+        )
+        .await?;
+
+        let commands = match manifest {
+            Some(ref manifest) if host_only => {
+                info!("{} host-only package {} with swift", action, manifest.name);
+                package::swift_commands(project_dir, manifest, request, packages_dir.as_deref())?
+            }
+            _ => {
+                info!("{} {}", action, container.path().display());
+                vec![xcodebuild_command(
+                    &container,
+                    project_dir,
+                    request,
+                    &result_bundle_path,
+                    packages_dir.as_deref(),
+                )]
+            }
+        };
+        let _ = log_sender.send(serde_json::to_string(&LogMessage::build_progress(
+            action,
+            Some(request.scheme.clone()),
+            None,
+        ))?);
+        stream_commands(commands, &log).await
+    }
+    .await;
+    drop(log);
+    match log_file_task.await {
+        Ok(Err(e)) => error!("Failed to write build log: {}", e),
+        Err(e) => error!("Build log task failed: {}", e),
+        Ok(Ok(())) => {}
+    }
+    let XcodebuildOutput { status, warnings } = match output {
+        Ok(output) => output,
+        Err(e) => {
+            let _ = log_sender.send(serde_json::to_string(&LogMessage::system_event(
+                xscape_common::SystemEventType::BuildFailed,
+                format!("{:#}", e),
+            ))?);
+            return Err(e);
+        }
+    };
 
-    // Build xcodebuild command
-    let mut cmd = Command::new("xcodebuild");
+    let outcome = if request.test { "Tests" } else { "Build" };
+    if !status.success() {
+        let _ = log_sender.send(serde_json::to_string(&LogMessage::system_event(
+            xscape_common::SystemEventType::BuildFailed,
+            format!("{} failed with exit code: {:?}", outcome, status.code()),
+        ))?);
+        return Err(anyhow!("{} failed with exit code: {:?}", outcome, status.code()));
+    }
 
-    if is_workspace {
-        cmd.arg("-workspace").arg(&project_file);
-    } else {
-        cmd.arg("-project").arg(&project_file);
+    let result_bundle_path = result_bundle_path.exists().then_some(result_bundle_path);
+
+    // Packages build libraries and tests, not an app
+    if let Some(manifest) = manifest {
+        let _ = log_sender.send(serde_json::to_string(&LogMessage::system_event(
+            xscape_common::SystemEventType::BuildSucceeded,
+            format!("{} succeeded: {}", outcome, manifest.name),
+        ))?);
+        return Ok(BuildArtifacts {
+            app_path: None,
+            bundle_id: None,
+            app_info: None,
+            warnings,
+            dsym_paths: Vec::new(),
+            result_bundle_path,
+            log_path: Some(log_path),
+            archive_path: None,
+            ipa_path: None,
+        });
     }
 
+    // Find built app and keep this build's products, which the next build of
+    // the scheme replaces in DerivedData
+    let built_app = find_built_app(
+        project_dir,
+        &request.scheme,
+        &request.configuration,
+        request.destination.platform,
+    )?;
+    let app_path = keep_app(&built_app, output_dir).await?;
+    let app_info = read_app_info(&app_path).await?;
+    let dsym_paths = keep_dsyms(&built_app, output_dir).await;
+    if !dsym_paths.is_empty() {
+        debug!("Kept dSYMs: {:?}", dsym_paths);
+    }
+
+    let _ = log_sender.send(serde_json::to_string(&LogMessage::system_event(
+        xscape_common::SystemEventType::BuildSucceeded,
+        format!("Build succeeded: {}", app_path),
+    ))?);
+
+    Ok(BuildArtifacts {
+        app_path: Some(app_path),
+        bundle_id: Some(app_info.bundle_id.clone()),
+        app_info: Some(app_info),
+        warnings,
+        dsym_paths,
+        result_bundle_path,
+        log_path: Some(log_path),
+        archive_path: None,
+        ipa_path: None,
+    })
+}
+
+/// The xcodebuild invocation for a build or test request, using the
+/// dependency phase's package checkouts if there are any
+fn xcodebuild_command(
+    container: &XcodeContainer,
+    project_dir: &Path,
+    request: &BuildRequest,
+    result_bundle_path: &Path,
+    packages_dir: Option<&Path>,
+) -> Command {
+    let mut cmd = Command::new("xcodebuild");
+    container.add_args(&mut cmd);
+
     cmd.arg("-scheme")
         .arg(&request.scheme)
         .arg("-configuration")
         .arg(request.configuration.to_string())
         .arg("-sdk")
-        .arg("iphonesimulator")
+        .arg(request.destination.platform.sdk())
         .arg("-destination")
-        .arg(request.destination.to_xcodebuild_arg());
+        .arg(request.destination.to_xcodebuild_arg())
+        .arg("-resultBundlePath")
+        .arg(result_bundle_path);
+    if let Some(packages_dir) = packages_dir {
+        cmd.arg("-clonedSourcePackagesDirPath").arg(packages_dir);
+        // The checkouts match Package.resolved; never fetch in offline builds
+        if request.offline {
+            cmd.arg("-disableAutomaticPackageResolution");
+        }
+    }
+
+    // Overrides come after the project's own settings, sorted for stable logs
+    if let Some(ref xcconfig) = request.xcconfig {
+        cmd.arg("-xcconfig").arg(project_dir.join(xcconfig));
+    }
+    let mut build_settings: Vec<_> = request.build_settings.iter().collect();
+    build_settings.sort();
+    for (name, value) in build_settings {
+        cmd.arg(format!("{}={}", name, value));
+    }
 
     // Add clean if requested
     if request.clean {
         cmd.arg("clean");
     }
-    cmd.arg("build");
+    // Testing builds the scheme first
+    cmd.arg(if request.test { "test" } else { "build" });
 
     // Add extra args
     for arg in &request.extra_args {
         cmd.arg(arg);
     }
 
-    // Set working directory
+    // Set working directory; xcodebuild finds a Package.swift there
     cmd.current_dir(project_dir);
+    cmd
+}
+
+/// Exit status and warnings of an xcodebuild run
+struct XcodebuildOutput {
+    status: std::process::ExitStatus,
+    warnings: Vec<String>,
+}
+
+/// Destinations of xcodebuild output, and what to mask in it
+struct LogOutput<'a> {
+    sender: &'a broadcast::Sender<String>,
+    file: mpsc::UnboundedSender<String>,
+    secrets: &'a [String],
+}
+
+/// Run commands one after another, stopping at the first that fails
+async fn stream_commands(commands: Vec<Command>, log: &LogOutput<'_>) -> Result<XcodebuildOutput> {
+    let mut warnings = Vec::new();
+    let mut status = None;
+    for cmd in commands {
+        debug!("Running {:?}", cmd);
+        let output = stream_xcodebuild(cmd, log.sender, log.file.clone(), log.secrets).await?;
+        warnings.extend(output.warnings);
+        let success = output.status.success();
+        status = Some(output.status);
+        if !success {
+            break;
+        }
+    }
+    let status = status.ok_or_else(|| anyhow!("Nothing to run"))?;
+    Ok(XcodebuildOutput { status, warnings })
+}
 
+/// Run xcodebuild (or swift), streaming its output to the log channel and the log file.
+/// Every occurrence of a `secrets` entry is masked before a line leaves the agent.
+async fn stream_xcodebuild(
+    mut cmd: Command,
+    log_sender: &broadcast::Sender<String>,
+    log_file: mpsc::UnboundedSender<String>,
+    secrets: &[String],
+) -> Result<XcodebuildOutput> {
     // Capture output
     cmd.stdout(Stdio::piped());
     cmd.stderr(Stdio::piped());
 
-    debug!("Running xcodebuild: {:?}", cmd);
-
-    // Send build started event
-    let _ = log_sender.send(serde_json::to_string(&LogMessage::system_event(
-        xscape_common::SystemEventType::BuildStarted,
-        format!("Building scheme '{}' for {}", request.scheme, request.destination.device_name),
-    ))?);
-
-    let mut child = cmd.spawn().context("Failed to spawn xcodebuild")?;
+    let program = cmd.as_std().get_program().to_string_lossy().into_owned();
+    let mut child = cmd.spawn().with_context(|| format!("Failed to spawn {}", program))?;
 
     let stdout = child.stdout.take().unwrap();
     let stderr = child.stderr.take().unwrap();
 
     // Stream stdout
     let log_sender_clone = log_sender.clone();
+    let log_file_clone = log_file.clone();
+    let secrets_clone = secrets.to_vec();
     let stdout_task = tokio::spawn(async move {
         let reader = BufReader::new(stdout);
         let mut lines = reader.lines();
         let mut warnings = Vec::new();
 
         while let Ok(Some(line)) = lines.next_line().await {
+            let line = redact(line, &secrets_clone);
+
             // Parse xcodebuild output
             let (level, message) = parse_xcodebuild_line(&line);
 
@@ -132,6 +377,7 @@ pub async fn run_build(
 
             let log_msg = LogMessage::build_output(level, &line);
             let _ = log_sender_clone.send(serde_json::to_string(&log_msg).unwrap_or_default());
+            let _ = log_file_clone.send(line);
         }
 
         warnings
@@ -139,54 +385,103 @@ pub async fn run_build(
 
     // Stream stderr
     let log_sender_clone = log_sender.clone();
+    let secrets_clone = secrets.to_vec();
     let stderr_task = tokio::spawn(async move {
         let reader = BufReader::new(stderr);
         let mut lines = reader.lines();
 
         while let Ok(Some(line)) = lines.next_line().await {
+            let line = redact(line, &secrets_clone);
             let log_msg = LogMessage::build_output(LogLevel::Error, &line);
             let _ = log_sender_clone.send(serde_json::to_string(&log_msg).unwrap_or_default());
+            let _ = log_file.send(line);
         }
     });
 
     // Wait for build to complete
-    let status = child.wait().await.context("Failed to wait for xcodebuild")?;
+    let status = child.wait().await.with_context(|| format!("Failed to wait for {}", program))?;
     let warnings = stdout_task.await.unwrap_or_default();
     let _ = stderr_task.await;
 
-    if !status.success() {
-        let _ = log_sender.send(serde_json::to_string(&LogMessage::system_event(
-            xscape_common::SystemEventType::BuildFailed,
-            format!("Build failed with exit code: {:?}", status.code()),
-        ))?);
-        return Err(anyhow!("Build failed with exit code: {:?}", status.code()));
+    Ok(XcodebuildOutput { status, warnings })
+}
+
+/// Mask secrets (e.g. certificate passwords) in an output line
+fn redact(line: String, secrets: &[String]) -> String {
+    secrets
+        .iter()
+        .filter(|secret| !secret.is_empty())
+        .fold(line, |line, secret| line.replace(secret.as_str(), "********"))
+}
+
+/// Write xcodebuild output lines to a file
+async fn write_log_file(path: PathBuf, mut lines: mpsc::UnboundedReceiver<String>) -> Result<()> {
+    let file = tokio::fs::File::create(&path)
+        .await
+        .with_context(|| format!("Failed to create {:?}", path))?;
+    let mut writer = tokio::io::BufWriter::new(file);
+    while let Some(line) = lines.recv().await {
+        writer.write_all(line.as_bytes()).await?;
+        writer.write_all(b"\n").await?;
     }
+    writer.flush().await?;
+    Ok(())
+}
 
-    // Find built app
-    let app_path = find_built_app(project_dir, &request.scheme, &request.configuration)?;
-    let bundle_id = get_bundle_id(&app_path)?;
+/// What xcodebuild builds in a project directory
+enum XcodeContainer {
+    Workspace(PathBuf),
+    Project(PathBuf),
+    /// A Swift package without an Xcode project
+    Package(PathBuf),
+}
 
-    let _ = log_sender.send(serde_json::to_string(&LogMessage::system_event(
-        xscape_common::SystemEventType::BuildSucceeded,
-        format!("Build succeeded: {}", app_path),
-    ))?);
+impl XcodeContainer {
+    fn path(&self) -> &Path {
+        match self {
+            Self::Workspace(path) | Self::Project(path) | Self::Package(path) => path,
+        }
+    }
 
-    Ok(BuildArtifacts {
-        app_path,
-        bundle_id: Some(bundle_id),
-        warnings,
-    })
+    /// Where Xcode pins the container's Swift packages
+    fn package_resolved(&self) -> PathBuf {
+        match self {
+            Self::Workspace(path) => path.join("xcshareddata/swiftpm/Package.resolved"),
+            Self::Project(path) => path.join("project.xcworkspace/xcshareddata/swiftpm/Package.resolved"),
+            Self::Package(path) => path.with_file_name("Package.resolved"),
+        }
+    }
+
+    /// Point an xcodebuild command at the container. Packages need no
+    /// argument: xcodebuild uses the Package.swift in its working directory.
+    fn add_args(&self, cmd: &mut Command) {
+        match self {
+            Self::Workspace(path) => {
+                cmd.arg("-workspace").arg(path);
+            }
+            Self::Project(path) => {
+                cmd.arg("-project").arg(path);
+            }
+            Self::Package(_) => {}
+        }
+    }
 }
 
-/// Find .xcodeproj or .xcworkspace in project directory
-fn find_xcode_project(project_dir: &Path, specified: &Option<String>) -> Result<(String, bool)> {
+/// Find the .xcworkspace, .xcodeproj or Package.swift in a project directory,
+/// in that order of preference
+fn find_xcode_project(project_dir: &Path, specified: &Option<String>) -> Result<XcodeContainer> {
     if let Some(file) = specified {
         let path = project_dir.join(file);
-        let is_workspace = file.ends_with(".xcworkspace");
-        if path.exists() {
-            return Ok((path.to_string_lossy().to_string(), is_workspace));
+        if !path.exists() {
+            return Err(anyhow!("Specified project file not found: {}", file));
         }
-        return Err(anyhow!("Specified project file not found: {}", file));
+        return Ok(if file.ends_with(".xcworkspace") {
+            XcodeContainer::Workspace(path)
+        } else if path.file_name().is_some_and(|n| n == "Package.swift") {
+            XcodeContainer::Package(path)
+        } else {
+            XcodeContainer::Project(path)
+        });
     }
 
     // Look for workspace first (preferred)
@@ -194,7 +489,7 @@ fn find_xcode_project(project_dir: &Path, specified: &Option<String>) -> Result<
         let entry = entry?;
         let path = entry.path();
         if path.extension().map_or(false, |e| e == "xcworkspace") {
-            return Ok((path.to_string_lossy().to_string(), true));
+            return Ok(XcodeContainer::Workspace(path));
         }
     }
 
@@ -203,11 +498,17 @@ fn find_xcode_project(project_dir: &Path, specified: &Option<String>) -> Result<
         let entry = entry?;
         let path = entry.path();
         if path.extension().map_or(false, |e| e == "xcodeproj") {
-            return Ok((path.to_string_lossy().to_string(), false));
+            return Ok(XcodeContainer::Project(path));
         }
     }
 
-    Err(anyhow!("No .xcodeproj or .xcworkspace found in project directory"))
+    // Then a Swift package
+    let manifest = project_dir.join("Package.swift");
+    if manifest.is_file() {
+        return Ok(XcodeContainer::Package(manifest));
+    }
+
+    Err(anyhow!("No .xcodeproj, .xcworkspace or Package.swift found in project directory"))
 }
 
 /// Parse xcodebuild output line to determine log level
@@ -225,8 +526,58 @@ fn parse_xcodebuild_line(line: &str) -> (LogLevel, String) {
     }
 }
 
+/// Check that an .xcconfig (and everything it includes) lies inside the
+/// project and doesn't override agent-controlled build settings
+pub fn check_xcconfig(project_dir: &Path, xcconfig: &str) -> Result<()> {
+    let project_dir = project_dir
+        .canonicalize()
+        .with_context(|| format!("Failed to resolve {:?}", project_dir))?;
+    let path = project_dir.join(xcconfig);
+    check_xcconfig_file(&project_dir, &path, 0)
+}
+
+fn check_xcconfig_file(project_dir: &Path, path: &Path, depth: usize) -> Result<()> {
+    const MAX_INCLUDE_DEPTH: usize = 16;
+    if depth > MAX_INCLUDE_DEPTH {
+        return Err(anyhow!("xcconfig includes nest deeper than {}", MAX_INCLUDE_DEPTH));
+    }
+
+    let path = path
+        .canonicalize()
+        .with_context(|| format!("xcconfig {:?} not found", path))?;
+    if !path.starts_with(project_dir) {
+        return Err(anyhow!("xcconfig {:?} is outside the project", path));
+    }
+    let content = std::fs::read_to_string(&path)
+        .with_context(|| format!("Failed to read {:?}", path))?;
+
+    let contents = XcconfigContents::parse(&content);
+    for name in &contents.settings {
+        validate_build_setting(name).map_err(|e| anyhow!("{:?}: {}", path, e))?;
+    }
+
+    let dir = path.parent().unwrap_or(project_dir);
+    for (include, required) in &contents.includes {
+        // <DEVELOPER_DIR>/... includes point into Xcode itself
+        if include.starts_with('<') {
+            continue;
+        }
+        let include_path = dir.join(include);
+        if !required && !include_path.exists() {
+            continue;
+        }
+        check_xcconfig_file(project_dir, &include_path, depth + 1)?;
+    }
+    Ok(())
+}
+
 /// Find the built .app in DerivedData
-fn find_built_app(project_dir: &Path, scheme: &str, config: &BuildConfiguration) -> Result<String> {
+fn find_built_app(
+    project_dir: &Path,
+    scheme: &str,
+    config: &BuildConfiguration,
+    platform: Platform,
+) -> Result<String> {
     // Common DerivedData locations
     let derived_data_paths = [
         project_dir.join("DerivedData"),
@@ -236,7 +587,8 @@ fn find_built_app(project_dir: &Path, scheme: &str, config: &BuildConfiguration)
             .join("Library/Developer/Xcode/DerivedData"),
     ];
 
-    let config_str = config.to_string();
+    // e.g. "Debug-watchsimulator"; companion apps build into a sibling directory
+    let products_dir = format!("{}-{}", config, platform.products_suffix());
 
     for dd_path in &derived_data_paths {
         if !dd_path.exists() {
@@ -251,9 +603,10 @@ fn find_built_app(project_dir: &Path, scheme: &str, config: &BuildConfiguration)
         {
             let path = entry.path();
             if path.extension().map_or(false, |e| e == "app") {
-                let path_str = path.to_string_lossy();
-                // Check if it's in the right build products directory
-                if path_str.contains(&config_str) && path_str.contains("iphonesimulator") {
+                // Check if it's in the right build products directory, and not
+                // an app embedded in another one (e.g., a watch app)
+                let in_products_dir = path.parent().is_some_and(|p| p.ends_with(&products_dir));
+                if in_products_dir {
                     return Ok(path.to_string_lossy().to_string());
                 }
             }
@@ -261,26 +614,114 @@ fn find_built_app(project_dir: &Path, scheme: &str, config: &BuildConfiguration)
     }
 
     Err(anyhow!(
-        "Could not find built app for scheme '{}' in DerivedData",
+        "Could not find built {} app for scheme '{}' in DerivedData",
+        platform,
         scheme
     ))
 }
 
-/// Get bundle identifier from app's Info.plist
-fn get_bundle_id(app_path: &str) -> Result<String> {
-    let plist_path = Path::new(app_path).join("Info.plist");
+/// Find dSYM bundles in the build products directory of an app
+fn find_dsyms(app_path: &str) -> Vec<PathBuf> {
+    let Some(products_dir) = Path::new(app_path).parent() else {
+        return Vec::new();
+    };
+
+    let mut dsyms: Vec<PathBuf> = std::fs::read_dir(products_dir)
+        .map(|entries| {
+            entries
+                .filter_map(|e| e.ok())
+                .map(|e| e.path())
+                .filter(|p| p.extension().is_some_and(|e| e == "dSYM"))
+                .collect()
+        })
+        .unwrap_or_default();
+    dsyms.sort();
+    dsyms
+}
 
-    // Use PlistBuddy to read bundle ID
-    let output = std::process::Command::new("/usr/libexec/PlistBuddy")
-        .args(["-c", "Print :CFBundleIdentifier", plist_path.to_str().unwrap()])
-        .output()
-        .context("Failed to read bundle ID from Info.plist")?;
+/// Copy the built app into the build's output directory. Installs, downloads
+/// and symbolication of the build use the copy.
+async fn keep_app(app_path: &str, output_dir: &Path) -> Result<String> {
+    let app = Path::new(app_path);
+    let name = app
+        .file_name()
+        .ok_or_else(|| anyhow!("Invalid app path: {}", app_path))?;
+    let dest = output_dir.join("Products").join(name);
+    dependencies::copy_dir_blocking(app.to_path_buf(), dest.clone())
+        .await
+        .with_context(|| format!("Failed to copy {} to the build's output", app_path))?;
+    Ok(dest.to_string_lossy().into_owned())
+}
 
-    if !output.status.success() {
-        return Err(anyhow!("Failed to read bundle ID: {}", String::from_utf8_lossy(&output.stderr)));
+/// Copy the app's dSYMs into the build's output directory, next to the app
+async fn keep_dsyms(app_path: &str, output_dir: &Path) -> Vec<PathBuf> {
+    let mut kept = Vec::new();
+    for dsym in find_dsyms(app_path) {
+        let Some(name) = dsym.file_name() else {
+            continue;
+        };
+        let dest = output_dir.join("Products").join(name);
+        match dependencies::copy_dir_blocking(dsym.clone(), dest.clone()).await {
+            Ok(()) => kept.push(dest),
+            Err(e) => warn!("Failed to keep {:?}: {:#}", dsym, e),
+        }
+    }
+    kept
+}
+
+/// Where a bundle keeps its Info.plist, PlugIns and Extensions: `Contents/`
+/// for macOS-style bundles (Mac Catalyst apps), the bundle itself otherwise
+fn bundle_contents(bundle_path: &Path) -> PathBuf {
+    let contents = bundle_path.join("Contents");
+    if contents.join("Info.plist").is_file() {
+        contents
+    } else {
+        bundle_path.to_path_buf()
+    }
+}
+
+/// Read and parse an app's Info.plist (XML or binary)
+pub async fn read_info_plist(bundle_path: &str) -> Result<PlistValue> {
+    let plist_path = bundle_contents(Path::new(bundle_path)).join("Info.plist");
+    let bytes = tokio::fs::read(&plist_path)
+        .await
+        .with_context(|| format!("Failed to read {}", plist_path.display()))?;
+
+    PlistValue::parse(&bytes).with_context(|| format!("Failed to parse {}", plist_path.display()))
+}
+
+/// Read an app's metadata, including its embedded app extensions
+pub async fn read_app_info(app_path: &str) -> Result<AppInfo> {
+    let plist = read_info_plist(app_path).await?;
+    let mut info = AppInfo::from_info_plist(&plist)
+        .ok_or_else(|| anyhow!("Info.plist of {} has no CFBundleIdentifier", app_path))?;
+
+    // PlugIns/ holds NSExtension-based extensions, Extensions/ ExtensionKit ones
+    let contents = bundle_contents(Path::new(app_path));
+    for dir in ["PlugIns", "Extensions"] {
+        let Ok(entries) = std::fs::read_dir(contents.join(dir)) else {
+            continue;
+        };
+        let mut appexes: Vec<PathBuf> = entries
+            .filter_map(|e| e.ok())
+            .map(|e| e.path())
+            .filter(|p| p.extension().is_some_and(|e| e == "appex"))
+            .collect();
+        appexes.sort();
+
+        for appex in appexes {
+            let name = appex
+                .file_name()
+                .map(|n| n.to_string_lossy().into_owned())
+                .unwrap_or_default();
+            match read_info_plist(&appex.to_string_lossy()).await {
+                Ok(plist) => info.extensions.push(AppExtensionInfo::from_info_plist(name, &plist)),
+                Err(e) => warn!("Skipping extension {}: {:#}", name, e),
+            }
+        }
     }
 
-    Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+    Ok(info)
 }
 
 // Helper for home directory
@@ -0,0 +1,133 @@
+use anyhow::{anyhow, Context, Result};
+use xscape_common::{BuildConfiguration, BuildRequest};
+use serde::Deserialize;
+use std::path::Path;
+use std::time::Duration;
+use tokio::process::Command;
+use tracing::debug;
+
+/// Evaluating a manifest can mean compiling it first
+const DUMP_TIMEOUT: Duration = Duration::from_secs(120);
+
+/// Platforms `platformName` uses for the OSes the agent has simulators (or
+/// Mac Catalyst) for
+const SIMULATOR_PLATFORMS: &[&str] = &["ios", "tvos", "watchos", "visionos", "maccatalyst"];
+
+/// The parts of `swift package dump-package` the agent uses
+#[derive(Debug, Deserialize)]
+pub struct PackageManifest {
+    pub name: String,
+    /// Minimum OS versions the package declares
+    #[serde(default)]
+    pub platforms: Vec<SupportedPlatform>,
+    #[serde(default)]
+    pub products: Vec<PackageProduct>,
+    #[serde(default)]
+    pub targets: Vec<PackageTarget>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SupportedPlatform {
+    /// e.g., "ios", "macos"
+    pub platform_name: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct PackageProduct {
+    pub name: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct PackageTarget {
+    pub name: String,
+}
+
+impl PackageManifest {
+    /// Evaluate the Package.swift in `dir`
+    pub async fn load(dir: &Path) -> Result<Self> {
+        let mut cmd = Command::new("swift");
+        cmd.args(["package", "dump-package"])
+            .current_dir(dir)
+            .kill_on_drop(true);
+        debug!("Running swift: {:?}", cmd);
+
+        let output = tokio::time::timeout(DUMP_TIMEOUT, cmd.output())
+            .await
+            .map_err(|_| anyhow!("swift package dump-package timed out after {}s", DUMP_TIMEOUT.as_secs()))?
+            .context("Failed to run swift package dump-package")?;
+        if !output.status.success() {
+            return Err(anyhow!(
+                "Failed to read Package.swift: {}",
+                String::from_utf8_lossy(&output.stderr).trim()
+            ));
+        }
+        serde_json::from_slice(&output.stdout).context("Failed to parse swift package dump-package output")
+    }
+
+    /// Whether the package only builds for the Mac it's on: it declares
+    /// platforms, none of which run in a simulator. Packages without a
+    /// platforms list build for every platform.
+    pub fn is_host_only(&self) -> bool {
+        !self.platforms.is_empty()
+            && self
+                .platforms
+                .iter()
+                .all(|p| !SIMULATOR_PLATFORMS.contains(&p.platform_name.as_str()))
+    }
+}
+
+/// `swift build` or `swift test` for a host-only package, preceded by
+/// `swift package clean` for clean builds. `cache_path` holds the
+/// repositories the dependency phase resolved.
+pub fn swift_commands(
+    dir: &Path,
+    manifest: &PackageManifest,
+    request: &BuildRequest,
+    cache_path: Option<&Path>,
+) -> Result<Vec<Command>> {
+    if !request.build_settings.is_empty() || request.xcconfig.is_some() {
+        return Err(anyhow!(
+            "Build setting overrides need xcodebuild, but {} is built with swift build",
+            manifest.name
+        ));
+    }
+    // SwiftPM has no other configurations
+    let configuration = match request.configuration {
+        BuildConfiguration::Debug => "debug",
+        BuildConfiguration::Release => "release",
+        BuildConfiguration::Custom(ref name) => {
+            return Err(anyhow!(
+                "swift build only knows the Debug and Release configurations, not {}",
+                name
+            ))
+        }
+    };
+
+    let mut commands = Vec::new();
+    if request.clean {
+        let mut cmd = Command::new("swift");
+        cmd.args(["package", "clean"]).current_dir(dir);
+        commands.push(cmd);
+    }
+
+    let mut cmd = Command::new("swift");
+    cmd.arg(if request.test { "test" } else { "build" })
+        .arg("-c")
+        .arg(configuration);
+    // A scheme named after a product builds just that product; the package's
+    // own scheme builds everything
+    if !request.test && manifest.products.iter().any(|p| p.name == request.scheme) {
+        cmd.arg("--product").arg(&request.scheme);
+    }
+    if let Some(cache_path) = cache_path {
+        cmd.arg("--cache-path").arg(cache_path);
+        if request.offline {
+            cmd.arg("--disable-automatic-resolution").arg("--skip-update");
+        }
+    }
+    cmd.args(&request.extra_args).current_dir(dir);
+    commands.push(cmd);
+
+    Ok(commands)
+}
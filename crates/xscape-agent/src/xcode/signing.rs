@@ -0,0 +1,342 @@
+use anyhow::{anyhow, Context, Result};
+use xscape_common::PlistValue;
+use std::os::unix::fs::{DirBuilderExt, OpenOptionsExt};
+use std::path::{Path, PathBuf};
+use tokio::process::Command;
+use tokio::sync::Mutex;
+use tracing::{debug, info, warn};
+use uuid::Uuid;
+
+use super::dirs;
+
+/// Serializes edits of the user keychain search list between concurrent archives
+static SEARCH_LIST_LOCK: Mutex<()> = Mutex::const_new(());
+
+/// Prefix of each session's directory in the temp dir
+const SESSION_DIR_PREFIX: &str = "xscape-signing-";
+
+/// Keychain file inside a session's directory
+const KEYCHAIN_FILE: &str = "signing.keychain-db";
+
+/// File in a session's directory listing the profiles it installed, so a
+/// session the agent never removed can still be cleaned up
+const PROFILES_FILE: &str = "installed-profiles";
+
+/// Signing assets uploaded with an archive request. Kept in memory only.
+#[derive(Default)]
+pub struct SigningAssets {
+    /// PKCS#12 certificate including its private key
+    pub certificate: Option<Vec<u8>>,
+    pub certificate_password: Option<String>,
+    /// Provisioning profiles (.mobileprovision)
+    pub profiles: Vec<Vec<u8>>,
+}
+
+/// The parts of a provisioning profile needed for export
+#[derive(Debug, Clone)]
+pub struct ProvisioningProfile {
+    pub uuid: String,
+    pub name: String,
+    pub team_id: Option<String>,
+    /// Bundle ID the profile is for (None for wildcard profiles)
+    pub bundle_id: Option<String>,
+}
+
+impl ProvisioningProfile {
+    /// Parse a .mobileprovision: a signed CMS envelope around an XML plist
+    pub fn parse(bytes: &[u8]) -> Result<Self> {
+        let start = find(bytes, b"<?xml").ok_or_else(|| anyhow!("No plist in provisioning profile"))?;
+        let end = find(&bytes[start..], b"</plist>")
+            .map(|i| start + i + b"</plist>".len())
+            .ok_or_else(|| anyhow!("Truncated plist in provisioning profile"))?;
+        let plist = PlistValue::parse(&bytes[start..end])?;
+
+        let string = |key: &str| plist.get(key).and_then(|v| v.as_str()).map(String::from);
+        let app_id = plist
+            .get("Entitlements")
+            .and_then(|e| e.get("application-identifier"))
+            .and_then(|v| v.as_str());
+
+        Ok(Self {
+            uuid: string("UUID").ok_or_else(|| anyhow!("Provisioning profile has no UUID"))?,
+            name: string("Name").unwrap_or_default(),
+            team_id: plist
+                .get("TeamIdentifier")
+                .and_then(|t| t.as_array())
+                .and_then(|t| t.first())
+                .and_then(|t| t.as_str())
+                .map(String::from),
+            // "TEAMID.com.example.app"; wildcard profiles end in "*"
+            bundle_id: app_id
+                .and_then(|id| id.split_once('.'))
+                .map(|(_, bundle_id)| bundle_id)
+                .filter(|bundle_id| !bundle_id.contains('*'))
+                .map(String::from),
+        })
+    }
+}
+
+fn find(haystack: &[u8], needle: &[u8]) -> Option<usize> {
+    haystack.windows(needle.len()).position(|w| w == needle)
+}
+
+/// Ephemeral keychain and installed profiles for one archive.
+/// Call `remove` when done; if the agent dies first,
+/// `remove_stale_sessions` cleans up at the next start.
+pub struct SigningSession {
+    dir: PathBuf,
+    keychain: Option<PathBuf>,
+    keychain_password: String,
+    installed_profiles: Vec<PathBuf>,
+    pub profiles: Vec<ProvisioningProfile>,
+}
+
+impl SigningSession {
+    /// Install the profiles and import the certificate into a new keychain
+    pub async fn install(assets: &SigningAssets) -> Result<Self> {
+        let dir = std::env::temp_dir().join(format!("{}{}", SESSION_DIR_PREFIX, Uuid::new_v4()));
+        std::fs::DirBuilder::new()
+            .mode(0o700)
+            .create(&dir)
+            .with_context(|| format!("Failed to create {:?}", dir))?;
+
+        let mut session = Self {
+            dir,
+            keychain: None,
+            keychain_password: Uuid::new_v4().simple().to_string(),
+            installed_profiles: Vec::new(),
+            profiles: Vec::new(),
+        };
+
+        match session.setup(assets).await {
+            Ok(()) => Ok(session),
+            Err(e) => {
+                session.remove().await;
+                Err(e)
+            }
+        }
+    }
+
+    async fn setup(&mut self, assets: &SigningAssets) -> Result<()> {
+        for bytes in &assets.profiles {
+            let profile = ProvisioningProfile::parse(bytes)?;
+            self.install_profile(&profile, bytes)?;
+            info!("Installed provisioning profile '{}' ({})", profile.name, profile.uuid);
+            self.profiles.push(profile);
+        }
+
+        if let Some(certificate) = &assets.certificate {
+            let password = assets.certificate_password.as_deref().unwrap_or("");
+            self.import_certificate(certificate, password).await?;
+        }
+
+        Ok(())
+    }
+
+    /// Path of the ephemeral keychain, if a certificate was imported
+    pub fn keychain(&self) -> Option<&Path> {
+        self.keychain.as_deref()
+    }
+
+    /// Values that must never appear in logs
+    pub fn secrets(&self) -> Vec<String> {
+        vec![self.keychain_password.clone()]
+    }
+
+    /// Copy a profile where Xcode looks for it, keeping profiles already there
+    fn install_profile(&mut self, profile: &ProvisioningProfile, bytes: &[u8]) -> Result<()> {
+        use std::io::Write;
+
+        let home = dirs::home_dir().ok_or_else(|| anyhow!("HOME is not set"))?;
+        // Xcode 16 moved the directory; older versions use the MobileDevice one
+        let profile_dirs = [
+            home.join("Library/Developer/Xcode/UserData/Provisioning Profiles"),
+            home.join("Library/MobileDevice/Provisioning Profiles"),
+        ];
+
+        for dir in profile_dirs {
+            let path = dir.join(format!("{}.mobileprovision", profile.uuid));
+            if path.exists() {
+                debug!("Profile {} already installed at {:?}", profile.uuid, path);
+                continue;
+            }
+            std::fs::create_dir_all(&dir).with_context(|| format!("Failed to create {:?}", dir))?;
+            // Record the profile before writing it, so it can't be left behind unlisted
+            let mut listed = std::fs::OpenOptions::new()
+                .create(true)
+                .append(true)
+                .mode(0o600)
+                .open(self.dir.join(PROFILES_FILE))
+                .context("Failed to record installed profile")?;
+            writeln!(listed, "{}", path.display()).context("Failed to record installed profile")?;
+            std::fs::write(&path, bytes).with_context(|| format!("Failed to write {:?}", path))?;
+            self.installed_profiles.push(path);
+        }
+        Ok(())
+    }
+
+    async fn import_certificate(&mut self, certificate: &[u8], password: &str) -> Result<()> {
+        let keychain = self.dir.join(KEYCHAIN_FILE);
+        let keychain_str = keychain.to_string_lossy().into_owned();
+        let secrets = [self.keychain_password.as_str(), password];
+
+        security(&["create-keychain", "-p", &self.keychain_password, &keychain_str], &secrets).await?;
+        self.keychain = Some(keychain.clone());
+        // Lock again after an hour (-u -t), but not on sleep (no -l), which
+        // would stop codesign partway through an archive
+        security(&["set-keychain-settings", "-ut", "3600", &keychain_str], &secrets).await?;
+        security(&["unlock-keychain", "-p", &self.keychain_password, &keychain_str], &secrets).await?;
+
+        let p12_path = self.dir.join("certificate.p12");
+        write_private(&p12_path, certificate)?;
+        let p12_str = p12_path.to_string_lossy().into_owned();
+        let imported = security(
+            &[
+                "import", &p12_str, "-k", &keychain_str, "-f", "pkcs12", "-P", password,
+                "-T", "/usr/bin/codesign", "-T", "/usr/bin/security",
+            ],
+            &secrets,
+        )
+        .await;
+        let _ = std::fs::remove_file(&p12_path);
+        imported.context("Failed to import signing certificate (wrong password?)")?;
+
+        // Let codesign use the key without a UI prompt
+        security(
+            &[
+                "set-key-partition-list", "-S", "apple-tool:,apple:,codesign:", "-s",
+                "-k", &self.keychain_password, &keychain_str,
+            ],
+            &secrets,
+        )
+        .await?;
+
+        // xcodebuild only finds identities in keychains on the search list
+        let _guard = SEARCH_LIST_LOCK.lock().await;
+        let mut keychains = search_list().await?;
+        keychains.insert(0, keychain_str);
+        set_search_list(&keychains).await?;
+
+        info!("Imported signing certificate into ephemeral keychain");
+        Ok(())
+    }
+
+    /// Delete the keychain and the profiles this session installed
+    pub async fn remove(self) {
+        cleanup(&self.dir, self.keychain.as_deref(), &self.installed_profiles).await;
+    }
+}
+
+/// Remove the sessions of archives that never finished, e.g. because the
+/// agent was killed mid-archive. Only call this while no archive is running.
+pub async fn remove_stale_sessions() {
+    let Ok(entries) = std::fs::read_dir(std::env::temp_dir()) else {
+        return;
+    };
+    for entry in entries.filter_map(|e| e.ok()) {
+        let is_session = entry.file_name().to_string_lossy().starts_with(SESSION_DIR_PREFIX)
+            && entry.file_type().is_ok_and(|t| t.is_dir());
+        if !is_session {
+            continue;
+        }
+        let dir = entry.path();
+        warn!("Removing signing session left behind by an earlier run: {:?}", dir);
+        let keychain = Some(dir.join(KEYCHAIN_FILE)).filter(|k| k.exists());
+        let profiles: Vec<PathBuf> = std::fs::read_to_string(dir.join(PROFILES_FILE))
+            .unwrap_or_default()
+            .lines()
+            .filter(|line| !line.is_empty())
+            .map(PathBuf::from)
+            .collect();
+        cleanup(&dir, keychain.as_deref(), &profiles).await;
+    }
+}
+
+/// Take a session's keychain off the search list and delete it, then delete
+/// its profiles and directory
+async fn cleanup(dir: &Path, keychain: Option<&Path>, profiles: &[PathBuf]) {
+    if let Some(keychain) = keychain {
+        let keychain_str = keychain.to_string_lossy().into_owned();
+        {
+            let _guard = SEARCH_LIST_LOCK.lock().await;
+            match search_list().await {
+                Ok(keychains) => {
+                    let remaining: Vec<String> =
+                        keychains.into_iter().filter(|k| *k != keychain_str).collect();
+                    if let Err(e) = set_search_list(&remaining).await {
+                        warn!("Failed to restore keychain search list: {}", e);
+                    }
+                }
+                Err(e) => warn!("Failed to read keychain search list: {}", e),
+            }
+        }
+        if let Err(e) = security(&["delete-keychain", &keychain_str], &[]).await {
+            warn!("Failed to delete ephemeral keychain: {}", e);
+        }
+    }
+
+    for profile in profiles {
+        match std::fs::remove_file(profile) {
+            Ok(()) => {}
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+            Err(e) => warn!("Failed to remove provisioning profile {:?}: {}", profile, e),
+        }
+    }
+
+    if let Err(e) = std::fs::remove_dir_all(dir) {
+        warn!("Failed to remove {:?}: {}", dir, e);
+    }
+}
+
+/// Write a file only the agent user can read
+fn write_private(path: &Path, bytes: &[u8]) -> Result<()> {
+    use std::io::Write;
+
+    let mut file = std::fs::OpenOptions::new()
+        .write(true)
+        .create_new(true)
+        .mode(0o600)
+        .open(path)
+        .with_context(|| format!("Failed to create {:?}", path))?;
+    file.write_all(bytes)?;
+    Ok(())
+}
+
+/// Run `security`. Arguments may contain passwords, so only the subcommand is
+/// logged and `secrets` are masked in error output.
+async fn security(args: &[&str], secrets: &[&str]) -> Result<String> {
+    debug!("Running security {}", args.first().unwrap_or(&""));
+
+    let output = Command::new("security")
+        .args(args)
+        .output()
+        .await
+        .context("Failed to run security")?;
+
+    if !output.status.success() {
+        let mut stderr = String::from_utf8_lossy(&output.stderr).trim().to_string();
+        for secret in secrets.iter().filter(|s| !s.is_empty()) {
+            stderr = stderr.replace(secret, "********");
+        }
+        return Err(anyhow!("security {} failed: {}", args.first().unwrap_or(&""), stderr));
+    }
+
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+/// Keychains on the user search list
+async fn search_list() -> Result<Vec<String>> {
+    let output = security(&["list-keychains", "-d", "user"], &[]).await?;
+    Ok(output
+        .lines()
+        .map(|line| line.trim().trim_matches('"').to_string())
+        .filter(|line| !line.is_empty())
+        .collect())
+}
+
+async fn set_search_list(keychains: &[String]) -> Result<()> {
+    let mut args = vec!["list-keychains", "-d", "user", "-s"];
+    args.extend(keychains.iter().map(String::as_str));
+    security(&args, &[]).await?;
+    Ok(())
+}
@@ -0,0 +1,282 @@
+use serde::{Deserialize, Serialize};
+
+/// Light or dark mode
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum Appearance {
+    Light,
+    Dark,
+}
+
+impl Appearance {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Light => "light",
+            Self::Dark => "dark",
+        }
+    }
+}
+
+impl std::fmt::Display for Appearance {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl std::str::FromStr for Appearance {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "light" => Ok(Self::Light),
+            "dark" => Ok(Self::Dark),
+            _ => Err(format!("unknown appearance '{}' (expected light or dark)", s)),
+        }
+    }
+}
+
+/// Dynamic Type content size category
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+pub enum ContentSize {
+    ExtraSmall,
+    Small,
+    Medium,
+    Large,
+    ExtraLarge,
+    ExtraExtraLarge,
+    ExtraExtraExtraLarge,
+    AccessibilityMedium,
+    AccessibilityLarge,
+    AccessibilityExtraLarge,
+    AccessibilityExtraExtraLarge,
+    AccessibilityExtraExtraExtraLarge,
+}
+
+impl ContentSize {
+    pub const ALL: [ContentSize; 12] = [
+        Self::ExtraSmall,
+        Self::Small,
+        Self::Medium,
+        Self::Large,
+        Self::ExtraLarge,
+        Self::ExtraExtraLarge,
+        Self::ExtraExtraExtraLarge,
+        Self::AccessibilityMedium,
+        Self::AccessibilityLarge,
+        Self::AccessibilityExtraLarge,
+        Self::AccessibilityExtraExtraLarge,
+        Self::AccessibilityExtraExtraExtraLarge,
+    ];
+
+    /// Category name as understood by `simctl ui content_size`
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::ExtraSmall => "extra-small",
+            Self::Small => "small",
+            Self::Medium => "medium",
+            Self::Large => "large",
+            Self::ExtraLarge => "extra-large",
+            Self::ExtraExtraLarge => "extra-extra-large",
+            Self::ExtraExtraExtraLarge => "extra-extra-extra-large",
+            Self::AccessibilityMedium => "accessibility-medium",
+            Self::AccessibilityLarge => "accessibility-large",
+            Self::AccessibilityExtraLarge => "accessibility-extra-large",
+            Self::AccessibilityExtraExtraLarge => "accessibility-extra-extra-large",
+            Self::AccessibilityExtraExtraExtraLarge => "accessibility-extra-extra-extra-large",
+        }
+    }
+}
+
+impl std::fmt::Display for ContentSize {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl std::str::FromStr for ContentSize {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::ALL
+            .into_iter()
+            .find(|size| size.as_str() == s.to_lowercase())
+            .ok_or_else(|| {
+                let names: Vec<_> = Self::ALL.iter().map(|s| s.as_str()).collect();
+                format!("unknown content size '{}' (expected one of: {})", s, names.join(", "))
+            })
+    }
+}
+
+/// Request to change simulator UI settings. Unset fields are left as-is.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct UiSettingsRequest {
+    #[serde(default)]
+    pub appearance: Option<Appearance>,
+    #[serde(default)]
+    pub content_size: Option<ContentSize>,
+}
+
+/// Current simulator UI settings
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UiSettingsResponse {
+    pub device_udid: String,
+    pub appearance: Option<Appearance>,
+    pub content_size: Option<ContentSize>,
+}
+
+const DATA_NETWORKS: &[&str] = &[
+    "hide", "wifi", "3g", "4g", "lte", "lte-a", "lte+", "5g", "5g+", "5g-uwb", "5g-uc",
+];
+const WIFI_MODES: &[&str] = &["searching", "failed", "active"];
+const CELLULAR_MODES: &[&str] = &["notSupported", "searching", "failed", "active"];
+const BATTERY_STATES: &[&str] = &["charging", "charged", "discharging"];
+
+/// Status bar override (`simctl status_bar override`). Unset fields keep
+/// their current value.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct StatusBarOverride {
+    /// Displayed time (e.g., "9:41")
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub time: Option<String>,
+    /// hide, wifi, 3g, 4g, lte, lte-a, lte+, 5g, 5g+, 5g-uwb, 5g-uc
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub data_network: Option<String>,
+    /// searching, failed, active
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub wifi_mode: Option<String>,
+    /// 0-3
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub wifi_bars: Option<u8>,
+    /// notSupported, searching, failed, active
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cellular_mode: Option<String>,
+    /// 0-4
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cellular_bars: Option<u8>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub operator_name: Option<String>,
+    /// charging, charged, discharging
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub battery_state: Option<String>,
+    /// 0-100
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub battery_level: Option<u8>,
+}
+
+impl StatusBarOverride {
+    /// The classic marketing status bar: 9:41, full signal, full battery
+    pub fn clean() -> Self {
+        Self {
+            time: Some("9:41".to_string()),
+            data_network: Some("wifi".to_string()),
+            wifi_mode: Some("active".to_string()),
+            wifi_bars: Some(3),
+            cellular_mode: Some("active".to_string()),
+            cellular_bars: Some(4),
+            operator_name: None,
+            battery_state: Some("charged".to_string()),
+            battery_level: Some(100),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.to_simctl_args().is_empty()
+    }
+
+    /// Check values against what simctl accepts
+    pub fn validate(&self) -> Result<(), String> {
+        fn one_of(field: &str, value: &Option<String>, allowed: &[&str]) -> Result<(), String> {
+            match value {
+                Some(v) if !allowed.contains(&v.as_str()) => Err(format!(
+                    "invalid {} '{}' (expected one of: {})",
+                    field,
+                    v,
+                    allowed.join(", ")
+                )),
+                _ => Ok(()),
+            }
+        }
+        fn at_most(field: &str, value: Option<u8>, max: u8) -> Result<(), String> {
+            match value {
+                Some(v) if v > max => Err(format!("{} must be between 0 and {}", field, max)),
+                _ => Ok(()),
+            }
+        }
+
+        one_of("data network", &self.data_network, DATA_NETWORKS)?;
+        one_of("wifi mode", &self.wifi_mode, WIFI_MODES)?;
+        one_of("cellular mode", &self.cellular_mode, CELLULAR_MODES)?;
+        one_of("battery state", &self.battery_state, BATTERY_STATES)?;
+        at_most("wifi bars", self.wifi_bars, 3)?;
+        at_most("cellular bars", self.cellular_bars, 4)?;
+        at_most("battery level", self.battery_level, 100)?;
+        Ok(())
+    }
+
+    /// Convert to `simctl status_bar override` arguments
+    pub fn to_simctl_args(&self) -> Vec<String> {
+        let mut args = Vec::new();
+        let mut push = |flag: &str, value: Option<String>| {
+            if let Some(value) = value {
+                args.push(flag.to_string());
+                args.push(value);
+            }
+        };
+
+        push("--time", self.time.clone());
+        push("--dataNetwork", self.data_network.clone());
+        push("--wifiMode", self.wifi_mode.clone());
+        push("--wifiBars", self.wifi_bars.map(|v| v.to_string()));
+        push("--cellularMode", self.cellular_mode.clone());
+        push("--cellularBars", self.cellular_bars.map(|v| v.to_string()));
+        push("--operatorName", self.operator_name.clone());
+        push("--batteryState", self.battery_state.clone());
+        push("--batteryLevel", self.battery_level.map(|v| v.to_string()));
+        args
+    }
+}
+
+/// Response after changing the status bar
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StatusBarResponse {
+    pub device_udid: String,
+    /// Active override (None when cleared)
+    pub status_bar: Option<StatusBarOverride>,
+}
+
+/// Check a locale identifier such as "de_DE" or "zh_Hans_CN"
+pub fn validate_locale(locale: &str) -> Result<(), String> {
+    let valid = !locale.is_empty()
+        && locale.chars().next().is_some_and(|c| c.is_ascii_alphabetic())
+        && locale
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '_' | '-' | '@' | '='));
+    if valid {
+        Ok(())
+    } else {
+        Err(format!("invalid locale identifier '{}'", locale))
+    }
+}
+
+/// Launch arguments that override the app's language and region.
+/// Without explicit languages, the language is derived from the locale.
+pub fn locale_launch_args(locale: Option<&str>, languages: &[String]) -> Vec<String> {
+    let mut args = Vec::new();
+
+    let languages: Vec<String> = if languages.is_empty() {
+        locale.map(|l| vec![l.replace('_', "-")]).unwrap_or_default()
+    } else {
+        languages.to_vec()
+    };
+    if !languages.is_empty() {
+        args.push("-AppleLanguages".to_string());
+        args.push(format!("({})", languages.join(", ")));
+    }
+    if let Some(locale) = locale {
+        args.push("-AppleLocale".to_string());
+        args.push(locale.to_string());
+    }
+
+    args
+}
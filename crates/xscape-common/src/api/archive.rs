@@ -0,0 +1,83 @@
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use super::build::BuildConfiguration;
+
+/// Request to archive a project for devices and export it as an .ipa.
+///
+/// Sent as the `request` field of a multipart form; signing assets travel in
+/// the same form (`certificate`, `certificate_password`, `profile`,
+/// `export_options`) and are never stored with the build.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ArchiveRequest {
+    /// Project ID from sync
+    pub project_id: Uuid,
+    /// Relative path to .xcodeproj or .xcworkspace within project
+    #[serde(default)]
+    pub project_file: Option<String>,
+    /// Xcode scheme to archive
+    pub scheme: String,
+    /// Build configuration
+    #[serde(default = "default_configuration")]
+    pub configuration: BuildConfiguration,
+    /// Export method, used when no ExportOptions plist is uploaded
+    #[serde(default)]
+    pub export_method: Option<ExportMethod>,
+    /// Development team, used when no ExportOptions plist is uploaded
+    #[serde(default)]
+    pub team_id: Option<String>,
+    /// Additional xcodebuild arguments for the archive step
+    #[serde(default)]
+    pub extra_args: Vec<String>,
+    /// Resolve CocoaPods and Swift packages only from the agent's cache
+    #[serde(default)]
+    pub offline: bool,
+}
+
+fn default_configuration() -> BuildConfiguration {
+    BuildConfiguration::Release
+}
+
+/// How an archive is exported (ExportOptions `method`)
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+pub enum ExportMethod {
+    AdHoc,
+    Development,
+    AppStore,
+    Enterprise,
+}
+
+impl ExportMethod {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::AdHoc => "ad-hoc",
+            Self::Development => "development",
+            Self::AppStore => "app-store",
+            Self::Enterprise => "enterprise",
+        }
+    }
+}
+
+impl std::fmt::Display for ExportMethod {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl std::str::FromStr for ExportMethod {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "ad-hoc" | "adhoc" => Ok(Self::AdHoc),
+            "development" => Ok(Self::Development),
+            "app-store" | "appstore" => Ok(Self::AppStore),
+            "enterprise" => Ok(Self::Enterprise),
+            _ => Err(format!(
+                "unknown export method '{}' (expected ad-hoc, development, app-store or enterprise)",
+                s
+            )),
+        }
+    }
+}
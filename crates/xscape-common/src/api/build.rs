@@ -1,13 +1,17 @@
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 use chrono::{DateTime, Utc};
+use std::collections::HashMap;
+
+use crate::platform::Platform;
+use crate::plist::PlistValue;
 
 /// Request to build a project
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct BuildRequest {
     /// Project ID from sync
     pub project_id: Uuid,
-    /// Relative path to .xcodeproj or .xcworkspace within project
+    /// Relative path to .xcodeproj, .xcworkspace or Package.swift within project
     #[serde(default)]
     pub project_file: Option<String>,
     /// Xcode scheme to build
@@ -17,66 +21,279 @@ pub struct BuildRequest {
     pub configuration: BuildConfiguration,
     /// Target device for build destination
     pub destination: BuildDestination,
+    /// Build setting overrides (e.g., SWIFT_ACTIVE_COMPILATION_CONDITIONS)
+    #[serde(default)]
+    pub build_settings: HashMap<String, String>,
+    /// Relative path to an .xcconfig within the project, passed as -xcconfig
+    #[serde(default)]
+    pub xcconfig: Option<String>,
     /// Additional xcodebuild arguments
     #[serde(default)]
     pub extra_args: Vec<String>,
     /// Clean build directory first
     #[serde(default)]
     pub clean: bool,
+    /// Run the scheme's tests instead of only building it
+    #[serde(default)]
+    pub test: bool,
+    /// Resolve CocoaPods and Swift packages only from the agent's cache
+    #[serde(default)]
+    pub offline: bool,
 }
 
-/// Build configuration
-#[derive(Debug, Clone, Copy, Serialize, Deserialize, Default, PartialEq, Eq)]
-#[serde(rename_all = "lowercase")]
+impl BuildRequest {
+    /// Check the build setting overrides, including those passed as extra
+    /// arguments
+    pub fn validate_build_settings(&self) -> Result<(), String> {
+        self.build_settings
+            .keys()
+            .try_for_each(|name| validate_build_setting(name))?;
+        validate_extra_args(&self.extra_args)
+    }
+}
+
+/// Build configuration. Debug and Release are the well-known ones; any other
+/// configuration of the project can be named.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
 pub enum BuildConfiguration {
     #[default]
     Debug,
     Release,
+    Custom(String),
+}
+
+impl BuildConfiguration {
+    /// Name as passed to `xcodebuild -configuration`
+    pub fn name(&self) -> &str {
+        match self {
+            Self::Debug => "Debug",
+            Self::Release => "Release",
+            Self::Custom(name) => name,
+        }
+    }
+}
+
+impl From<&str> for BuildConfiguration {
+    /// Debug and Release match case-insensitively; other names are kept as is
+    fn from(name: &str) -> Self {
+        match name.to_lowercase().as_str() {
+            "debug" => Self::Debug,
+            "release" => Self::Release,
+            _ => Self::Custom(name.to_string()),
+        }
+    }
+}
+
+impl std::str::FromStr for BuildConfiguration {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let name = s.trim();
+        if name.is_empty() {
+            return Err("configuration name is empty".to_string());
+        }
+        Ok(Self::from(name))
+    }
 }
 
 impl std::fmt::Display for BuildConfiguration {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+// Serialized as "debug"/"release" like the original two-variant enum, and
+// other configurations by name
+impl Serialize for BuildConfiguration {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
         match self {
-            BuildConfiguration::Debug => write!(f, "Debug"),
-            BuildConfiguration::Release => write!(f, "Release"),
+            Self::Debug => serializer.serialize_str("debug"),
+            Self::Release => serializer.serialize_str("release"),
+            Self::Custom(name) => serializer.serialize_str(name),
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for BuildConfiguration {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let name = String::deserialize(deserializer)?;
+        name.parse().map_err(serde::de::Error::custom)
+    }
+}
+
+/// Build settings the agent sets or relies on to find build products.
+/// Requests may not override them.
+pub const RESERVED_BUILD_SETTINGS: &[&str] = &[
+    "BUILD_DIR",
+    "BUILD_ROOT",
+    "BUILT_PRODUCTS_DIR",
+    "CONFIGURATION_BUILD_DIR",
+    "CONFIGURATION_TEMP_DIR",
+    "DERIVED_DATA_DIR",
+    "DSTROOT",
+    "OBJROOT",
+    "PROJECT_TEMP_DIR",
+    "SDKROOT",
+    "SHARED_PRECOMPS_DIR",
+    "SYMROOT",
+    "TARGET_BUILD_DIR",
+    "TARGET_TEMP_DIR",
+];
+
+/// Check that a build setting name is an identifier the agent doesn't control
+pub fn validate_build_setting(name: &str) -> Result<(), String> {
+    let valid = name.chars().next().is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+        && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_');
+    if !valid {
+        return Err(format!("invalid build setting name '{}'", name));
+    }
+    if RESERVED_BUILD_SETTINGS.contains(&name) {
+        return Err(format!(
+            "build setting '{}' is controlled by the agent and can't be overridden",
+            name
+        ));
+    }
+    Ok(())
+}
+
+/// xcodebuild and `swift build`/`swift test` options whose values the agent
+/// sets, or that would point the build outside the agent's directories.
+/// Requests may not pass them as extra arguments.
+pub const RESERVED_OPTIONS: &[&str] = &[
+    "-archivePath",
+    "-clonedSourcePackagesDirPath",
+    "-derivedDataPath",
+    "-exportPath",
+    "-resultBundlePath",
+    "-sdk",
+    "-xcconfig",
+    "--build-path",
+    "--cache-path",
+    "--package-path",
+    "--scratch-path",
+];
+
+/// Check extra xcodebuild arguments: agent-controlled options are rejected,
+/// and `KEY=VALUE` arguments are build settings (`KEY[sdk=...]=VALUE` too)
+/// held to `validate_build_setting`
+pub fn validate_extra_args(args: &[String]) -> Result<(), String> {
+    for arg in args {
+        if arg.starts_with('-') {
+            let option = arg.split('=').next().unwrap_or(arg);
+            if RESERVED_OPTIONS.iter().any(|o| o.eq_ignore_ascii_case(option)) {
+                return Err(format!("{} is controlled by the agent and can't be passed", option));
+            }
+            continue;
+        }
+        let Some((key, _)) = arg.split_once('=') else {
+            continue;
+        };
+        // Anything else with an `=` is an option's value, e.g. a destination
+        let name = key.split('[').next().unwrap_or(key);
+        if !name.is_empty() && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_') {
+            validate_build_setting(name)?;
+        }
+    }
+    Ok(())
+}
+
+/// What an .xcconfig file assigns and includes
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct XcconfigContents {
+    /// Assigned setting names, without conditions (`KEY[sdk=*]` is `KEY`)
+    pub settings: Vec<String>,
+    /// Included paths, with whether the include is required (`#include?` isn't)
+    pub includes: Vec<(String, bool)>,
+}
+
+impl XcconfigContents {
+    pub fn parse(content: &str) -> Self {
+        let mut contents = Self::default();
+        for line in content.lines() {
+            let line = line.split("//").next().unwrap_or("").trim();
+            if let Some(include) = line.strip_prefix("#include") {
+                let (optional, include) = match include.strip_prefix('?') {
+                    Some(rest) => (true, rest),
+                    None => (false, include),
+                };
+                let path = include.trim().trim_matches('"');
+                if !path.is_empty() {
+                    contents.includes.push((path.to_string(), !optional));
+                }
+            } else if let Some((key, _)) = line.split_once('=') {
+                let key = key.split('[').next().unwrap_or("").trim();
+                if !key.is_empty() {
+                    contents.settings.push(key.to_string());
+                }
+            }
         }
+        contents
     }
 }
 
 /// Build destination specifying target platform and device
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct BuildDestination {
-    /// Platform (e.g., "iOS Simulator")
-    #[serde(default = "default_platform")]
-    pub platform: String,
-    /// Device name (e.g., "iPhone 15 Pro")
+    /// Platform to build for
+    #[serde(default)]
+    pub platform: Platform,
+    /// Device name (e.g., "iPhone 15 Pro"); empty for any device of the platform
+    #[serde(default)]
     pub device_name: String,
     /// OS version (e.g., "17.0"), optional
     pub os_version: Option<String>,
 }
 
-fn default_platform() -> String {
-    "iOS Simulator".to_string()
-}
-
 impl BuildDestination {
     /// Create a new iOS Simulator destination
     pub fn ios_simulator(device_name: impl Into<String>) -> Self {
+        Self::simulator(Platform::Ios, device_name)
+    }
+
+    /// Create a simulator destination for any platform
+    pub fn simulator(platform: Platform, device_name: impl Into<String>) -> Self {
         Self {
-            platform: "iOS Simulator".to_string(),
+            platform,
             device_name: device_name.into(),
             os_version: None,
         }
     }
 
+    /// Build for the platform without picking a device (e.g., Mac Catalyst)
+    pub fn generic(platform: Platform) -> Self {
+        Self::simulator(platform, "")
+    }
+
     /// Convert to xcodebuild -destination string
     pub fn to_xcodebuild_arg(&self) -> String {
-        let mut dest = format!("platform={},name={}", self.platform, self.device_name);
+        if self.platform == Platform::MacCatalyst {
+            return "platform=macOS,variant=Mac Catalyst".to_string();
+        }
+        if self.device_name.is_empty() {
+            return format!("generic/platform={}", self.platform.destination_platform());
+        }
+
+        let mut dest = format!(
+            "platform={},name={}",
+            self.platform.destination_platform(),
+            self.device_name
+        );
         if let Some(ref os) = self.os_version {
             dest.push_str(&format!(",OS={}", os));
         }
         dest
     }
+
+    /// Human-readable target (e.g., "iPhone 15 Pro" or "any tvOS Simulator")
+    pub fn describe(&self) -> String {
+        if self.platform == Platform::MacCatalyst {
+            self.platform.to_string()
+        } else if self.device_name.is_empty() {
+            format!("any {}", self.platform.destination_platform())
+        } else {
+            self.device_name.clone()
+        }
+    }
 }
 
 /// Response when build is started
@@ -126,4 +343,385 @@ pub struct BuildStatusResponse {
     pub warnings: Vec<String>,
     /// Build duration in seconds
     pub duration_secs: Option<f64>,
+    /// Metadata read from the built app's Info.plist
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub app_info: Option<AppInfo>,
+}
+
+/// Metadata of a built app, read from its Info.plist
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct AppInfo {
+    /// CFBundleIdentifier
+    pub bundle_id: String,
+    /// CFBundleDisplayName, falling back to CFBundleName
+    pub display_name: Option<String>,
+    /// CFBundleShortVersionString (e.g., "1.2.0")
+    pub version: Option<String>,
+    /// CFBundleVersion (e.g., "42")
+    pub build_number: Option<String>,
+    /// MinimumOSVersion
+    pub minimum_os_version: Option<String>,
+    /// Schemes of all CFBundleURLTypes entries
+    #[serde(default)]
+    pub url_schemes: Vec<String>,
+    /// UIDeviceFamily
+    #[serde(default)]
+    pub device_families: Vec<DeviceFamily>,
+    /// App extensions embedded in the bundle (PlugIns/*.appex)
+    #[serde(default)]
+    pub extensions: Vec<AppExtensionInfo>,
+}
+
+impl AppInfo {
+    /// Read the app metadata from a parsed Info.plist.
+    /// Returns None if the plist has no CFBundleIdentifier.
+    pub fn from_info_plist(plist: &PlistValue) -> Option<Self> {
+        let string = |key: &str| plist.get(key).and_then(|v| v.as_str()).map(String::from);
+
+        Some(Self {
+            bundle_id: string("CFBundleIdentifier")?,
+            display_name: string("CFBundleDisplayName").or_else(|| string("CFBundleName")),
+            version: string("CFBundleShortVersionString"),
+            build_number: string("CFBundleVersion"),
+            minimum_os_version: string("MinimumOSVersion"),
+            url_schemes: url_types(plist)
+                .into_iter()
+                .flat_map(|t| t.schemes)
+                .collect(),
+            device_families: plist
+                .get("UIDeviceFamily")
+                .and_then(|v| v.as_array())
+                .map(|a| {
+                    a.iter()
+                        .filter_map(|v| v.as_integer())
+                        .filter_map(DeviceFamily::from_code)
+                        .collect()
+                })
+                .unwrap_or_default(),
+            extensions: Vec::new(),
+        })
+    }
+}
+
+/// Device family an app supports (UIDeviceFamily values)
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum DeviceFamily {
+    Iphone,
+    Ipad,
+    Tv,
+    Watch,
+    Mac,
+    Vision,
+}
+
+impl DeviceFamily {
+    pub fn from_code(code: i64) -> Option<Self> {
+        match code {
+            1 => Some(Self::Iphone),
+            2 => Some(Self::Ipad),
+            3 => Some(Self::Tv),
+            4 => Some(Self::Watch),
+            6 => Some(Self::Mac),
+            7 => Some(Self::Vision),
+            _ => None,
+        }
+    }
+}
+
+impl std::fmt::Display for DeviceFamily {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Iphone => write!(f, "iPhone"),
+            Self::Ipad => write!(f, "iPad"),
+            Self::Tv => write!(f, "Apple TV"),
+            Self::Watch => write!(f, "Apple Watch"),
+            Self::Mac => write!(f, "Mac"),
+            Self::Vision => write!(f, "Apple Vision"),
+        }
+    }
+}
+
+/// App extension embedded in an app
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AppExtensionInfo {
+    /// Bundle name (e.g., "Widgets.appex")
+    pub name: String,
+    pub bundle_id: Option<String>,
+    /// NSExtensionPointIdentifier (e.g., "com.apple.widgetkit-extension")
+    pub extension_point: Option<String>,
+}
+
+impl AppExtensionInfo {
+    pub fn from_info_plist(name: impl Into<String>, plist: &PlistValue) -> Self {
+        Self {
+            name: name.into(),
+            bundle_id: plist
+                .get("CFBundleIdentifier")
+                .and_then(|v| v.as_str())
+                .map(String::from),
+            extension_point: plist
+                .get("NSExtension")
+                .and_then(|e| e.get("NSExtensionPointIdentifier"))
+                .and_then(|v| v.as_str())
+                .map(String::from),
+        }
+    }
+}
+
+/// URL type registered by an app (CFBundleURLTypes entry)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UrlType {
+    /// CFBundleURLName
+    pub name: Option<String>,
+    /// CFBundleURLSchemes
+    pub schemes: Vec<String>,
+}
+
+/// Extract CFBundleURLTypes from an Info.plist
+pub fn url_types(info_plist: &PlistValue) -> Vec<UrlType> {
+    info_plist
+        .get("CFBundleURLTypes")
+        .and_then(|t| t.as_array())
+        .map(|types| {
+            types
+                .iter()
+                .map(|t| UrlType {
+                    name: t
+                        .get("CFBundleURLName")
+                        .and_then(|n| n.as_str())
+                        .map(String::from),
+                    schemes: t
+                        .get("CFBundleURLSchemes")
+                        .map(|s| s.string_array())
+                        .unwrap_or_default(),
+                })
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// URL schemes registered by a built app
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UrlSchemesResponse {
+    pub build_id: Uuid,
+    pub bundle_id: Option<String>,
+    pub url_types: Vec<UrlType>,
+}
+
+/// Kind of product kept from a build
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ArtifactKind {
+    /// The built .app bundle
+    App,
+    /// Debug symbols
+    Dsym,
+    /// xcodebuild result bundle (.xcresult)
+    ResultBundle,
+    /// Full xcodebuild output
+    Log,
+    /// Device archive (.xcarchive)
+    Archive,
+    /// Exported, signed app (.ipa)
+    Ipa,
+}
+
+impl ArtifactKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::App => "app",
+            Self::Dsym => "dsym",
+            Self::ResultBundle => "result_bundle",
+            Self::Log => "log",
+            Self::Archive => "archive",
+            Self::Ipa => "ipa",
+        }
+    }
+}
+
+impl std::fmt::Display for ArtifactKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// A downloadable build product
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BuildArtifactInfo {
+    /// File name, used to download the artifact (e.g. "MyApp.app")
+    pub name: String,
+    pub kind: ArtifactKind,
+    /// Directories are downloaded as archives
+    pub is_dir: bool,
+    /// Total size of all files
+    pub size_bytes: u64,
+}
+
+/// Response listing the products of a build
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ListArtifactsResponse {
+    pub build_id: Uuid,
+    pub artifacts: Vec<BuildArtifactInfo>,
+}
+
+/// Archive format for directory downloads
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub enum ArchiveFormat {
+    #[default]
+    #[serde(rename = "tar.gz")]
+    TarGz,
+    #[serde(rename = "zip")]
+    Zip,
+}
+
+impl ArchiveFormat {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::TarGz => "tar.gz",
+            Self::Zip => "zip",
+        }
+    }
+
+    pub fn content_type(&self) -> &'static str {
+        match self {
+            Self::TarGz => "application/gzip",
+            Self::Zip => "application/zip",
+        }
+    }
+}
+
+impl std::fmt::Display for ArchiveFormat {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl std::str::FromStr for ArchiveFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "tar.gz" | "tgz" => Ok(Self::TarGz),
+            "zip" => Ok(Self::Zip),
+            _ => Err(format!("unknown archive format '{}' (expected tar.gz or zip)", s)),
+        }
+    }
+}
+
+/// Query for artifact downloads
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ArtifactQuery {
+    #[serde(default)]
+    pub format: ArchiveFormat,
+}
+
+/// Response after uploading a prebuilt .app; the app is registered as a build
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UploadAppResponse {
+    /// Pseudo-build ID, usable wherever a build ID is expected
+    pub build_id: Uuid,
+    pub bundle_id: String,
+    /// Name of the .app bundle
+    pub app_name: String,
+    /// CFBundleSupportedPlatforms of the app
+    pub platforms: Vec<String>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn plist(entries: &str) -> PlistValue {
+        let xml = format!(
+            "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<plist version=\"1.0\">\n<dict>\n{}\n</dict>\n</plist>",
+            entries
+        );
+        PlistValue::parse(xml.as_bytes()).unwrap()
+    }
+
+    #[test]
+    fn reads_app_info() {
+        let info = AppInfo::from_info_plist(&plist(
+            r#"
+            <key>CFBundleIdentifier</key><string>com.example.Demo</string>
+            <key>CFBundleName</key><string>Demo</string>
+            <key>CFBundleDisplayName</key><string>Demo App</string>
+            <key>CFBundleShortVersionString</key><string>1.2.0</string>
+            <key>CFBundleVersion</key><string>42</string>
+            <key>MinimumOSVersion</key><string>17.0</string>
+            <key>UIDeviceFamily</key><array><integer>1</integer><integer>2</integer><integer>5</integer></array>
+            <key>CFBundleURLTypes</key>
+            <array>
+                <dict>
+                    <key>CFBundleURLName</key><string>com.example.demo</string>
+                    <key>CFBundleURLSchemes</key><array><string>demo</string><string>demo-dev</string></array>
+                </dict>
+                <dict>
+                    <key>CFBundleURLSchemes</key><array><string>fb123</string></array>
+                </dict>
+            </array>
+            "#,
+        ))
+        .unwrap();
+
+        assert_eq!(info.bundle_id, "com.example.Demo");
+        assert_eq!(info.display_name.as_deref(), Some("Demo App"));
+        assert_eq!(info.version.as_deref(), Some("1.2.0"));
+        assert_eq!(info.build_number.as_deref(), Some("42"));
+        assert_eq!(info.minimum_os_version.as_deref(), Some("17.0"));
+        assert_eq!(info.url_schemes, ["demo", "demo-dev", "fb123"]);
+        // Unknown families are skipped
+        assert_eq!(info.device_families, [DeviceFamily::Iphone, DeviceFamily::Ipad]);
+        assert!(info.extensions.is_empty());
+    }
+
+    #[test]
+    fn app_info_falls_back_and_tolerates_missing_keys() {
+        let info = AppInfo::from_info_plist(&plist(
+            "<key>CFBundleIdentifier</key><string>com.example.Demo</string>\
+             <key>CFBundleName</key><string>Demo</string>\
+             <key>CFBundleVersion</key><integer>42</integer>",
+        ))
+        .unwrap();
+        assert_eq!(info.display_name.as_deref(), Some("Demo"));
+        // Values of the wrong type are ignored rather than guessed at
+        assert_eq!(info.build_number, None);
+        assert_eq!(info.version, None);
+        assert!(info.url_schemes.is_empty());
+        assert!(info.device_families.is_empty());
+
+        assert!(AppInfo::from_info_plist(&plist("<key>CFBundleName</key><string>Demo</string>")).is_none());
+        assert!(AppInfo::from_info_plist(&PlistValue::Array(Vec::new())).is_none());
+    }
+
+    #[test]
+    fn rejects_reserved_extra_args() {
+        let check = |args: &[&str]| {
+            validate_extra_args(&args.iter().map(|a| a.to_string()).collect::<Vec<_>>())
+        };
+        assert!(check(&["-quiet", "-destination", "platform=iOS Simulator,name=iPhone 15"]).is_ok());
+        assert!(check(&["-c", "release"]).is_ok());
+        for option in ["--scratch-path", "--build-path", "--package-path", "-derivedDataPath"] {
+            assert!(check(&[option, "/tmp/elsewhere"]).is_err(), "{} accepted", option);
+        }
+        assert!(check(&["--scratch-path=/tmp/elsewhere"]).is_err());
+        assert!(check(&["-DERIVEDDATAPATH", "/tmp/elsewhere"]).is_err());
+    }
+
+    #[test]
+    fn reads_extension_info() {
+        let extension = AppExtensionInfo::from_info_plist(
+            "Widget.appex",
+            &plist(
+                "<key>CFBundleIdentifier</key><string>com.example.Demo.Widget</string>\
+                 <key>NSExtension</key><dict>\
+                 <key>NSExtensionPointIdentifier</key><string>com.apple.widgetkit-extension</string>\
+                 </dict>",
+            ),
+        );
+        assert_eq!(extension.name, "Widget.appex");
+        assert_eq!(extension.bundle_id.as_deref(), Some("com.example.Demo.Widget"));
+        assert_eq!(extension.extension_point.as_deref(), Some("com.apple.widgetkit-extension"));
+    }
 }
@@ -0,0 +1,124 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::path::{Component, Path, PathBuf};
+
+/// App container to access (`simctl get_app_container`)
+#[derive(Debug, Clone, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub enum ContainerType {
+    /// The installed .app bundle
+    App,
+    /// The app's sandbox (Documents, Library, tmp)
+    #[default]
+    Data,
+    /// A shared app group container (e.g., "group.com.example.shared")
+    Group(String),
+}
+
+impl ContainerType {
+    /// Container argument as understood by simctl
+    pub fn as_str(&self) -> &str {
+        match self {
+            Self::App => "app",
+            Self::Data => "data",
+            Self::Group(id) => id,
+        }
+    }
+}
+
+impl std::fmt::Display for ContainerType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl std::str::FromStr for ContainerType {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "app" => Ok(Self::App),
+            "data" => Ok(Self::Data),
+            _ if s.starts_with("group.") && s.len() > "group.".len() => {
+                Ok(Self::Group(s.to_string()))
+            }
+            _ => Err(format!(
+                "unknown container '{}' (expected app, data or a group identifier like group.com.example)",
+                s
+            )),
+        }
+    }
+}
+
+impl TryFrom<String> for ContainerType {
+    type Error = String;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        value.parse()
+    }
+}
+
+impl From<ContainerType> for String {
+    fn from(value: ContainerType) -> Self {
+        value.as_str().to_string()
+    }
+}
+
+/// Query parameters selecting a path inside an app container
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ContainerQuery {
+    #[serde(default)]
+    pub container: ContainerType,
+    /// Path relative to the container root (empty for the root)
+    #[serde(default)]
+    pub path: String,
+}
+
+/// File or directory inside an app container
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ContainerEntry {
+    pub name: String,
+    pub is_dir: bool,
+    pub size_bytes: u64,
+    pub modified: Option<DateTime<Utc>>,
+}
+
+/// Directory listing of an app container
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ListContainerResponse {
+    pub bundle_id: String,
+    pub container: ContainerType,
+    pub path: String,
+    pub entries: Vec<ContainerEntry>,
+}
+
+/// Response after uploading a file into an app container
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UploadContainerResponse {
+    pub path: String,
+    pub size_bytes: u64,
+    /// Whether an existing file was replaced
+    pub replaced: bool,
+}
+
+/// Normalize a client-supplied container path into a relative path,
+/// rejecting absolute paths and any `..` component.
+pub fn sanitize_container_path(path: &str) -> Result<PathBuf, String> {
+    let mut clean = PathBuf::new();
+    for component in Path::new(path).components() {
+        match component {
+            Component::Normal(part) => clean.push(part),
+            Component::CurDir => {}
+            Component::ParentDir => {
+                return Err(format!("path '{}' must not contain '..'", path));
+            }
+            Component::RootDir | Component::Prefix(_) => {
+                return Err(format!(
+                    "path '{}' must be relative to the container root",
+                    path
+                ));
+            }
+        }
+    }
+    Ok(clean)
+}
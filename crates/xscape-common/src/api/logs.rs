@@ -1,5 +1,6 @@
 use serde::{Deserialize, Serialize};
 use chrono::{DateTime, Utc};
+use uuid::Uuid;
 
 /// Log message streamed from agent
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -24,6 +25,9 @@ pub enum LogMessage {
         timestamp: DateTime<Utc>,
         event: SystemEventType,
         message: String,
+        /// Run session the event belongs to (app events only)
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        session_id: Option<Uuid>,
     },
     /// Build phase progress
     BuildProgress {
@@ -84,6 +88,16 @@ impl LogMessage {
             timestamp: Utc::now(),
             event,
             message: message.into(),
+            session_id: None,
+        }
+    }
+
+    pub fn session_event(event: SystemEventType, session_id: Uuid, message: impl Into<String>) -> Self {
+        Self::SystemEvent {
+            timestamp: Utc::now(),
+            event,
+            message: message.into(),
+            session_id: Some(session_id),
         }
     }
 
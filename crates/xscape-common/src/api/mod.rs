@@ -1,10 +1,16 @@
+pub mod appearance;
+pub mod archive;
 pub mod build;
+pub mod container;
 pub mod health;
 pub mod logs;
 pub mod project;
 pub mod simulator;
 
+pub use appearance::*;
+pub use archive::*;
 pub use build::*;
+pub use container::*;
 pub use health::*;
 pub use logs::*;
 pub use project::*;
@@ -32,3 +32,31 @@ pub struct ProjectInfo {
     pub path: String,
     pub synced_at: chrono::DateTime<chrono::Utc>,
 }
+
+/// Schemes, targets and configurations of a synced project, as reported by
+/// `xcodebuild -list`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProjectInfoResponse {
+    pub project_id: Uuid,
+    /// The .xcodeproj, .xcworkspace or Package.swift that was listed,
+    /// relative to the project
+    pub project_file: String,
+    pub is_workspace: bool,
+    /// Whether the project is a Swift package without an Xcode project
+    #[serde(default)]
+    pub is_package: bool,
+    pub schemes: Vec<String>,
+    /// Targets of the project, or of every project in the workspace
+    pub targets: Vec<String>,
+    /// Build configurations (e.g., Debug, Release, Staging)
+    pub configurations: Vec<String>,
+}
+
+/// Query for project info
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ProjectInfoQuery {
+    /// Relative path to .xcodeproj, .xcworkspace or Package.swift (defaults to
+    /// the one a build would use)
+    #[serde(default)]
+    pub project_file: Option<String>,
+}
@@ -1,7 +1,12 @@
+use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 use std::collections::HashMap;
 
+use crate::crash::CrashSummary;
+use crate::location::Coordinate;
+use crate::platform::Platform;
+
 /// A simulator device
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SimulatorDevice {
@@ -21,6 +26,13 @@ pub struct SimulatorDevice {
     pub is_available: bool,
 }
 
+impl SimulatorDevice {
+    /// Platform of the device's runtime
+    pub fn platform(&self) -> Option<Platform> {
+        Platform::from_runtime_identifier(&self.runtime_identifier)
+    }
+}
+
 /// Simulator device state
 #[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
 #[serde(rename_all = "lowercase")]
@@ -59,11 +71,31 @@ pub struct ListSimulatorsResponse {
     pub runtimes: Vec<SimulatorRuntime>,
 }
 
+impl ListSimulatorsResponse {
+    /// Keep only the devices and runtimes of one platform
+    pub fn retain_platform(&mut self, platform: Platform) {
+        self.devices.retain(|d| d.platform() == Some(platform));
+        self.runtimes
+            .retain(|r| Platform::from_runtime_identifier(&r.identifier) == Some(platform));
+    }
+}
+
+/// Query parameters for listing simulators
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ListSimulatorsQuery {
+    /// Only list simulators of this platform
+    #[serde(default)]
+    pub platform: Option<Platform>,
+}
+
 /// Request to boot a simulator
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct BootSimulatorRequest {
     /// Device UDID to boot
     pub device_udid: String,
+    /// PEM root certificates to trust once booted
+    #[serde(default)]
+    pub root_certificates: Vec<String>,
 }
 
 /// Response after booting simulator
@@ -73,6 +105,21 @@ pub struct BootSimulatorResponse {
     pub state: SimulatorState,
 }
 
+/// Request to install a build without launching it
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct InstallAppRequest {
+    pub build_id: Uuid,
+    pub device_udid: String,
+}
+
+/// Response after installing an app
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct InstallAppResponse {
+    pub build_id: Uuid,
+    pub bundle_id: String,
+    pub device_udid: String,
+}
+
 /// Request to run an app in simulator
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct RunAppRequest {
@@ -89,6 +136,20 @@ pub struct RunAppRequest {
     /// Wait for app to exit (vs launch and return immediately)
     #[serde(default)]
     pub wait_for_exit: bool,
+    /// Privacy permissions to apply before launch. `all` is applied first,
+    /// so other entries override it (e.g., revoke all, but grant photos)
+    #[serde(default)]
+    pub permissions: HashMap<PrivacyService, PrivacyAction>,
+    /// Locale override (e.g., "de_DE"), passed as -AppleLocale
+    #[serde(default)]
+    pub locale: Option<String>,
+    /// Preferred languages (e.g., ["de"]), passed as -AppleLanguages.
+    /// Derived from `locale` if empty.
+    #[serde(default)]
+    pub languages: Vec<String>,
+    /// PEM root certificates to trust if the device has to be booted
+    #[serde(default)]
+    pub root_certificates: Vec<String>,
 }
 
 /// Response after launching app
@@ -104,9 +165,353 @@ pub struct RunAppResponse {
     pub device_udid: String,
 }
 
+/// An app launched via /simulator/run
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SessionInfo {
+    pub session_id: Uuid,
+    pub build_id: Uuid,
+    pub device_udid: String,
+    pub bundle_id: String,
+    pub pid: Option<u32>,
+    pub started_at: DateTime<Utc>,
+}
+
+/// Crash report for a run session
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CrashReportResponse {
+    pub session_id: Uuid,
+    pub bundle_id: String,
+    pub device_udid: String,
+    /// Path of the .ips file on the agent
+    pub report_path: String,
+    pub summary: CrashSummary,
+    /// Full .ips report
+    pub report: String,
+    /// Images whose frames were symbolicated with the build's debug symbols
+    #[serde(default)]
+    pub symbolicated_images: Vec<String>,
+}
+
+/// Query for crash report and symbolication endpoints
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SymbolicateQuery {
+    /// Local checkout path that replaces the agent's project directory in
+    /// source file paths
+    #[serde(default)]
+    pub source_root: Option<String>,
+}
+
+/// Response from symbolicating an uploaded .ips report
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SymbolicateResponse {
+    pub build_id: Uuid,
+    pub bundle_id: Option<String>,
+    pub summary: CrashSummary,
+    /// Symbolicated .ips report
+    pub report: String,
+    /// Images whose frames were symbolicated
+    pub symbolicated_images: Vec<String>,
+}
+
+/// Response listing run sessions (most recent first)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ListSessionsResponse {
+    pub sessions: Vec<SessionInfo>,
+}
+
 /// Request to shutdown a simulator
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ShutdownSimulatorRequest {
     /// Device UDID to shutdown
     pub device_udid: String,
 }
+
+/// Request to set a fixed simulated location
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SetLocationRequest {
+    pub latitude: f64,
+    pub longitude: f64,
+}
+
+/// Request to play back a route on a simulator
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PlayRouteRequest {
+    /// Waypoints in travel order, at least 2
+    pub waypoints: Vec<Coordinate>,
+    /// Travel speed in meters per second
+    #[serde(default = "default_route_speed")]
+    pub speed_mps: f64,
+}
+
+fn default_route_speed() -> f64 {
+    10.0
+}
+
+/// Response after changing a simulator's location
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LocationResponse {
+    pub device_udid: String,
+    /// Current fixed location (None when cleared or playing a route)
+    pub location: Option<Coordinate>,
+    /// Number of waypoints in the route being played
+    #[serde(default)]
+    pub route_waypoints: usize,
+}
+
+/// Request to deliver a push notification to an app
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PushNotificationRequest {
+    /// Bundle identifier of the target app
+    pub bundle_id: String,
+    /// APNs JSON payload (must contain an "aps" dictionary)
+    pub payload: serde_json::Value,
+}
+
+/// Response after delivering a push notification
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PushNotificationResponse {
+    pub device_udid: String,
+    pub bundle_id: String,
+}
+
+/// Request to open a URL in a booted simulator
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OpenUrlRequest {
+    /// URL to open (custom scheme or universal link)
+    pub url: String,
+    /// Build to install and launch first if its app isn't installed
+    #[serde(default)]
+    pub build_id: Option<Uuid>,
+}
+
+/// Response after opening a URL
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OpenUrlResponse {
+    pub device_udid: String,
+    pub url: String,
+    /// Whether the app had to be installed and launched first
+    #[serde(default)]
+    pub launched_app: bool,
+}
+
+/// Privacy-protected service managed by `simctl privacy`
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash)]
+#[serde(rename_all = "kebab-case")]
+pub enum PrivacyService {
+    /// Apply the action to all services
+    All,
+    Calendar,
+    ContactsLimited,
+    Contacts,
+    /// Location while in use
+    Location,
+    LocationAlways,
+    /// Add-only photo library access
+    PhotosAdd,
+    Photos,
+    MediaLibrary,
+    Microphone,
+    Motion,
+    Reminders,
+    Siri,
+}
+
+impl PrivacyService {
+    pub const ALL: [PrivacyService; 13] = [
+        Self::All,
+        Self::Calendar,
+        Self::ContactsLimited,
+        Self::Contacts,
+        Self::Location,
+        Self::LocationAlways,
+        Self::PhotosAdd,
+        Self::Photos,
+        Self::MediaLibrary,
+        Self::Microphone,
+        Self::Motion,
+        Self::Reminders,
+        Self::Siri,
+    ];
+
+    /// Service name as understood by simctl
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::All => "all",
+            Self::Calendar => "calendar",
+            Self::ContactsLimited => "contacts-limited",
+            Self::Contacts => "contacts",
+            Self::Location => "location",
+            Self::LocationAlways => "location-always",
+            Self::PhotosAdd => "photos-add",
+            Self::Photos => "photos",
+            Self::MediaLibrary => "media-library",
+            Self::Microphone => "microphone",
+            Self::Motion => "motion",
+            Self::Reminders => "reminders",
+            Self::Siri => "siri",
+        }
+    }
+}
+
+impl std::fmt::Display for PrivacyService {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl std::str::FromStr for PrivacyService {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::ALL
+            .into_iter()
+            .find(|service| service.as_str() == s.to_lowercase())
+            .ok_or_else(|| {
+                let names: Vec<_> = Self::ALL.iter().map(|s| s.as_str()).collect();
+                format!("unknown privacy service '{}' (expected one of: {})", s, names.join(", "))
+            })
+    }
+}
+
+/// Action to apply to a privacy service
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum PrivacyAction {
+    /// Grant access without prompting
+    Grant,
+    /// Deny access without prompting
+    Revoke,
+    /// Prompt again on next use
+    Reset,
+}
+
+impl std::fmt::Display for PrivacyAction {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PrivacyAction::Grant => write!(f, "grant"),
+            PrivacyAction::Revoke => write!(f, "revoke"),
+            PrivacyAction::Reset => write!(f, "reset"),
+        }
+    }
+}
+
+/// Request to change privacy permissions on a simulator
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PrivacyRequest {
+    pub action: PrivacyAction,
+    pub service: PrivacyService,
+    /// Target app (optional for reset, which then applies to all apps)
+    #[serde(default)]
+    pub bundle_id: Option<String>,
+}
+
+/// Response after changing privacy permissions
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PrivacyResponse {
+    pub device_udid: String,
+    pub action: PrivacyAction,
+    pub service: PrivacyService,
+    pub bundle_id: Option<String>,
+}
+
+/// Simulator pasteboard contents (plain text)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PasteboardContent {
+    pub text: String,
+}
+
+/// Kind of media that can be added to a simulator with `simctl addmedia`
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum MediaKind {
+    Photo,
+    Video,
+    Contact,
+}
+
+impl MediaKind {
+    /// Detect the media kind from a file extension
+    pub fn from_file_name(file_name: &str) -> Option<Self> {
+        let ext = std::path::Path::new(file_name)
+            .extension()?
+            .to_str()?
+            .to_lowercase();
+        match ext.as_str() {
+            "jpg" | "jpeg" | "png" | "heic" | "heif" | "gif" | "tif" | "tiff" | "bmp" | "webp" => {
+                Some(Self::Photo)
+            }
+            "mov" | "mp4" | "m4v" => Some(Self::Video),
+            "vcf" | "vcard" => Some(Self::Contact),
+            _ => None,
+        }
+    }
+}
+
+impl std::fmt::Display for MediaKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Photo => write!(f, "photo"),
+            Self::Video => write!(f, "video"),
+            Self::Contact => write!(f, "contact"),
+        }
+    }
+}
+
+/// File added to a simulator's Photos library or Contacts
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AddedMedia {
+    pub file_name: String,
+    pub kind: MediaKind,
+    pub size_bytes: u64,
+}
+
+/// Response after adding media to a simulator
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AddMediaResponse {
+    pub device_udid: String,
+    pub added: Vec<AddedMedia>,
+}
+
+/// Keychain operation (`simctl keychain`)
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+pub enum KeychainAction {
+    /// Add a certificate as a trusted root
+    AddRootCert,
+    /// Add a certificate without trusting it
+    AddCert,
+    /// Remove all keychain items
+    Reset,
+}
+
+impl KeychainAction {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::AddRootCert => "add-root-cert",
+            Self::AddCert => "add-cert",
+            Self::Reset => "reset",
+        }
+    }
+}
+
+impl std::fmt::Display for KeychainAction {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Request to change a simulator keychain
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct KeychainRequest {
+    pub action: KeychainAction,
+    /// PEM certificate (required for add-root-cert and add-cert)
+    #[serde(default)]
+    pub certificate: Option<String>,
+}
+
+/// Response after changing a simulator keychain
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct KeychainResponse {
+    pub device_udid: String,
+    pub action: KeychainAction,
+}
@@ -0,0 +1,167 @@
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use thiserror::Error;
+
+const PEM_BEGIN: &str = "-----BEGIN ";
+const PEM_END: &str = "-----END ";
+
+/// Errors from certificate parsing
+#[derive(Debug, Error)]
+pub enum CertificateError {
+    #[error("No certificate found")]
+    Empty,
+
+    #[error("Invalid PEM: {0}")]
+    InvalidPem(String),
+
+    #[error("Expected a CERTIFICATE block, found {0}")]
+    NotACertificate(String),
+
+    #[error("Invalid DER certificate: {0}")]
+    InvalidDer(String),
+}
+
+/// Parse one or more X.509 certificates from PEM or DER data.
+/// Returns the DER encoding of each certificate.
+pub fn parse_certificates(data: &[u8]) -> Result<Vec<Vec<u8>>, CertificateError> {
+    if data.iter().all(|b| b.is_ascii_whitespace()) {
+        return Err(CertificateError::Empty);
+    }
+
+    match std::str::from_utf8(data) {
+        Ok(text) if text.contains(PEM_BEGIN) => parse_pem(text),
+        _ => {
+            validate_der(data)?;
+            Ok(vec![data.to_vec()])
+        }
+    }
+}
+
+/// Encode a DER certificate as PEM
+pub fn certificate_to_pem(der: &[u8]) -> String {
+    let encoded = STANDARD.encode(der);
+    let mut pem = String::from("-----BEGIN CERTIFICATE-----\n");
+    for line in encoded.as_bytes().chunks(64) {
+        pem.push_str(std::str::from_utf8(line).unwrap_or_default());
+        pem.push('\n');
+    }
+    pem.push_str("-----END CERTIFICATE-----\n");
+    pem
+}
+
+fn parse_pem(text: &str) -> Result<Vec<Vec<u8>>, CertificateError> {
+    let mut certificates = Vec::new();
+    let mut lines = text.lines().map(str::trim);
+
+    while let Some(line) = lines.next() {
+        let Some(label) = pem_label(line, PEM_BEGIN) else {
+            continue;
+        };
+        if label != "CERTIFICATE" {
+            return Err(CertificateError::NotACertificate(label.to_string()));
+        }
+
+        let mut body = String::new();
+        loop {
+            let line = lines.next().ok_or_else(|| {
+                CertificateError::InvalidPem("missing END CERTIFICATE line".to_string())
+            })?;
+            if let Some(end) = pem_label(line, PEM_END) {
+                if end != label {
+                    return Err(CertificateError::InvalidPem(format!(
+                        "BEGIN {} closed by END {}",
+                        label, end
+                    )));
+                }
+                break;
+            }
+            // Skip RFC 1421 style headers
+            if !line.contains(':') {
+                body.push_str(line);
+            }
+        }
+
+        let der = STANDARD
+            .decode(body.as_bytes())
+            .map_err(|e| CertificateError::InvalidPem(e.to_string()))?;
+        validate_der(&der)?;
+        certificates.push(der);
+    }
+
+    if certificates.is_empty() {
+        return Err(CertificateError::Empty);
+    }
+
+    Ok(certificates)
+}
+
+fn pem_label<'a>(line: &'a str, prefix: &str) -> Option<&'a str> {
+    line.strip_prefix(prefix)?.strip_suffix("-----")
+}
+
+/// Check the outer structure of an X.509 certificate:
+/// SEQUENCE { tbsCertificate SEQUENCE, signatureAlgorithm SEQUENCE, signature BIT STRING }
+fn validate_der(der: &[u8]) -> Result<(), CertificateError> {
+    if der.first() != Some(&0x30) {
+        return Err(CertificateError::InvalidDer(
+            "not an ASN.1 SEQUENCE".to_string(),
+        ));
+    }
+    let (_, content, rest) = read_tlv(der)?;
+    if !rest.is_empty() {
+        return Err(CertificateError::InvalidDer(
+            "trailing data after certificate".to_string(),
+        ));
+    }
+
+    let mut content = content;
+    for (expected, name) in [
+        (0x30, "tbsCertificate"),
+        (0x30, "signatureAlgorithm"),
+        (0x03, "signature"),
+    ] {
+        let (tag, _, rest) = read_tlv(content)?;
+        if tag != expected {
+            return Err(CertificateError::InvalidDer(format!("malformed {}", name)));
+        }
+        content = rest;
+    }
+    if !content.is_empty() {
+        return Err(CertificateError::InvalidDer(
+            "unexpected fields after signature".to_string(),
+        ));
+    }
+
+    Ok(())
+}
+
+/// Read one DER tag-length-value, returning (tag, value, remaining input)
+fn read_tlv(data: &[u8]) -> Result<(u8, &[u8], &[u8]), CertificateError> {
+    let truncated = || CertificateError::InvalidDer("truncated data".to_string());
+
+    let (&tag, data) = data.split_first().ok_or_else(truncated)?;
+    let (&first, data) = data.split_first().ok_or_else(truncated)?;
+
+    let (len, data) = if first < 0x80 {
+        (first as usize, data)
+    } else {
+        let count = (first & 0x7f) as usize;
+        if count == 0 || count > 4 {
+            return Err(CertificateError::InvalidDer(
+                "unsupported length encoding".to_string(),
+            ));
+        }
+        if data.len() < count {
+            return Err(truncated());
+        }
+        let len = data[..count]
+            .iter()
+            .fold(0usize, |acc, &b| (acc << 8) | b as usize);
+        (len, &data[count..])
+    };
+
+    if data.len() < len {
+        return Err(truncated());
+    }
+    Ok((tag, &data[..len], &data[len..]))
+}
@@ -192,6 +192,9 @@ pub struct SimulatorConfig {
     /// Preferred iOS runtime version
     #[serde(default)]
     pub preferred_runtime: Option<String>,
+    /// Root certificates (PEM or DER files) to trust whenever a device is booted
+    #[serde(default)]
+    pub root_certificates: Vec<PathBuf>,
 }
 
 impl Default for SimulatorConfig {
@@ -199,6 +202,7 @@ impl Default for SimulatorConfig {
         Self {
             preferred_device: default_device(),
             preferred_runtime: None,
+            root_certificates: Vec::new(),
         }
     }
 }
@@ -318,12 +322,25 @@ pub struct StorageConfig {
     /// Directory for logs
     #[serde(default = "default_logs_dir")]
     pub logs_dir: PathBuf,
+    /// Installed pods, Swift package checkouts and generated Xcode projects,
+    /// keyed by lockfile or manifest hash and shared by all projects
+    #[serde(default = "default_dependencies_dir")]
+    pub dependencies_dir: PathBuf,
     /// Maximum number of projects to cache
     #[serde(default = "default_max_projects")]
     pub max_projects: usize,
     /// Clean up projects older than this (hours)
     #[serde(default = "default_cleanup_hours")]
     pub cleanup_after_hours: u32,
+    /// Finished builds whose outputs (copied app, dSYMs, archive, log) are
+    /// kept in `logs_dir`; the oldest are forgotten and deleted past this
+    /// many (0 keeps them all)
+    #[serde(default = "default_max_builds")]
+    pub max_builds: usize,
+    /// Evict dependency cache entries no build has used for this many days
+    /// (0 keeps them forever)
+    #[serde(default = "default_dependencies_max_age_days")]
+    pub dependencies_max_age_days: u32,
 }
 
 impl Default for StorageConfig {
@@ -331,8 +348,11 @@ impl Default for StorageConfig {
         Self {
             projects_dir: default_projects_dir(),
             logs_dir: default_logs_dir(),
+            dependencies_dir: default_dependencies_dir(),
             max_projects: default_max_projects(),
             cleanup_after_hours: default_cleanup_hours(),
+            max_builds: default_max_builds(),
+            dependencies_max_age_days: default_dependencies_max_age_days(),
         }
     }
 }
@@ -345,6 +365,10 @@ fn default_logs_dir() -> PathBuf {
     PathBuf::from("/var/xcode-agent/logs")
 }
 
+fn default_dependencies_dir() -> PathBuf {
+    PathBuf::from("/var/xcode-agent/dependencies")
+}
+
 fn default_max_projects() -> usize {
     10
 }
@@ -353,6 +377,14 @@ fn default_cleanup_hours() -> u32 {
     24
 }
 
+fn default_max_builds() -> usize {
+    20
+}
+
+fn default_dependencies_max_age_days() -> u32 {
+    30
+}
+
 /// Xcode configuration for agent
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
 pub struct XcodeConfig {
@@ -370,9 +402,13 @@ pub struct AgentSimulatorConfig {
     /// Auto-boot simulator when needed
     #[serde(default = "default_true")]
     pub auto_boot: bool,
-    /// Shutdown simulator after idle for this many minutes
+    /// Shutdown simulator after idle for this many minutes (0 disables)
     #[serde(default = "default_idle_shutdown")]
     pub shutdown_idle_after_minutes: u32,
+    /// Maximum number of simultaneously booted simulators (0, the default,
+    /// for no limit). The least recently used device is shut down to make room.
+    #[serde(default = "default_max_booted")]
+    pub max_booted_devices: u32,
 }
 
 impl Default for AgentSimulatorConfig {
@@ -380,6 +416,7 @@ impl Default for AgentSimulatorConfig {
         Self {
             auto_boot: true,
             shutdown_idle_after_minutes: default_idle_shutdown(),
+            max_booted_devices: default_max_booted(),
         }
     }
 }
@@ -387,3 +424,7 @@ impl Default for AgentSimulatorConfig {
 fn default_idle_shutdown() -> u32 {
     30
 }
+
+fn default_max_booted() -> u32 {
+    0
+}
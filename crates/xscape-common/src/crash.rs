@@ -0,0 +1,345 @@
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use thiserror::Error;
+
+/// Errors from crash report parsing
+#[derive(Debug, Error)]
+pub enum CrashReportError {
+    #[error("Crash report is empty")]
+    Empty,
+
+    #[error("Invalid crash report header: {0}")]
+    InvalidHeader(serde_json::Error),
+
+    #[error("Invalid crash report body: {0}")]
+    InvalidBody(serde_json::Error),
+}
+
+/// First line of an `.ips` file
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct IpsHeader {
+    #[serde(default)]
+    pub app_name: Option<String>,
+    #[serde(rename = "bundleID", default)]
+    pub bundle_id: Option<String>,
+    #[serde(default)]
+    pub app_version: Option<String>,
+    #[serde(default)]
+    pub build_version: Option<String>,
+    #[serde(default)]
+    pub bug_type: Option<String>,
+    #[serde(default)]
+    pub timestamp: Option<String>,
+    #[serde(default)]
+    pub os_version: Option<String>,
+}
+
+/// Crash report body (the JSON document after the header line)
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct IpsBody {
+    #[serde(default)]
+    pub pid: Option<u32>,
+    #[serde(default)]
+    pub proc_name: Option<String>,
+    #[serde(default)]
+    pub exception: Option<IpsException>,
+    #[serde(default)]
+    pub termination: Option<IpsTermination>,
+    #[serde(default)]
+    pub faulting_thread: Option<usize>,
+    #[serde(default)]
+    pub threads: Vec<IpsThread>,
+    #[serde(default)]
+    pub used_images: Vec<IpsImage>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct IpsException {
+    #[serde(rename = "type", default)]
+    pub exception_type: Option<String>,
+    #[serde(default)]
+    pub signal: Option<String>,
+    #[serde(default)]
+    pub subtype: Option<String>,
+    #[serde(default)]
+    pub codes: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct IpsTermination {
+    #[serde(default)]
+    pub namespace: Option<String>,
+    #[serde(default)]
+    pub indicator: Option<String>,
+    #[serde(default)]
+    pub reasons: Vec<String>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct IpsThread {
+    #[serde(default)]
+    pub triggered: bool,
+    #[serde(default)]
+    pub name: Option<String>,
+    #[serde(default)]
+    pub queue: Option<String>,
+    #[serde(default)]
+    pub frames: Vec<IpsFrame>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct IpsFrame {
+    #[serde(default)]
+    pub image_index: usize,
+    /// Offset of the frame address from the image load address
+    #[serde(default)]
+    pub image_offset: u64,
+    #[serde(default)]
+    pub symbol: Option<String>,
+    /// Offset of the frame address from the start of `symbol`
+    #[serde(default)]
+    pub symbol_location: Option<u64>,
+    #[serde(default)]
+    pub source_file: Option<String>,
+    #[serde(default)]
+    pub source_line: Option<u32>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct IpsImage {
+    /// Load address
+    #[serde(default)]
+    pub base: u64,
+    #[serde(default)]
+    pub size: u64,
+    #[serde(default)]
+    pub uuid: Option<String>,
+    #[serde(default)]
+    pub name: Option<String>,
+    #[serde(default)]
+    pub path: Option<String>,
+    #[serde(default)]
+    pub arch: Option<String>,
+}
+
+/// Function and source location resolved for a frame address
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ResolvedSymbol {
+    pub function: String,
+    pub file: Option<String>,
+    pub line: Option<u32>,
+}
+
+impl ResolvedSymbol {
+    /// Parse one line of `atos -fullPath` output, e.g.
+    /// "ContentView.crash() (in MyApp) (/src/MyApp/ContentView.swift:12)".
+    /// Returns None for addresses atos could not resolve.
+    pub fn from_atos_line(line: &str) -> Option<Self> {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with("0x") {
+            return None;
+        }
+
+        let (function, rest) = match line.find(" (in ") {
+            Some(idx) => (&line[..idx], &line[idx + 5..]),
+            None => (line, ""),
+        };
+
+        // Image name is followed by an optional "(file:line)"
+        let location = rest
+            .find(") (")
+            .map(|idx| &rest[idx + 3..])
+            .and_then(|loc| loc.strip_suffix(')'));
+        let (file, line) = match location.and_then(|loc| loc.rsplit_once(':')) {
+            Some((file, line)) => match line.parse::<u32>() {
+                Ok(line) => (Some(file.to_string()), Some(line)),
+                Err(_) => (None, None),
+            },
+            None => (None, None),
+        };
+
+        Some(Self {
+            function: function.trim().to_string(),
+            file,
+            line,
+        })
+    }
+}
+
+/// Parsed `.ips` crash report (JSON header line followed by a JSON body)
+#[derive(Debug, Clone)]
+pub struct CrashReport {
+    pub header: IpsHeader,
+    pub body: IpsBody,
+}
+
+/// Short description of a crash
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct CrashSummary {
+    /// e.g. "EXC_BAD_ACCESS"
+    pub exception_type: Option<String>,
+    /// e.g. "SIGSEGV"
+    pub signal: Option<String>,
+    /// Termination reason(s) reported by the OS
+    pub termination_reason: Option<String>,
+    /// Index of the crashing thread
+    pub crashed_thread: Option<usize>,
+    /// Name or dispatch queue of the crashing thread
+    pub crashed_thread_name: Option<String>,
+    /// Formatted frames of the crashing thread
+    pub frames: Vec<String>,
+}
+
+impl CrashReport {
+    /// Parse the contents of an `.ips` file
+    pub fn parse(text: &str) -> Result<Self, CrashReportError> {
+        let text = text.trim_start();
+        if text.is_empty() {
+            return Err(CrashReportError::Empty);
+        }
+
+        let (header, body) = text.split_once('\n').unwrap_or((text, ""));
+        let header: IpsHeader =
+            serde_json::from_str(header).map_err(CrashReportError::InvalidHeader)?;
+        let body: IpsBody = if body.trim().is_empty() {
+            IpsBody::default()
+        } else {
+            serde_json::from_str(body).map_err(CrashReportError::InvalidBody)?
+        };
+
+        Ok(Self { header, body })
+    }
+
+    /// Frame image offsets of all threads, grouped by image index
+    pub fn frame_offsets(&self) -> BTreeMap<usize, Vec<u64>> {
+        let mut offsets: BTreeMap<usize, Vec<u64>> = BTreeMap::new();
+        for frame in self.body.threads.iter().flat_map(|t| &t.frames) {
+            offsets.entry(frame.image_index).or_default().push(frame.image_offset);
+        }
+        for list in offsets.values_mut() {
+            list.sort_unstable();
+            list.dedup();
+        }
+        offsets
+    }
+
+    /// Index of the thread that crashed
+    pub fn crashed_thread(&self) -> Option<usize> {
+        self.body
+            .faulting_thread
+            .or_else(|| self.body.threads.iter().position(|t| t.triggered))
+    }
+
+    /// One-line description, e.g. "EXC_BAD_ACCESS (SIGSEGV)"
+    pub fn exception_description(&self) -> String {
+        let exception = self.body.exception.as_ref();
+        let kind = exception
+            .and_then(|e| e.exception_type.as_deref())
+            .unwrap_or("Unknown exception");
+        match exception.and_then(|e| e.signal.as_deref()) {
+            Some(signal) => format!("{} ({})", kind, signal),
+            None => kind.to_string(),
+        }
+    }
+
+    /// Format a frame as "image  symbol + offset (File.swift:line)"
+    pub fn format_frame(&self, frame: &IpsFrame) -> String {
+        let image = self.body.used_images.get(frame.image_index);
+        let image_name = image.and_then(|i| i.name.as_deref()).unwrap_or("???");
+
+        let location = match (&frame.symbol, frame.symbol_location) {
+            (Some(symbol), Some(offset)) => format!("{} + {}", symbol, offset),
+            (Some(symbol), None) => symbol.clone(),
+            _ => {
+                let base = image.map(|i| i.base).unwrap_or(0);
+                format!("0x{:x}", base + frame.image_offset)
+            }
+        };
+
+        match (&frame.source_file, frame.source_line) {
+            (Some(file), Some(line)) => format!("{:<24} {} ({}:{})", image_name, location, file, line),
+            _ => format!("{:<24} {}", image_name, location),
+        }
+    }
+
+    pub fn summary(&self) -> CrashSummary {
+        let crashed_thread = self.crashed_thread();
+        let thread = crashed_thread.and_then(|i| self.body.threads.get(i));
+
+        let termination_reason = self.body.termination.as_ref().and_then(|t| {
+            if !t.reasons.is_empty() {
+                Some(t.reasons.join("; "))
+            } else {
+                t.indicator.clone()
+            }
+        });
+
+        CrashSummary {
+            exception_type: self
+                .body
+                .exception
+                .as_ref()
+                .and_then(|e| e.exception_type.clone()),
+            signal: self.body.exception.as_ref().and_then(|e| e.signal.clone()),
+            termination_reason,
+            crashed_thread,
+            crashed_thread_name: thread.and_then(|t| t.name.clone().or_else(|| t.queue.clone())),
+            frames: thread
+                .map(|t| t.frames.iter().map(|f| self.format_frame(f)).collect())
+                .unwrap_or_default(),
+        }
+    }
+}
+
+/// Rewrite the frames of a raw `.ips` report with resolved symbols, keyed by
+/// (image index, image offset). Fields the typed model doesn't know are kept.
+pub fn symbolicate_report(
+    text: &str,
+    symbols: &HashMap<(usize, u64), ResolvedSymbol>,
+) -> Result<String, CrashReportError> {
+    let text = text.trim_start();
+    let (header, body) = text.split_once('\n').ok_or(CrashReportError::Empty)?;
+    let mut body: serde_json::Value =
+        serde_json::from_str(body).map_err(CrashReportError::InvalidBody)?;
+
+    let threads = body
+        .get_mut("threads")
+        .and_then(|t| t.as_array_mut())
+        .into_iter()
+        .flatten();
+    for thread in threads {
+        let frames = thread
+            .get_mut("frames")
+            .and_then(|f| f.as_array_mut())
+            .into_iter()
+            .flatten();
+        for frame in frames {
+            let Some(frame) = frame.as_object_mut() else {
+                continue;
+            };
+            let image_index = frame.get("imageIndex").and_then(|v| v.as_u64()).unwrap_or(0);
+            let image_offset = frame.get("imageOffset").and_then(|v| v.as_u64()).unwrap_or(0);
+            let Some(symbol) = symbols.get(&(image_index as usize, image_offset)) else {
+                continue;
+            };
+
+            frame.insert("symbol".into(), symbol.function.clone().into());
+            frame.remove("symbolLocation");
+            match (&symbol.file, symbol.line) {
+                (Some(file), Some(line)) => {
+                    frame.insert("sourceFile".into(), file.clone().into());
+                    frame.insert("sourceLine".into(), line.into());
+                }
+                _ => {
+                    frame.remove("sourceFile");
+                    frame.remove("sourceLine");
+                }
+            }
+        }
+    }
+
+    let body = serde_json::to_string_pretty(&body).map_err(CrashReportError::InvalidBody)?;
+    Ok(format!("{}\n{}\n", header.trim_end(), body))
+}
@@ -14,6 +14,9 @@ pub enum IosSimError {
     #[error("Simulator not found: {0}")]
     SimulatorNotFound(String),
 
+    #[error("Simulator is not booted and auto-boot is disabled: {0}")]
+    SimulatorNotBooted(String),
+
     #[error("Simulator runtime not available: {0}")]
     RuntimeNotAvailable(String),
 
@@ -106,6 +109,7 @@ impl From<IosSimError> for ApiError {
             IosSimError::XcodeNotFound => "XCODE_NOT_FOUND",
             IosSimError::XcodeToolsNotInstalled => "XCODE_TOOLS_NOT_INSTALLED",
             IosSimError::SimulatorNotFound(_) => "SIMULATOR_NOT_FOUND",
+            IosSimError::SimulatorNotBooted(_) => "SIMULATOR_NOT_BOOTED",
             IosSimError::RuntimeNotAvailable(_) => "RUNTIME_NOT_AVAILABLE",
             IosSimError::BuildFailed(_) => "BUILD_FAILED",
             IosSimError::ProjectNotFound(_) => "PROJECT_NOT_FOUND",
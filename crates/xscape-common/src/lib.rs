@@ -1,7 +1,19 @@
 pub mod api;
+pub mod certificate;
 pub mod config;
+pub mod crash;
 pub mod error;
+pub mod location;
+pub mod platform;
+pub mod plist;
+pub mod push;
 
 pub use api::*;
+pub use certificate::*;
 pub use config::*;
+pub use crash::*;
 pub use error::*;
+pub use location::*;
+pub use platform::*;
+pub use plist::*;
+pub use push::*;
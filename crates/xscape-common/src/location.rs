@@ -0,0 +1,221 @@
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+/// A geographic coordinate in decimal degrees
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+pub struct Coordinate {
+    pub latitude: f64,
+    pub longitude: f64,
+}
+
+impl Coordinate {
+    pub fn new(latitude: f64, longitude: f64) -> Self {
+        Self {
+            latitude,
+            longitude,
+        }
+    }
+
+    /// Check that latitude and longitude are within valid ranges
+    pub fn validate(&self) -> Result<(), RouteError> {
+        if !(-90.0..=90.0).contains(&self.latitude) || !(-180.0..=180.0).contains(&self.longitude)
+        {
+            return Err(RouteError::InvalidCoordinate(*self));
+        }
+        Ok(())
+    }
+}
+
+impl std::fmt::Display for Coordinate {
+    /// Format as simctl waypoint (`lat,lon`)
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{},{}", self.latitude, self.longitude)
+    }
+}
+
+/// Route file format
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RouteFormat {
+    Gpx,
+    GeoJson,
+}
+
+impl RouteFormat {
+    /// Guess the format from a file name, falling back to the content
+    pub fn detect(file_name: &str, content: &str) -> Option<Self> {
+        let lower = file_name.to_lowercase();
+        if lower.ends_with(".gpx") {
+            return Some(Self::Gpx);
+        }
+        if lower.ends_with(".geojson") || lower.ends_with(".json") {
+            return Some(Self::GeoJson);
+        }
+
+        match content.trim_start().chars().next() {
+            Some('<') => Some(Self::Gpx),
+            Some('{') => Some(Self::GeoJson),
+            _ => None,
+        }
+    }
+}
+
+/// Errors from route parsing
+#[derive(Debug, Error)]
+pub enum RouteError {
+    #[error("Unrecognized route format (expected GPX or GeoJSON)")]
+    UnknownFormat,
+
+    #[error("Invalid GPX: {0}")]
+    Gpx(String),
+
+    #[error("Invalid GeoJSON: {0}")]
+    GeoJson(String),
+
+    #[error("Route needs at least 2 waypoints, found {0}; set a single location instead")]
+    TooShort(usize),
+
+    #[error("Coordinate out of range: {0}")]
+    InvalidCoordinate(Coordinate),
+}
+
+/// Parse a route file, detecting the format from its name and content
+pub fn parse_route(file_name: &str, content: &str) -> Result<Vec<Coordinate>, RouteError> {
+    let waypoints = match RouteFormat::detect(file_name, content) {
+        Some(RouteFormat::Gpx) => parse_gpx(content)?,
+        Some(RouteFormat::GeoJson) => parse_geojson(content)?,
+        None => return Err(RouteError::UnknownFormat),
+    };
+    validate_route(&waypoints)?;
+    Ok(waypoints)
+}
+
+/// Check that a route has somewhere to go (simctl needs two waypoints) and
+/// every waypoint is valid
+pub fn validate_route(waypoints: &[Coordinate]) -> Result<(), RouteError> {
+    if waypoints.len() < 2 {
+        return Err(RouteError::TooShort(waypoints.len()));
+    }
+    waypoints.iter().try_for_each(Coordinate::validate)
+}
+
+/// Parse waypoints from GPX. Track points are preferred, then route
+/// points, then plain waypoints.
+pub fn parse_gpx(content: &str) -> Result<Vec<Coordinate>, RouteError> {
+    let doc = roxmltree::Document::parse(content).map_err(|e| RouteError::Gpx(e.to_string()))?;
+
+    if doc.root_element().tag_name().name() != "gpx" {
+        return Err(RouteError::Gpx("root element is not <gpx>".to_string()));
+    }
+
+    for tag in ["trkpt", "rtept", "wpt"] {
+        let points = doc
+            .descendants()
+            .filter(|n| n.is_element() && n.tag_name().name() == tag)
+            .map(|n| {
+                let attr = |name: &str| -> Result<f64, RouteError> {
+                    n.attribute(name)
+                        .ok_or_else(|| RouteError::Gpx(format!("<{}> missing {} attribute", tag, name)))?
+                        .trim()
+                        .parse()
+                        .map_err(|_| RouteError::Gpx(format!("<{}> has invalid {} attribute", tag, name)))
+                };
+                Ok(Coordinate::new(attr("lat")?, attr("lon")?))
+            })
+            .collect::<Result<Vec<_>, RouteError>>()?;
+
+        if !points.is_empty() {
+            return Ok(points);
+        }
+    }
+
+    Ok(Vec::new())
+}
+
+/// Parse waypoints from GeoJSON. Accepts a FeatureCollection, Feature or
+/// bare geometry; line and point geometries are concatenated in order.
+pub fn parse_geojson(content: &str) -> Result<Vec<Coordinate>, RouteError> {
+    let value: serde_json::Value =
+        serde_json::from_str(content).map_err(|e| RouteError::GeoJson(e.to_string()))?;
+
+    let mut points = Vec::new();
+    collect_geojson(&value, &mut points)?;
+    Ok(points)
+}
+
+fn collect_geojson(value: &serde_json::Value, points: &mut Vec<Coordinate>) -> Result<(), RouteError> {
+    let kind = value
+        .get("type")
+        .and_then(|t| t.as_str())
+        .ok_or_else(|| RouteError::GeoJson("object has no \"type\"".to_string()))?;
+
+    match kind {
+        "FeatureCollection" => {
+            let features = value
+                .get("features")
+                .and_then(|f| f.as_array())
+                .ok_or_else(|| RouteError::GeoJson("FeatureCollection has no features".to_string()))?;
+            for feature in features {
+                collect_geojson(feature, points)?;
+            }
+        }
+        "Feature" => {
+            if let Some(geometry) = value.get("geometry").filter(|g| !g.is_null()) {
+                collect_geojson(geometry, points)?;
+            }
+        }
+        "GeometryCollection" => {
+            let geometries = value
+                .get("geometries")
+                .and_then(|g| g.as_array())
+                .ok_or_else(|| RouteError::GeoJson("GeometryCollection has no geometries".to_string()))?;
+            for geometry in geometries {
+                collect_geojson(geometry, points)?;
+            }
+        }
+        "Point" => points.push(geojson_position(coordinates(value)?)?),
+        "MultiPoint" | "LineString" => {
+            for position in as_array(coordinates(value)?)? {
+                points.push(geojson_position(position)?);
+            }
+        }
+        "MultiLineString" => {
+            for line in as_array(coordinates(value)?)? {
+                for position in as_array(line)? {
+                    points.push(geojson_position(position)?);
+                }
+            }
+        }
+        other => {
+            return Err(RouteError::GeoJson(format!(
+                "unsupported geometry type \"{}\"",
+                other
+            )))
+        }
+    }
+
+    Ok(())
+}
+
+fn coordinates(value: &serde_json::Value) -> Result<&serde_json::Value, RouteError> {
+    value
+        .get("coordinates")
+        .ok_or_else(|| RouteError::GeoJson("geometry has no coordinates".to_string()))
+}
+
+fn as_array(value: &serde_json::Value) -> Result<&Vec<serde_json::Value>, RouteError> {
+    value
+        .as_array()
+        .ok_or_else(|| RouteError::GeoJson("expected an array of positions".to_string()))
+}
+
+/// GeoJSON positions are `[longitude, latitude, (altitude)]`
+fn geojson_position(value: &serde_json::Value) -> Result<Coordinate, RouteError> {
+    let position = as_array(value)?;
+    let number = |i: usize| {
+        position
+            .get(i)
+            .and_then(|v| v.as_f64())
+            .ok_or_else(|| RouteError::GeoJson("position must have numeric longitude and latitude".to_string()))
+    };
+    Ok(Coordinate::new(number(1)?, number(0)?))
+}
@@ -0,0 +1,126 @@
+use serde::{Deserialize, Serialize};
+
+/// Platform a build targets. Everything but Mac Catalyst builds for its
+/// simulator.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, Default, PartialEq, Eq, Hash)]
+pub enum Platform {
+    // Aliases accept the destination names older clients sent
+    #[default]
+    #[serde(rename = "ios", alias = "iOS Simulator")]
+    Ios,
+    #[serde(rename = "watchos", alias = "watchOS Simulator")]
+    WatchOs,
+    #[serde(rename = "tvos", alias = "tvOS Simulator")]
+    TvOs,
+    #[serde(rename = "visionos", alias = "visionOS Simulator")]
+    VisionOs,
+    #[serde(rename = "catalyst")]
+    MacCatalyst,
+}
+
+impl Platform {
+    /// Identifier used in the API and on the command line
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Ios => "ios",
+            Self::WatchOs => "watchos",
+            Self::TvOs => "tvos",
+            Self::VisionOs => "visionos",
+            Self::MacCatalyst => "catalyst",
+        }
+    }
+
+    /// SDK passed to `xcodebuild -sdk`
+    pub fn sdk(&self) -> &'static str {
+        match self {
+            Self::Ios => "iphonesimulator",
+            Self::WatchOs => "watchsimulator",
+            Self::TvOs => "appletvsimulator",
+            Self::VisionOs => "xrsimulator",
+            Self::MacCatalyst => "macosx",
+        }
+    }
+
+    /// Destination platform name (e.g., "watchOS Simulator")
+    pub fn destination_platform(&self) -> &'static str {
+        match self {
+            Self::Ios => "iOS Simulator",
+            Self::WatchOs => "watchOS Simulator",
+            Self::TvOs => "tvOS Simulator",
+            Self::VisionOs => "visionOS Simulator",
+            Self::MacCatalyst => "macOS",
+        }
+    }
+
+    /// Suffix of the build products directory (e.g., "Debug-watchsimulator")
+    pub fn products_suffix(&self) -> &'static str {
+        match self {
+            Self::MacCatalyst => "maccatalyst",
+            _ => self.sdk(),
+        }
+    }
+
+    /// Whether apps for this platform run in a simulator
+    pub fn has_simulator(&self) -> bool {
+        !matches!(self, Self::MacCatalyst)
+    }
+
+    /// Platform of a simulator runtime
+    /// (e.g., "com.apple.CoreSimulator.SimRuntime.watchOS-10-0")
+    pub fn from_runtime_identifier(identifier: &str) -> Option<Self> {
+        let os = identifier
+            .rsplit('.')
+            .next()?
+            .split('-')
+            .next()?;
+        match os {
+            "iOS" => Some(Self::Ios),
+            "watchOS" => Some(Self::WatchOs),
+            "tvOS" => Some(Self::TvOs),
+            "xrOS" | "visionOS" => Some(Self::VisionOs),
+            _ => None,
+        }
+    }
+
+    /// Platform of an Info.plist `CFBundleSupportedPlatforms` entry
+    pub fn from_bundle_platform(name: &str) -> Option<Self> {
+        match name {
+            "iPhoneSimulator" | "iPhoneOS" => Some(Self::Ios),
+            "WatchSimulator" | "WatchOS" => Some(Self::WatchOs),
+            "AppleTVSimulator" | "AppleTVOS" => Some(Self::TvOs),
+            "XRSimulator" | "XROS" => Some(Self::VisionOs),
+            "MacOSX" => Some(Self::MacCatalyst),
+            _ => None,
+        }
+    }
+}
+
+impl std::fmt::Display for Platform {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Self::Ios => "iOS",
+            Self::WatchOs => "watchOS",
+            Self::TvOs => "tvOS",
+            Self::VisionOs => "visionOS",
+            Self::MacCatalyst => "Mac Catalyst",
+        })
+    }
+}
+
+impl std::str::FromStr for Platform {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "ios" => Ok(Self::Ios),
+            "watchos" => Ok(Self::WatchOs),
+            "tvos" => Ok(Self::TvOs),
+            "visionos" | "xros" => Ok(Self::VisionOs),
+            "catalyst" | "maccatalyst" | "mac-catalyst" => Ok(Self::MacCatalyst),
+            _ => Err(format!(
+                "unknown platform '{}' (expected ios, watchos, tvos, visionos or catalyst)",
+                s
+            )),
+        }
+    }
+}
@@ -0,0 +1,799 @@
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use chrono::{DateTime, Utc};
+use std::cell::Cell;
+use std::collections::BTreeMap;
+use thiserror::Error;
+
+/// Errors from property list parsing
+#[derive(Debug, Error)]
+pub enum PlistError {
+    #[error("Invalid XML plist: {0}")]
+    Xml(String),
+
+    #[error("Invalid binary plist: {0}")]
+    Binary(String),
+
+    #[error("Unsupported plist format (expected XML or binary)")]
+    UnknownFormat,
+}
+
+/// A property list value
+#[derive(Debug, Clone, PartialEq)]
+pub enum PlistValue {
+    Dictionary(BTreeMap<String, PlistValue>),
+    Array(Vec<PlistValue>),
+    String(String),
+    Integer(i64),
+    Real(f64),
+    Boolean(bool),
+    Date(DateTime<Utc>),
+    Data(Vec<u8>),
+    /// Object reference in keyed archives (binary only)
+    Uid(u64),
+}
+
+impl PlistValue {
+    /// Parse an XML or binary (`bplist00`) property list
+    pub fn parse(bytes: &[u8]) -> Result<Self, PlistError> {
+        if bytes.starts_with(b"bplist00") {
+            return BinaryReader::new(bytes)?.read_top();
+        }
+
+        let text = std::str::from_utf8(bytes).map_err(|e| PlistError::Xml(e.to_string()))?;
+        if text.trim_start().starts_with('<') {
+            parse_xml(text)
+        } else {
+            Err(PlistError::UnknownFormat)
+        }
+    }
+
+    /// Value of a dictionary key
+    pub fn get(&self, key: &str) -> Option<&PlistValue> {
+        self.as_dict().and_then(|d| d.get(key))
+    }
+
+    pub fn as_dict(&self) -> Option<&BTreeMap<String, PlistValue>> {
+        match self {
+            Self::Dictionary(dict) => Some(dict),
+            _ => None,
+        }
+    }
+
+    pub fn as_array(&self) -> Option<&[PlistValue]> {
+        match self {
+            Self::Array(array) => Some(array),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            Self::String(s) => Some(s),
+            _ => None,
+        }
+    }
+
+    pub fn as_integer(&self) -> Option<i64> {
+        match self {
+            Self::Integer(i) => Some(*i),
+            _ => None,
+        }
+    }
+
+    pub fn as_bool(&self) -> Option<bool> {
+        match self {
+            Self::Boolean(b) => Some(*b),
+            _ => None,
+        }
+    }
+
+    /// Strings of an array value, skipping other element types
+    pub fn string_array(&self) -> Vec<String> {
+        self.as_array()
+            .map(|a| a.iter().filter_map(|v| v.as_str()).map(String::from).collect())
+            .unwrap_or_default()
+    }
+
+    /// Serialize as an XML property list
+    pub fn to_xml(&self) -> String {
+        let mut xml = String::from(concat!(
+            "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n",
+            "<!DOCTYPE plist PUBLIC \"-//Apple//DTD PLIST 1.0//EN\" ",
+            "\"http://www.apple.com/DTDs/PropertyList-1.0.dtd\">\n",
+            "<plist version=\"1.0\">\n",
+        ));
+        write_xml_value(&mut xml, self, 0);
+        xml.push_str("</plist>\n");
+        xml
+    }
+}
+
+fn write_xml_value(xml: &mut String, value: &PlistValue, depth: usize) {
+    let indent = "\t".repeat(depth);
+    match value {
+        PlistValue::Dictionary(dict) if dict.is_empty() => xml.push_str(&format!("{}<dict/>\n", indent)),
+        PlistValue::Dictionary(dict) => {
+            xml.push_str(&format!("{}<dict>\n", indent));
+            for (key, value) in dict {
+                xml.push_str(&format!("{}\t<key>{}</key>\n", indent, escape_xml(key)));
+                write_xml_value(xml, value, depth + 1);
+            }
+            xml.push_str(&format!("{}</dict>\n", indent));
+        }
+        PlistValue::Array(array) if array.is_empty() => xml.push_str(&format!("{}<array/>\n", indent)),
+        PlistValue::Array(array) => {
+            xml.push_str(&format!("{}<array>\n", indent));
+            for value in array {
+                write_xml_value(xml, value, depth + 1);
+            }
+            xml.push_str(&format!("{}</array>\n", indent));
+        }
+        PlistValue::String(s) => xml.push_str(&format!("{}<string>{}</string>\n", indent, escape_xml(s))),
+        PlistValue::Integer(i) => xml.push_str(&format!("{}<integer>{}</integer>\n", indent, i)),
+        PlistValue::Real(r) => xml.push_str(&format!("{}<real>{}</real>\n", indent, r)),
+        PlistValue::Boolean(true) => xml.push_str(&format!("{}<true/>\n", indent)),
+        PlistValue::Boolean(false) => xml.push_str(&format!("{}<false/>\n", indent)),
+        PlistValue::Date(date) => xml.push_str(&format!(
+            "{}<date>{}</date>\n",
+            indent,
+            date.format("%Y-%m-%dT%H:%M:%SZ")
+        )),
+        PlistValue::Data(data) => {
+            xml.push_str(&format!("{}<data>{}</data>\n", indent, STANDARD.encode(data)))
+        }
+        // XML plists have no UID type; keyed archives store them as dictionaries
+        PlistValue::Uid(uid) => xml.push_str(&format!(
+            "{}<dict>\n{}\t<key>CF$UID</key>\n{}\t<integer>{}</integer>\n{}</dict>\n",
+            indent, indent, indent, uid, indent
+        )),
+    }
+}
+
+fn escape_xml(s: &str) -> String {
+    s.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;")
+}
+
+fn parse_xml(text: &str) -> Result<PlistValue, PlistError> {
+    // roxmltree recurses too, so deep documents are turned away before parsing
+    if xml_depth(text) > MAX_DEPTH {
+        return Err(PlistError::Xml("nesting too deep".into()));
+    }
+
+    // Plists written by Xcode carry a DOCTYPE declaration
+    let options = roxmltree::ParsingOptions {
+        allow_dtd: true,
+        ..Default::default()
+    };
+    let doc = roxmltree::Document::parse_with_options(text, options)
+        .map_err(|e| PlistError::Xml(e.to_string()))?;
+
+    let root = doc.root_element();
+    if !root.has_tag_name("plist") {
+        return Err(PlistError::Xml(format!(
+            "unexpected root element <{}>",
+            root.tag_name().name()
+        )));
+    }
+    let value = root
+        .children()
+        .find(|n| n.is_element())
+        .ok_or_else(|| PlistError::Xml("empty <plist>".into()))?;
+    parse_xml_value(value)
+}
+
+/// Deepest element nesting in `text`, from a scan of its tags. Comments,
+/// declarations and CDATA sections are skipped.
+fn xml_depth(text: &str) -> usize {
+    let bytes = text.as_bytes();
+    let (mut depth, mut deepest) = (0usize, 0usize);
+    let mut i = 0;
+    while let Some(start) = bytes[i..].iter().position(|&b| b == b'<').map(|p| i + p) {
+        let rest = &text[start..];
+        let terminator = if rest.starts_with("<!--") {
+            "-->"
+        } else if rest.starts_with("<![CDATA[") {
+            "]]>"
+        } else {
+            ">"
+        };
+        let end = rest.find(terminator).map_or(bytes.len(), |p| start + p + terminator.len());
+        match bytes.get(start + 1) {
+            Some(b'/') => depth = depth.saturating_sub(1),
+            Some(b'?' | b'!') => {}
+            _ if bytes[..end].ends_with(b"/>") => {}
+            _ => {
+                depth += 1;
+                deepest = deepest.max(depth);
+            }
+        }
+        i = end;
+    }
+    deepest
+}
+
+fn parse_xml_value(node: roxmltree::Node) -> Result<PlistValue, PlistError> {
+    let text = || node.text().unwrap_or("");
+    let invalid = |kind: &str| PlistError::Xml(format!("invalid <{}> value {:?}", kind, text()));
+
+    match node.tag_name().name() {
+        "dict" => {
+            let mut dict = BTreeMap::new();
+            let mut children = node.children().filter(|n| n.is_element());
+            while let Some(key) = children.next() {
+                if !key.has_tag_name("key") {
+                    return Err(PlistError::Xml(format!(
+                        "expected <key> in <dict>, found <{}>",
+                        key.tag_name().name()
+                    )));
+                }
+                let value = children
+                    .next()
+                    .ok_or_else(|| PlistError::Xml("<key> without a value".into()))?;
+                dict.insert(key.text().unwrap_or("").to_string(), parse_xml_value(value)?);
+            }
+            Ok(PlistValue::Dictionary(dict))
+        }
+        "array" => node
+            .children()
+            .filter(|n| n.is_element())
+            .map(parse_xml_value)
+            .collect::<Result<_, _>>()
+            .map(PlistValue::Array),
+        "string" => Ok(PlistValue::String(text().to_string())),
+        "integer" => {
+            let s = text().trim();
+            let parsed = match s.strip_prefix("0x").or_else(|| s.strip_prefix("0X")) {
+                Some(hex) => u64::from_str_radix(hex, 16).map(|v| v as i64).ok(),
+                None => s
+                    .parse::<i64>()
+                    .ok()
+                    .or_else(|| s.parse::<u64>().ok().map(|v| v as i64)),
+            };
+            parsed.map(PlistValue::Integer).ok_or_else(|| invalid("integer"))
+        }
+        "real" => text()
+            .trim()
+            .parse()
+            .map(PlistValue::Real)
+            .map_err(|_| invalid("real")),
+        "true" => Ok(PlistValue::Boolean(true)),
+        "false" => Ok(PlistValue::Boolean(false)),
+        "date" => DateTime::parse_from_rfc3339(text().trim())
+            .map(|d| PlistValue::Date(d.with_timezone(&Utc)))
+            .map_err(|_| invalid("date")),
+        "data" => {
+            let encoded: String = text().chars().filter(|c| !c.is_whitespace()).collect();
+            STANDARD
+                .decode(encoded)
+                .map(PlistValue::Data)
+                .map_err(|_| invalid("data"))
+        }
+        other => Err(PlistError::Xml(format!("unknown element <{}>", other))),
+    }
+}
+
+/// Seconds between the Unix epoch and the plist epoch (2001-01-01)
+const PLIST_EPOCH_OFFSET: i64 = 978_307_200;
+
+/// Deepest container nesting accepted in binary plists, and deepest element
+/// nesting in XML ones. Parsing recurses, so this also keeps a hostile file
+/// from exhausting a 2 MiB thread stack.
+const MAX_DEPTH: usize = 128;
+
+/// Most memory one binary plist may expand to. Every object read is charged
+/// its size, plus the length of its string or data, each time it is read: a
+/// small file can reference one large object many times.
+const MAX_EXPANDED_BYTES: usize = 64 * 1024 * 1024;
+
+/// Reader for the `bplist00` format: objects addressed through an offset
+/// table described by a 32-byte trailer
+struct BinaryReader<'a> {
+    bytes: &'a [u8],
+    offsets: Vec<usize>,
+    ref_size: usize,
+    top_object: usize,
+    /// Bytes charged against `MAX_EXPANDED_BYTES` so far
+    expanded: Cell<usize>,
+}
+
+impl<'a> BinaryReader<'a> {
+    fn new(bytes: &'a [u8]) -> Result<Self, PlistError> {
+        if bytes.len() < 8 + 32 {
+            return Err(binary_error("file too short"));
+        }
+        let trailer = &bytes[bytes.len() - 32..];
+        let offset_size = trailer[6] as usize;
+        let ref_size = trailer[7] as usize;
+        let num_objects = be_uint(&trailer[8..16]) as usize;
+        let top_object = be_uint(&trailer[16..24]) as usize;
+        let table_offset = be_uint(&trailer[24..32]) as usize;
+
+        if !(1..=8).contains(&offset_size) || !(1..=8).contains(&ref_size) {
+            return Err(binary_error("invalid trailer"));
+        }
+        let table_len = num_objects
+            .checked_mul(offset_size)
+            .ok_or_else(|| binary_error("invalid object count"))?;
+        let table = table_offset
+            .checked_add(table_len)
+            .and_then(|end| bytes.get(table_offset..end))
+            .ok_or_else(|| binary_error("offset table out of bounds"))?;
+
+        let offsets = table
+            .chunks(offset_size)
+            .map(|chunk| be_uint(chunk) as usize)
+            .collect();
+
+        Ok(Self {
+            bytes,
+            offsets,
+            ref_size,
+            top_object,
+            expanded: Cell::new(0),
+        })
+    }
+
+    fn read_top(&self) -> Result<PlistValue, PlistError> {
+        self.read_object(self.top_object, &mut Vec::new())
+    }
+
+    /// Read an object; `path` holds the containers currently being read
+    fn read_object(&self, index: usize, path: &mut Vec<usize>) -> Result<PlistValue, PlistError> {
+        self.charge(std::mem::size_of::<PlistValue>())?;
+        let offset = *self
+            .offsets
+            .get(index)
+            .ok_or_else(|| binary_error(format!("object {} out of range", index)))?;
+        let marker = *self
+            .bytes
+            .get(offset)
+            .ok_or_else(|| binary_error("object offset out of bounds"))?;
+        let info = (marker & 0x0f) as usize;
+
+        match marker >> 4 {
+            0x0 => match marker {
+                0x08 => Ok(PlistValue::Boolean(false)),
+                0x09 => Ok(PlistValue::Boolean(true)),
+                _ => Err(binary_error(format!("unsupported marker 0x{:02x}", marker))),
+            },
+            0x1 => {
+                // 8-byte integers are signed, smaller ones unsigned; 16-byte
+                // integers keep their low 64 bits
+                let bytes = self.slice(offset + 1, 1 << info)?;
+                let bytes = &bytes[bytes.len().saturating_sub(8)..];
+                Ok(PlistValue::Integer(be_uint(bytes) as i64))
+            }
+            0x2 => {
+                let bytes = self.slice(offset + 1, 1 << info)?;
+                match bytes.len() {
+                    4 => Ok(PlistValue::Real(f32::from_be_bytes(bytes.try_into().unwrap()) as f64)),
+                    8 => Ok(PlistValue::Real(f64::from_be_bytes(bytes.try_into().unwrap()))),
+                    n => Err(binary_error(format!("unsupported real size {}", n))),
+                }
+            }
+            0x3 if marker == 0x33 => {
+                let bytes = self.slice(offset + 1, 8)?;
+                let secs = f64::from_be_bytes(bytes.try_into().unwrap());
+                if !secs.is_finite() {
+                    return Err(binary_error("date out of range"));
+                }
+                // Whole seconds round down so the nanoseconds are never negative
+                let whole = secs.floor();
+                let nanos = ((secs - whole) * 1e9) as u32;
+                PLIST_EPOCH_OFFSET
+                    .checked_add(whole as i64)
+                    .and_then(|unix| DateTime::from_timestamp(unix, nanos.min(999_999_999)))
+                    .map(PlistValue::Date)
+                    .ok_or_else(|| binary_error("date out of range"))
+            }
+            0x4 => {
+                let (len, start) = self.length(offset, info)?;
+                self.charge(len)?;
+                Ok(PlistValue::Data(self.slice(start, len)?.to_vec()))
+            }
+            0x5 => {
+                let (len, start) = self.length(offset, info)?;
+                self.charge(len)?;
+                let bytes = self.slice(start, len)?;
+                // ASCII in theory; Latin-1 in practice for some writers
+                Ok(PlistValue::String(bytes.iter().map(|&b| b as char).collect()))
+            }
+            0x6 => {
+                let (len, start) = self.length(offset, info)?;
+                let byte_len = len
+                    .checked_mul(2)
+                    .ok_or_else(|| binary_error("string too long"))?;
+                self.charge(byte_len)?;
+                let units: Vec<u16> = self
+                    .slice(start, byte_len)?
+                    .chunks(2)
+                    .map(|c| u16::from_be_bytes([c[0], c[1]]))
+                    .collect();
+                String::from_utf16(&units)
+                    .map(PlistValue::String)
+                    .map_err(|e| binary_error(e.to_string()))
+            }
+            0x8 => Ok(PlistValue::Uid(be_uint(self.slice(offset + 1, info + 1)?))),
+            // Arrays, sets and ordered sets
+            0xa..=0xc => {
+                let (len, start) = self.length(offset, info)?;
+                let refs = self.refs(start, len)?;
+                self.nested(index, path, |path| {
+                    refs.iter()
+                        .map(|&r| self.read_object(r, path))
+                        .collect::<Result<_, _>>()
+                        .map(PlistValue::Array)
+                })
+            }
+            0xd => {
+                let (len, start) = self.length(offset, info)?;
+                let count = len
+                    .checked_mul(2)
+                    .ok_or_else(|| binary_error("dictionary too large"))?;
+                let refs = self.refs(start, count)?;
+                let (keys, values) = refs.split_at(len);
+                self.nested(index, path, |path| {
+                    let mut dict = BTreeMap::new();
+                    for (&key, &value) in keys.iter().zip(values) {
+                        let PlistValue::String(key) = self.read_object(key, path)? else {
+                            return Err(binary_error("dictionary key is not a string"));
+                        };
+                        dict.insert(key, self.read_object(value, path)?);
+                    }
+                    Ok(PlistValue::Dictionary(dict))
+                })
+            }
+            _ => Err(binary_error(format!("unsupported marker 0x{:02x}", marker))),
+        }
+    }
+
+    /// Read the children of container `index`, rejecting cycles and runaway
+    /// nesting
+    fn nested<F>(&self, index: usize, path: &mut Vec<usize>, read: F) -> Result<PlistValue, PlistError>
+    where
+        F: FnOnce(&mut Vec<usize>) -> Result<PlistValue, PlistError>,
+    {
+        if path.contains(&index) {
+            return Err(binary_error("object graph contains a cycle"));
+        }
+        if path.len() >= MAX_DEPTH {
+            return Err(binary_error("nesting too deep"));
+        }
+
+        path.push(index);
+        let result = read(path);
+        path.pop();
+        result
+    }
+
+    /// Charge `bytes` against the expansion budget
+    fn charge(&self, bytes: usize) -> Result<(), PlistError> {
+        let expanded = self.expanded.get().saturating_add(bytes);
+        if expanded > MAX_EXPANDED_BYTES {
+            return Err(binary_error("too many objects"));
+        }
+        self.expanded.set(expanded);
+        Ok(())
+    }
+
+    /// Element count of a variable-length object and where its content starts.
+    /// A count of 0xF means the real count follows as an integer object.
+    fn length(&self, offset: usize, info: usize) -> Result<(usize, usize), PlistError> {
+        if info != 0x0f {
+            return Ok((info, offset + 1));
+        }
+        let marker = *self
+            .bytes
+            .get(offset + 1)
+            .ok_or_else(|| binary_error("length out of bounds"))?;
+        if marker >> 4 != 0x1 {
+            return Err(binary_error("invalid length marker"));
+        }
+        let size = 1usize << (marker & 0x0f);
+        if size > 8 {
+            return Err(binary_error("length too large"));
+        }
+        let len = be_uint(self.slice(offset + 2, size)?) as usize;
+        Ok((len, offset + 2 + size))
+    }
+
+    fn refs(&self, start: usize, count: usize) -> Result<Vec<usize>, PlistError> {
+        let len = count
+            .checked_mul(self.ref_size)
+            .ok_or_else(|| binary_error("too many references"))?;
+        Ok(self
+            .slice(start, len)?
+            .chunks(self.ref_size)
+            .map(|chunk| be_uint(chunk) as usize)
+            .collect())
+    }
+
+    fn slice(&self, start: usize, len: usize) -> Result<&'a [u8], PlistError> {
+        start
+            .checked_add(len)
+            .and_then(|end| self.bytes.get(start..end))
+            .ok_or_else(|| binary_error("object data out of bounds"))
+    }
+}
+
+/// Big-endian unsigned integer of up to 8 bytes
+fn be_uint(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0u64, |acc, &b| (acc << 8) | b as u64)
+}
+
+fn binary_error(message: impl Into<String>) -> PlistError {
+    PlistError::Binary(message.into())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A bplist00 file holding `objects` (each marker byte and payload),
+    /// with 2-byte offsets and references
+    fn bplist(objects: &[Vec<u8>], top: u64) -> Vec<u8> {
+        let mut bytes = b"bplist00".to_vec();
+        let mut offsets = Vec::new();
+        for object in objects {
+            offsets.push(bytes.len() as u16);
+            bytes.extend_from_slice(object);
+        }
+        let table_offset = bytes.len() as u64;
+        for offset in offsets {
+            bytes.extend_from_slice(&offset.to_be_bytes());
+        }
+        bytes.extend_from_slice(&[0; 6]);
+        bytes.extend_from_slice(&[2, 2]);
+        bytes.extend_from_slice(&(objects.len() as u64).to_be_bytes());
+        bytes.extend_from_slice(&top.to_be_bytes());
+        bytes.extend_from_slice(&table_offset.to_be_bytes());
+        bytes
+    }
+
+    /// Parse a plist whose only object is `object`
+    fn single(object: &[u8]) -> Result<PlistValue, PlistError> {
+        PlistValue::parse(&bplist(&[object.to_vec()], 0))
+    }
+
+    fn marker_with(marker: u8, payload: &[u8]) -> Vec<u8> {
+        let mut object = vec![marker];
+        object.extend_from_slice(payload);
+        object
+    }
+
+    fn refs(ids: &[u16]) -> Vec<u8> {
+        ids.iter().flat_map(|id| id.to_be_bytes()).collect()
+    }
+
+    fn date(secs: f64) -> Result<PlistValue, PlistError> {
+        single(&marker_with(0x33, &secs.to_be_bytes()))
+    }
+
+    fn binary_message(result: Result<PlistValue, PlistError>) -> String {
+        match result {
+            Err(PlistError::Binary(message)) => message,
+            other => panic!("expected a binary plist error, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn reads_binary_integers() {
+        assert_eq!(single(&[0x10, 0x2a]).unwrap(), PlistValue::Integer(42));
+        assert_eq!(single(&[0x11, 0x01, 0x02]).unwrap(), PlistValue::Integer(0x0102));
+        assert_eq!(single(&[0x12, 0xff, 0xff, 0xff, 0xff]).unwrap(), PlistValue::Integer(0xffff_ffff));
+        assert_eq!(single(&marker_with(0x13, &(-2i64).to_be_bytes())).unwrap(), PlistValue::Integer(-2));
+        // 16-byte integers keep their low 64 bits
+        let mut wide = [0u8; 16];
+        wide[15] = 7;
+        assert_eq!(single(&marker_with(0x14, &wide)).unwrap(), PlistValue::Integer(7));
+    }
+
+    #[test]
+    fn reads_binary_reals() {
+        assert_eq!(single(&marker_with(0x22, &1.5f32.to_be_bytes())).unwrap(), PlistValue::Real(1.5));
+        assert_eq!(single(&marker_with(0x23, &(-2.25f64).to_be_bytes())).unwrap(), PlistValue::Real(-2.25));
+        assert_eq!(binary_message(single(&[0x21, 0, 0])), "unsupported real size 2");
+    }
+
+    #[test]
+    fn reads_binary_dates() {
+        let at = |s: &str| PlistValue::Date(s.parse().unwrap());
+        assert_eq!(date(0.0).unwrap(), at("2001-01-01T00:00:00Z"));
+        assert_eq!(date(86_400.5).unwrap(), at("2001-01-02T00:00:00.5Z"));
+        assert_eq!(date(-0.5).unwrap(), at("2000-12-31T23:59:59.5Z"));
+        assert_eq!(date(-978_307_200.0).unwrap(), at("1970-01-01T00:00:00Z"));
+    }
+
+    #[test]
+    fn rejects_dates_out_of_range() {
+        for secs in [1e300, -1e300, f64::MAX, f64::INFINITY, f64::NAN, 9.3e18] {
+            assert_eq!(binary_message(date(secs)), "date out of range", "{}", secs);
+        }
+    }
+
+    #[test]
+    fn reads_strings() {
+        assert_eq!(single(b"\x52hi").unwrap(), PlistValue::String("hi".into()));
+        // Single-byte strings are read as Latin-1
+        assert_eq!(single(b"\x51\xe9").unwrap(), PlistValue::String("é".into()));
+
+        let utf16: Vec<u8> = "é✓😀".encode_utf16().flat_map(|u| u.to_be_bytes()).collect();
+        assert_eq!(single(&marker_with(0x64, &utf16)).unwrap(), PlistValue::String("é✓😀".into()));
+        // A lone surrogate
+        assert!(single(&[0x61, 0xd8, 0x00]).is_err());
+    }
+
+    #[test]
+    fn reads_uids_and_data() {
+        assert_eq!(single(&[0x80, 0x05]).unwrap(), PlistValue::Uid(5));
+        assert_eq!(single(&[0x81, 0x01, 0x00]).unwrap(), PlistValue::Uid(256));
+        assert_eq!(single(&[0x43, 1, 2, 3]).unwrap(), PlistValue::Data(vec![1, 2, 3]));
+        assert_eq!(single(&[0x09]).unwrap(), PlistValue::Boolean(true));
+        assert_eq!(single(&[0x08]).unwrap(), PlistValue::Boolean(false));
+    }
+
+    #[test]
+    fn reads_extended_lengths() {
+        let text = "a string longer than fourteen bytes";
+        let mut object = vec![0x5f, 0x10, text.len() as u8];
+        object.extend_from_slice(text.as_bytes());
+        assert_eq!(single(&object).unwrap(), PlistValue::String(text.into()));
+
+        // A 2-byte count, and an array of 20 references to one string
+        let mut array = vec![0xaf, 0x11, 0x00, 20];
+        array.extend(refs(&[1; 20]));
+        let value = PlistValue::parse(&bplist(&[array, b"\x51x".to_vec()], 0)).unwrap();
+        assert_eq!(value.as_array().unwrap().len(), 20);
+
+        assert_eq!(binary_message(single(&[0x5f, 0x20, 0x01])), "invalid length marker");
+        assert_eq!(binary_message(single(&[0x5f, 0x14, 0x01])), "length too large");
+    }
+
+    #[test]
+    fn reads_dictionaries() {
+        let dict = marker_with(0xd2, &refs(&[1, 2, 3, 4]));
+        let objects = [dict, b"\x51a".to_vec(), b"\x51b".to_vec(), vec![0x10, 1], vec![0x09]];
+        let value = PlistValue::parse(&bplist(&objects, 0)).unwrap();
+        assert_eq!(value.get("a"), Some(&PlistValue::Integer(1)));
+        assert_eq!(value.get("b"), Some(&PlistValue::Boolean(true)));
+
+        let dict = marker_with(0xd1, &refs(&[1, 1]));
+        let objects = [dict, vec![0x10, 1]];
+        assert_eq!(
+            binary_message(PlistValue::parse(&bplist(&objects, 0))),
+            "dictionary key is not a string"
+        );
+    }
+
+    #[test]
+    fn rejects_cycles() {
+        let objects = [marker_with(0xa1, &refs(&[1])), marker_with(0xa1, &refs(&[0]))];
+        assert_eq!(
+            binary_message(PlistValue::parse(&bplist(&objects, 0))),
+            "object graph contains a cycle"
+        );
+        let objects = [marker_with(0xd1, &refs(&[1, 0])), b"\x51k".to_vec()];
+        assert_eq!(
+            binary_message(PlistValue::parse(&bplist(&objects, 0))),
+            "object graph contains a cycle"
+        );
+    }
+
+    #[test]
+    fn rejects_deep_nesting() {
+        // Arrays each holding the next, down to an empty one
+        let nested = |depth: u16| {
+            let mut objects: Vec<Vec<u8>> = (1..depth).map(|next| marker_with(0xa1, &refs(&[next]))).collect();
+            objects.push(vec![0xa0]);
+            PlistValue::parse(&bplist(&objects, 0))
+        };
+        assert!(nested(MAX_DEPTH as u16).is_ok());
+        assert_eq!(binary_message(nested(MAX_DEPTH as u16 + 1)), "nesting too deep");
+        assert_eq!(binary_message(nested(10_000)), "nesting too deep");
+    }
+
+    #[test]
+    fn rejects_deep_xml_nesting() {
+        let nested = |depth: usize| {
+            let xml = format!(
+                "<?xml version=\"1.0\"?>\n<!-- <array> -->\n<plist version=\"1.0\">{}<true/>{}</plist>",
+                "<array>".repeat(depth - 1),
+                "</array>".repeat(depth - 1)
+            );
+            PlistValue::parse(xml.as_bytes())
+        };
+        assert!(nested(MAX_DEPTH).is_ok());
+        let too_deep = |depth| matches!(nested(depth), Err(PlistError::Xml(m)) if m == "nesting too deep");
+        assert!(too_deep(MAX_DEPTH + 1));
+        // Far deeper than roxmltree can parse on a 2 MiB stack
+        assert!(too_deep(100_000));
+    }
+
+    #[test]
+    fn rejects_runaway_sharing() {
+        // One array referencing the same 2 KiB data object `count` times
+        let shared = |count: u16| {
+            let mut data = vec![0x4f, 0x11, 0x08, 0x00];
+            data.extend([0xab; 2048]);
+            let mut array = vec![0xaf, 0x11];
+            array.extend(count.to_be_bytes());
+            array.extend(refs(&vec![0; count as usize]));
+            PlistValue::parse(&bplist(&[data, array], 1))
+        };
+        assert_eq!(shared(100).unwrap().as_array().unwrap().len(), 100);
+        // About 130 KB on disk, 128 MiB once read
+        assert_eq!(binary_message(shared(u16::MAX)), "too many objects");
+
+        // Small objects add up too: 65,535 references to 20,000 booleans
+        let mut inner = vec![0xaf, 0x11];
+        inner.extend(20_000u16.to_be_bytes());
+        inner.extend(refs(&[0; 20_000]));
+        let mut outer = vec![0xaf, 0x11, 0xff, 0xff];
+        outer.extend(refs(&[1; 65535]));
+        let bytes = bplist(&[vec![0x09], inner, outer], 2);
+        assert_eq!(binary_message(PlistValue::parse(&bytes)), "too many objects");
+    }
+
+    #[test]
+    fn rejects_bad_offsets() {
+        // Offset table past the end of the file
+        let mut bytes = bplist(&[vec![0x09]], 0);
+        let len = bytes.len();
+        bytes[len - 8..].copy_from_slice(&1000u64.to_be_bytes());
+        assert_eq!(binary_message(PlistValue::parse(&bytes)), "offset table out of bounds");
+
+        // An object count so large the table size overflows
+        let mut bytes = bplist(&[vec![0x09]], 0);
+        bytes[len - 24..len - 16].copy_from_slice(&u64::MAX.to_be_bytes());
+        assert_eq!(binary_message(PlistValue::parse(&bytes)), "invalid object count");
+
+        // An offset pointing past the end of the file
+        let mut bytes = bplist(&[vec![0x09]], 0);
+        bytes[len - 34..len - 32].copy_from_slice(&0xfff0u16.to_be_bytes());
+        assert_eq!(binary_message(PlistValue::parse(&bytes)), "object offset out of bounds");
+
+        // A top object or reference beyond the table
+        assert_eq!(
+            binary_message(PlistValue::parse(&bplist(&[vec![0x09]], 3))),
+            "object 3 out of range"
+        );
+        let objects = [marker_with(0xa1, &refs(&[9]))];
+        assert_eq!(binary_message(PlistValue::parse(&bplist(&objects, 0))), "object 9 out of range");
+
+        assert_eq!(binary_message(PlistValue::parse(b"bplist00")), "file too short");
+        let mut bytes = bplist(&[vec![0x09]], 0);
+        bytes[len - 26] = 0;
+        assert_eq!(binary_message(PlistValue::parse(&bytes)), "invalid trailer");
+    }
+
+    #[test]
+    fn parses_and_writes_xml() {
+        let xml = r#"<?xml version="1.0" encoding="UTF-8"?>
+<!DOCTYPE plist PUBLIC "-//Apple//DTD PLIST 1.0//EN" "http://www.apple.com/DTDs/PropertyList-1.0.dtd">
+<plist version="1.0">
+<dict>
+	<key>CFBundleIdentifier</key>
+	<string>com.example.Demo &amp; Co</string>
+	<key>UIDeviceFamily</key>
+	<array>
+		<integer>1</integer>
+		<integer>2</integer>
+	</array>
+	<key>Enabled</key>
+	<true/>
+	<key>Scale</key>
+	<real>2.5</real>
+	<key>Blob</key>
+	<data>AQID</data>
+</dict>
+</plist>"#;
+        let value = PlistValue::parse(xml.as_bytes()).unwrap();
+        assert_eq!(value.get("CFBundleIdentifier").and_then(|v| v.as_str()), Some("com.example.Demo & Co"));
+        assert_eq!(value.get("Blob"), Some(&PlistValue::Data(vec![1, 2, 3])));
+        assert_eq!(value.get("Scale"), Some(&PlistValue::Real(2.5)));
+        assert_eq!(PlistValue::parse(value.to_xml().as_bytes()).unwrap(), value);
+
+        assert!(matches!(PlistValue::parse(b"key = value;"), Err(PlistError::UnknownFormat)));
+    }
+}
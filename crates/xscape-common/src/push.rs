@@ -0,0 +1,70 @@
+use serde_json::Value;
+use thiserror::Error;
+
+/// Maximum APNs payload size in bytes
+pub const MAX_PUSH_PAYLOAD_BYTES: usize = 4096;
+
+/// Errors from push payload validation
+#[derive(Debug, Error)]
+pub enum PushPayloadError {
+    #[error("Payload must be a JSON object")]
+    NotAnObject,
+
+    #[error("Payload is missing the \"aps\" dictionary")]
+    MissingAps,
+
+    #[error("Invalid \"aps\" dictionary: {0}")]
+    InvalidAps(String),
+
+    #[error("Payload is {0} bytes (limit is {MAX_PUSH_PAYLOAD_BYTES})")]
+    TooLarge(usize),
+}
+
+/// Validate an APNs payload: an object with an `aps` dictionary whose
+/// well-known keys have the right types, within the APNs size limit.
+pub fn validate_push_payload(payload: &Value) -> Result<(), PushPayloadError> {
+    let object = payload.as_object().ok_or(PushPayloadError::NotAnObject)?;
+    let aps = object.get("aps").ok_or(PushPayloadError::MissingAps)?;
+    let aps = aps
+        .as_object()
+        .ok_or_else(|| PushPayloadError::InvalidAps("must be a dictionary".to_string()))?;
+
+    if let Some(alert) = aps.get("alert") {
+        if !(alert.is_string() || alert.is_object()) {
+            return Err(PushPayloadError::InvalidAps(
+                "\"alert\" must be a string or dictionary".to_string(),
+            ));
+        }
+    }
+    if let Some(badge) = aps.get("badge") {
+        if !badge.is_u64() {
+            return Err(PushPayloadError::InvalidAps(
+                "\"badge\" must be a non-negative integer".to_string(),
+            ));
+        }
+    }
+    if let Some(sound) = aps.get("sound") {
+        if !(sound.is_string() || sound.is_object()) {
+            return Err(PushPayloadError::InvalidAps(
+                "\"sound\" must be a string or dictionary".to_string(),
+            ));
+        }
+    }
+    for flag in ["content-available", "mutable-content"] {
+        if let Some(value) = aps.get(flag) {
+            if !matches!(value.as_u64(), Some(0 | 1)) {
+                return Err(PushPayloadError::InvalidAps(format!(
+                    "\"{}\" must be 0 or 1",
+                    flag
+                )));
+            }
+        }
+    }
+
+    let size = serde_json::to_vec(payload).map(|b| b.len()).unwrap_or(0);
+    if size > MAX_PUSH_PAYLOAD_BYTES {
+        return Err(PushPayloadError::TooLarge(size));
+    }
+
+    Ok(())
+}
@@ -1,14 +1,33 @@
 use anyhow::{Context, Result};
 use xscape_common::{
-    ApiError, BootSimulatorRequest, BootSimulatorResponse, BuildRequest, BuildResponse,
-    BuildStatusResponse, HealthResponse, ListSimulatorsResponse, RunAppRequest, RunAppResponse,
-    ShutdownSimulatorRequest, SyncProjectResponse,
+    AddMediaResponse, ApiError, ArchiveFormat, ArchiveRequest, ArtifactQuery, BootSimulatorRequest,
+    BootSimulatorResponse, BuildRequest, BuildResponse, BuildStatusResponse, ContainerQuery,
+    CrashReportResponse, HealthResponse, InstallAppRequest, InstallAppResponse, KeychainRequest,
+    KeychainResponse, ListArtifactsResponse, ListContainerResponse, ListSessionsResponse,
+    ListSimulatorsResponse, LocationResponse, OpenUrlRequest, OpenUrlResponse, PasteboardContent,
+    PlayRouteRequest, PrivacyRequest, PrivacyResponse, ProjectInfoResponse, PushNotificationRequest,
+    PushNotificationResponse, RunAppRequest, RunAppResponse, SetLocationRequest,
+    ShutdownSimulatorRequest, StatusBarOverride, StatusBarResponse, SymbolicateQuery,
+    SymbolicateResponse, SyncProjectResponse, UiSettingsRequest, UiSettingsResponse,
+    UploadAppResponse, UploadContainerResponse, UrlSchemesResponse,
 };
+use futures_util::StreamExt;
 use reqwest::multipart::{Form, Part};
+use std::path::PathBuf;
 use std::time::Duration;
+use tokio::io::AsyncWriteExt;
 use tracing::debug;
 use uuid::Uuid;
 
+/// Signing files sent with an archive request
+#[derive(Default)]
+pub struct ArchiveUpload {
+    pub export_options: Option<Vec<u8>>,
+    pub certificate: Option<Vec<u8>>,
+    pub certificate_password: Option<String>,
+    pub profiles: Vec<Vec<u8>>,
+}
+
 /// HTTP client for communicating with xcode-agent
 pub struct AgentClient {
     client: reqwest::Client,
@@ -84,6 +103,28 @@ impl AgentClient {
         response.json().await.context("Failed to parse sync response")
     }
 
+    /// Get the schemes, targets and configurations of a synced project
+    pub async fn project_info(&self, project_id: Uuid) -> Result<ProjectInfoResponse> {
+        let url = format!("{}/projects/{}/info", self.base_url, project_id);
+        debug!("GET {}", url);
+
+        // xcodebuild -list resolves packages before listing
+        let response = self.client
+            .get(&url)
+            .timeout(Duration::from_secs(600))
+            .send()
+            .await
+            .context("Failed to get project info")?;
+
+        if !response.status().is_success() {
+            let error: ApiError = response.json().await
+                .unwrap_or_else(|_| ApiError::new("UNKNOWN", "Unknown error"));
+            anyhow::bail!("Failed to get project info: {}", error);
+        }
+
+        response.json().await.context("Failed to parse project info response")
+    }
+
     /// Start a build
     pub async fn build(&self, request: &BuildRequest) -> Result<BuildResponse> {
         let url = format!("{}/build", self.base_url);
@@ -105,6 +146,41 @@ impl AgentClient {
         response.json().await.context("Failed to parse build response")
     }
 
+    /// Start archiving and exporting an .ipa
+    pub async fn archive(&self, request: &ArchiveRequest, upload: ArchiveUpload) -> Result<BuildResponse> {
+        let url = format!("{}/archive", self.base_url);
+        debug!("POST {}", url);
+
+        let mut form = Form::new().text("request", serde_json::to_string(request)?);
+        if let Some(options) = upload.export_options {
+            form = form.part("export_options", Part::bytes(options).file_name("ExportOptions.plist"));
+        }
+        if let Some(certificate) = upload.certificate {
+            form = form.part("certificate", Part::bytes(certificate).file_name("certificate.p12"));
+        }
+        if let Some(password) = upload.certificate_password {
+            form = form.text("certificate_password", password);
+        }
+        for profile in upload.profiles {
+            form = form.part("profile", Part::bytes(profile).file_name("profile.mobileprovision"));
+        }
+
+        let response = self.client
+            .post(&url)
+            .multipart(form)
+            .send()
+            .await
+            .context("Failed to start archive")?;
+
+        if !response.status().is_success() {
+            let error: ApiError = response.json().await
+                .unwrap_or_else(|_| ApiError::new("UNKNOWN", "Unknown error"));
+            anyhow::bail!("Archive failed: {}", error);
+        }
+
+        response.json().await.context("Failed to parse archive response")
+    }
+
     /// Get build status
     pub async fn get_build_status(&self, build_id: Uuid) -> Result<BuildStatusResponse> {
         let url = format!("{}/build/{}", self.base_url, build_id);
@@ -125,6 +201,46 @@ impl AgentClient {
         response.json().await.context("Failed to parse build status")
     }
 
+    /// Get the most recent successful build
+    pub async fn get_latest_build(&self) -> Result<BuildStatusResponse> {
+        let url = format!("{}/build/latest", self.base_url);
+        debug!("GET {}", url);
+
+        let response = self.client
+            .get(&url)
+            .send()
+            .await
+            .context("Failed to get latest build")?;
+
+        if !response.status().is_success() {
+            let error: ApiError = response.json().await
+                .unwrap_or_else(|_| ApiError::new("UNKNOWN", "Unknown error"));
+            anyhow::bail!("Failed to get latest build: {}", error);
+        }
+
+        response.json().await.context("Failed to parse build status")
+    }
+
+    /// Get URL schemes registered by a built app
+    pub async fn get_url_schemes(&self, build_id: Uuid) -> Result<UrlSchemesResponse> {
+        let url = format!("{}/build/{}/url-schemes", self.base_url, build_id);
+        debug!("GET {}", url);
+
+        let response = self.client
+            .get(&url)
+            .send()
+            .await
+            .context("Failed to get URL schemes")?;
+
+        if !response.status().is_success() {
+            let error: ApiError = response.json().await
+                .unwrap_or_else(|_| ApiError::new("UNKNOWN", "Unknown error"));
+            anyhow::bail!("Failed to get URL schemes: {}", error);
+        }
+
+        response.json().await.context("Failed to parse URL schemes response")
+    }
+
     /// List simulators
     pub async fn list_simulators(&self) -> Result<ListSimulatorsResponse> {
         let url = format!("{}/simulator/list", self.base_url);
@@ -146,12 +262,17 @@ impl AgentClient {
     }
 
     /// Boot simulator
-    pub async fn boot_simulator(&self, device_udid: &str) -> Result<BootSimulatorResponse> {
+    pub async fn boot_simulator(
+        &self,
+        device_udid: &str,
+        root_certificates: Vec<String>,
+    ) -> Result<BootSimulatorResponse> {
         let url = format!("{}/simulator/boot", self.base_url);
         debug!("POST {}", url);
 
         let request = BootSimulatorRequest {
             device_udid: device_udid.to_string(),
+            root_certificates,
         };
 
         let response = self.client
@@ -216,6 +337,585 @@ impl AgentClient {
         response.json().await.context("Failed to parse shutdown response")
     }
 
+    /// Set a fixed simulated location
+    pub async fn set_location(
+        &self,
+        device_udid: &str,
+        request: &SetLocationRequest,
+    ) -> Result<LocationResponse> {
+        let url = format!("{}/simulator/{}/location", self.base_url, device_udid);
+        debug!("POST {}", url);
+
+        let response = self.client
+            .post(&url)
+            .json(request)
+            .send()
+            .await
+            .context("Failed to set location")?;
+
+        if !response.status().is_success() {
+            let error: ApiError = response.json().await
+                .unwrap_or_else(|_| ApiError::new("UNKNOWN", "Unknown error"));
+            anyhow::bail!("Failed to set location: {}", error);
+        }
+
+        response.json().await.context("Failed to parse location response")
+    }
+
+    /// Clear the simulated location
+    pub async fn clear_location(&self, device_udid: &str) -> Result<LocationResponse> {
+        let url = format!("{}/simulator/{}/location", self.base_url, device_udid);
+        debug!("DELETE {}", url);
+
+        let response = self.client
+            .delete(&url)
+            .send()
+            .await
+            .context("Failed to clear location")?;
+
+        if !response.status().is_success() {
+            let error: ApiError = response.json().await
+                .unwrap_or_else(|_| ApiError::new("UNKNOWN", "Unknown error"));
+            anyhow::bail!("Failed to clear location: {}", error);
+        }
+
+        response.json().await.context("Failed to parse location response")
+    }
+
+    /// Play back a route on a simulator
+    pub async fn play_route(
+        &self,
+        device_udid: &str,
+        request: &PlayRouteRequest,
+    ) -> Result<LocationResponse> {
+        let url = format!("{}/simulator/{}/location/route", self.base_url, device_udid);
+        debug!("POST {} ({} waypoints)", url, request.waypoints.len());
+
+        let response = self.client
+            .post(&url)
+            .json(request)
+            .send()
+            .await
+            .context("Failed to play route")?;
+
+        if !response.status().is_success() {
+            let error: ApiError = response.json().await
+                .unwrap_or_else(|_| ApiError::new("UNKNOWN", "Unknown error"));
+            anyhow::bail!("Failed to play route: {}", error);
+        }
+
+        response.json().await.context("Failed to parse location response")
+    }
+
+    /// Deliver a push notification to an app on a simulator
+    pub async fn send_push(
+        &self,
+        device_udid: &str,
+        request: &PushNotificationRequest,
+    ) -> Result<PushNotificationResponse> {
+        let url = format!("{}/simulator/{}/push", self.base_url, device_udid);
+        debug!("POST {}", url);
+
+        let response = self.client
+            .post(&url)
+            .json(request)
+            .send()
+            .await
+            .context("Failed to send push notification")?;
+
+        if !response.status().is_success() {
+            let error: ApiError = response.json().await
+                .unwrap_or_else(|_| ApiError::new("UNKNOWN", "Unknown error"));
+            anyhow::bail!("Failed to send push notification: {}", error);
+        }
+
+        response.json().await.context("Failed to parse push response")
+    }
+
+    /// Open a URL in a simulator
+    pub async fn open_url(&self, device_udid: &str, request: &OpenUrlRequest) -> Result<OpenUrlResponse> {
+        let url = format!("{}/simulator/{}/openurl", self.base_url, device_udid);
+        debug!("POST {}", url);
+
+        let response = self.client
+            .post(&url)
+            .json(request)
+            .send()
+            .await
+            .context("Failed to open URL")?;
+
+        if !response.status().is_success() {
+            let error: ApiError = response.json().await
+                .unwrap_or_else(|_| ApiError::new("UNKNOWN", "Unknown error"));
+            anyhow::bail!("Failed to open URL: {}", error);
+        }
+
+        response.json().await.context("Failed to parse open URL response")
+    }
+
+    /// Grant, revoke or reset a privacy permission
+    pub async fn set_privacy(&self, device_udid: &str, request: &PrivacyRequest) -> Result<PrivacyResponse> {
+        let url = format!("{}/simulator/{}/privacy", self.base_url, device_udid);
+        debug!("POST {}", url);
+
+        let response = self.client
+            .post(&url)
+            .json(request)
+            .send()
+            .await
+            .context("Failed to change privacy permission")?;
+
+        if !response.status().is_success() {
+            let error: ApiError = response.json().await
+                .unwrap_or_else(|_| ApiError::new("UNKNOWN", "Unknown error"));
+            anyhow::bail!("Failed to change privacy permission: {}", error);
+        }
+
+        response.json().await.context("Failed to parse privacy response")
+    }
+
+    /// Change simulator appearance and/or content size
+    pub async fn set_ui(&self, device_udid: &str, request: &UiSettingsRequest) -> Result<UiSettingsResponse> {
+        let url = format!("{}/simulator/{}/ui", self.base_url, device_udid);
+        debug!("POST {}", url);
+
+        let response = self.client
+            .post(&url)
+            .json(request)
+            .send()
+            .await
+            .context("Failed to change simulator UI settings")?;
+
+        if !response.status().is_success() {
+            let error: ApiError = response.json().await
+                .unwrap_or_else(|_| ApiError::new("UNKNOWN", "Unknown error"));
+            anyhow::bail!("Failed to change simulator UI settings: {}", error);
+        }
+
+        response.json().await.context("Failed to parse UI settings response")
+    }
+
+    /// Override simulator status bar values
+    pub async fn override_status_bar(&self, device_udid: &str, request: &StatusBarOverride) -> Result<StatusBarResponse> {
+        let url = format!("{}/simulator/{}/status-bar", self.base_url, device_udid);
+        debug!("POST {}", url);
+
+        let response = self.client
+            .post(&url)
+            .json(request)
+            .send()
+            .await
+            .context("Failed to override status bar")?;
+
+        if !response.status().is_success() {
+            let error: ApiError = response.json().await
+                .unwrap_or_else(|_| ApiError::new("UNKNOWN", "Unknown error"));
+            anyhow::bail!("Failed to override status bar: {}", error);
+        }
+
+        response.json().await.context("Failed to parse status bar response")
+    }
+
+    /// Remove simulator status bar overrides
+    pub async fn clear_status_bar(&self, device_udid: &str) -> Result<StatusBarResponse> {
+        let url = format!("{}/simulator/{}/status-bar", self.base_url, device_udid);
+        debug!("DELETE {}", url);
+
+        let response = self.client
+            .delete(&url)
+            .send()
+            .await
+            .context("Failed to clear status bar")?;
+
+        if !response.status().is_success() {
+            let error: ApiError = response.json().await
+                .unwrap_or_else(|_| ApiError::new("UNKNOWN", "Unknown error"));
+            anyhow::bail!("Failed to clear status bar: {}", error);
+        }
+
+        response.json().await.context("Failed to parse status bar response")
+    }
+
+    /// Add a certificate to, or reset, a simulator keychain
+    pub async fn update_keychain(&self, device_udid: &str, request: &KeychainRequest) -> Result<KeychainResponse> {
+        let url = format!("{}/simulator/{}/keychain", self.base_url, device_udid);
+        debug!("POST {}", url);
+
+        let response = self.client
+            .post(&url)
+            .json(request)
+            .send()
+            .await
+            .context("Failed to update keychain")?;
+
+        if !response.status().is_success() {
+            let error: ApiError = response.json().await
+                .unwrap_or_else(|_| ApiError::new("UNKNOWN", "Unknown error"));
+            anyhow::bail!("Failed to update keychain: {}", error);
+        }
+
+        response.json().await.context("Failed to parse keychain response")
+    }
+
+    /// List a directory inside an app container
+    pub async fn list_container(
+        &self,
+        device_udid: &str,
+        bundle_id: &str,
+        query: &ContainerQuery,
+    ) -> Result<ListContainerResponse> {
+        let url = format!(
+            "{}/simulator/{}/apps/{}/container",
+            self.base_url, device_udid, bundle_id
+        );
+        debug!("GET {}", url);
+
+        let response = self.client
+            .get(&url)
+            .query(query)
+            .send()
+            .await
+            .context("Failed to list container")?;
+
+        if !response.status().is_success() {
+            let error: ApiError = response.json().await
+                .unwrap_or_else(|_| ApiError::new("UNKNOWN", "Unknown error"));
+            anyhow::bail!("Failed to list container: {}", error);
+        }
+
+        response.json().await.context("Failed to parse container listing")
+    }
+
+    /// Download a file from an app container.
+    /// Returns the data and whether it is a tar.gz of a directory.
+    pub async fn download_container(
+        &self,
+        device_udid: &str,
+        bundle_id: &str,
+        query: &ContainerQuery,
+    ) -> Result<(Vec<u8>, bool)> {
+        let url = format!(
+            "{}/simulator/{}/apps/{}/container/download",
+            self.base_url, device_udid, bundle_id
+        );
+        debug!("GET {}", url);
+
+        let response = self.client
+            .get(&url)
+            .query(query)
+            .send()
+            .await
+            .context("Failed to download from container")?;
+
+        if !response.status().is_success() {
+            let error: ApiError = response.json().await
+                .unwrap_or_else(|_| ApiError::new("UNKNOWN", "Unknown error"));
+            anyhow::bail!("Failed to download from container: {}", error);
+        }
+
+        let is_archive = response
+            .headers()
+            .get(reqwest::header::CONTENT_TYPE)
+            .is_some_and(|v| v == "application/gzip");
+        let data = response.bytes().await.context("Failed to read download")?;
+
+        Ok((data.to_vec(), is_archive))
+    }
+
+    /// Upload a file into an app container
+    pub async fn upload_container(
+        &self,
+        device_udid: &str,
+        bundle_id: &str,
+        query: &ContainerQuery,
+        local: &std::path::Path,
+    ) -> Result<UploadContainerResponse> {
+        let url = format!(
+            "{}/simulator/{}/apps/{}/container/upload",
+            self.base_url, device_udid, bundle_id
+        );
+        debug!("PUT {}", url);
+
+        let file = tokio::fs::File::open(local)
+            .await
+            .with_context(|| format!("Failed to open {}", local.display()))?;
+
+        let response = self.client
+            .put(&url)
+            .query(query)
+            .body(file)
+            .send()
+            .await
+            .context("Failed to upload to container")?;
+
+        if !response.status().is_success() {
+            let error: ApiError = response.json().await
+                .unwrap_or_else(|_| ApiError::new("UNKNOWN", "Unknown error"));
+            anyhow::bail!("Failed to upload to container: {}", error);
+        }
+
+        response.json().await.context("Failed to parse upload response")
+    }
+
+    /// Upload photos, videos or vCard files to a simulator
+    pub async fn add_media(&self, device_udid: &str, files: &[PathBuf]) -> Result<AddMediaResponse> {
+        let url = format!("{}/simulator/{}/media", self.base_url, device_udid);
+        debug!("POST {} ({} files)", url, files.len());
+
+        let mut form = Form::new();
+        for path in files {
+            let part = Part::file(path)
+                .await
+                .with_context(|| format!("Failed to open {}", path.display()))?;
+            form = form.part("file", part);
+        }
+
+        let response = self.client
+            .post(&url)
+            .multipart(form)
+            .send()
+            .await
+            .context("Failed to upload media")?;
+
+        if !response.status().is_success() {
+            let error: ApiError = response.json().await
+                .unwrap_or_else(|_| ApiError::new("UNKNOWN", "Unknown error"));
+            anyhow::bail!("Failed to add media: {}", error);
+        }
+
+        response.json().await.context("Failed to parse media response")
+    }
+
+    /// Read the simulator pasteboard
+    pub async fn get_pasteboard(&self, device_udid: &str) -> Result<PasteboardContent> {
+        let url = format!("{}/simulator/{}/pasteboard", self.base_url, device_udid);
+        debug!("GET {}", url);
+
+        let response = self.client
+            .get(&url)
+            .send()
+            .await
+            .context("Failed to read simulator pasteboard")?;
+
+        if !response.status().is_success() {
+            let error: ApiError = response.json().await
+                .unwrap_or_else(|_| ApiError::new("UNKNOWN", "Unknown error"));
+            anyhow::bail!("Failed to read simulator pasteboard: {}", error);
+        }
+
+        response.json().await.context("Failed to parse pasteboard response")
+    }
+
+    /// Replace the simulator pasteboard
+    pub async fn set_pasteboard(&self, device_udid: &str, text: &str) -> Result<()> {
+        let url = format!("{}/simulator/{}/pasteboard", self.base_url, device_udid);
+        debug!("POST {}", url);
+
+        let response = self.client
+            .post(&url)
+            .json(&PasteboardContent { text: text.to_string() })
+            .send()
+            .await
+            .context("Failed to write simulator pasteboard")?;
+
+        if !response.status().is_success() {
+            let error: ApiError = response.json().await
+                .unwrap_or_else(|_| ApiError::new("UNKNOWN", "Unknown error"));
+            anyhow::bail!("Failed to write simulator pasteboard: {}", error);
+        }
+
+        Ok(())
+    }
+
+    /// List app run sessions (most recent first)
+    pub async fn list_sessions(&self) -> Result<ListSessionsResponse> {
+        let url = format!("{}/sessions", self.base_url);
+        debug!("GET {}", url);
+
+        let response = self.client
+            .get(&url)
+            .send()
+            .await
+            .context("Failed to list sessions")?;
+
+        if !response.status().is_success() {
+            let error: ApiError = response.json().await
+                .unwrap_or_else(|_| ApiError::new("UNKNOWN", "Unknown error"));
+            anyhow::bail!("Failed to list sessions: {}", error);
+        }
+
+        response.json().await.context("Failed to parse sessions response")
+    }
+
+    /// Get the crash report of a run session, with source paths mapped to `source_root`
+    pub async fn get_crash_report(
+        &self,
+        session_id: Uuid,
+        source_root: Option<&str>,
+    ) -> Result<CrashReportResponse> {
+        let url = format!("{}/sessions/{}/crash", self.base_url, session_id);
+        debug!("GET {}", url);
+
+        let query = SymbolicateQuery {
+            source_root: source_root.map(String::from),
+        };
+        let response = self.client
+            .get(&url)
+            .query(&query)
+            .send()
+            .await
+            .context("Failed to get crash report")?;
+
+        if !response.status().is_success() {
+            let error: ApiError = response.json().await
+                .unwrap_or_else(|_| ApiError::new("UNKNOWN", "Unknown error"));
+            anyhow::bail!("Failed to get crash report: {}", error);
+        }
+
+        response.json().await.context("Failed to parse crash report response")
+    }
+
+    /// Symbolicate an .ips report with a build's debug symbols
+    pub async fn symbolicate(
+        &self,
+        build_id: Uuid,
+        report: String,
+        source_root: Option<&str>,
+    ) -> Result<SymbolicateResponse> {
+        let url = format!("{}/build/{}/symbolicate", self.base_url, build_id);
+        debug!("POST {}", url);
+
+        let query = SymbolicateQuery {
+            source_root: source_root.map(String::from),
+        };
+        let response = self.client
+            .post(&url)
+            .query(&query)
+            .body(report)
+            .send()
+            .await
+            .context("Failed to symbolicate crash report")?;
+
+        if !response.status().is_success() {
+            let error: ApiError = response.json().await
+                .unwrap_or_else(|_| ApiError::new("UNKNOWN", "Unknown error"));
+            anyhow::bail!("Failed to symbolicate crash report: {}", error);
+        }
+
+        response.json().await.context("Failed to parse symbolicate response")
+    }
+
+    /// List the products of a build
+    pub async fn list_artifacts(&self, build_id: Uuid) -> Result<ListArtifactsResponse> {
+        let url = format!("{}/build/{}/artifacts", self.base_url, build_id);
+        debug!("GET {}", url);
+
+        let response = self.client
+            .get(&url)
+            .send()
+            .await
+            .context("Failed to list artifacts")?;
+
+        if !response.status().is_success() {
+            let error: ApiError = response.json().await
+                .unwrap_or_else(|_| ApiError::new("UNKNOWN", "Unknown error"));
+            anyhow::bail!("Failed to list artifacts: {}", error);
+        }
+
+        response.json().await.context("Failed to parse artifacts response")
+    }
+
+    /// Download a build product into `dest`, returning the number of bytes written.
+    /// Directories arrive as archives in `format`.
+    pub async fn download_artifact(
+        &self,
+        build_id: Uuid,
+        name: &str,
+        format: ArchiveFormat,
+        dest: &std::path::Path,
+    ) -> Result<u64> {
+        let url = format!("{}/build/{}/artifacts/{}", self.base_url, build_id, name);
+        debug!("GET {}", url);
+
+        // Large bundles can take longer than the default request timeout
+        let response = self.client
+            .get(&url)
+            .query(&ArtifactQuery { format })
+            .timeout(Duration::from_secs(3600))
+            .send()
+            .await
+            .context("Failed to download artifact")?;
+
+        if !response.status().is_success() {
+            let error: ApiError = response.json().await
+                .unwrap_or_else(|_| ApiError::new("UNKNOWN", "Unknown error"));
+            anyhow::bail!("Failed to download {}: {}", name, error);
+        }
+
+        let mut file = tokio::fs::File::create(dest)
+            .await
+            .with_context(|| format!("Failed to create {}", dest.display()))?;
+        let mut stream = response.bytes_stream();
+        let mut written = 0u64;
+        while let Some(chunk) = stream.next().await {
+            let chunk = chunk.context("Failed to read download")?;
+            file.write_all(&chunk).await?;
+            written += chunk.len() as u64;
+        }
+        file.flush().await?;
+
+        Ok(written)
+    }
+
+    /// Upload a zipped or tarred simulator .app, registering it as a build
+    pub async fn upload_app(&self, path: &std::path::Path) -> Result<UploadAppResponse> {
+        let url = format!("{}/apps/upload", self.base_url);
+        let file = tokio::fs::File::open(path)
+            .await
+            .with_context(|| format!("Failed to open {}", path.display()))?;
+        let size = file.metadata().await?.len();
+        debug!("POST {} ({} bytes)", url, size);
+
+        let response = self.client
+            .post(&url)
+            .header(reqwest::header::CONTENT_TYPE, "application/octet-stream")
+            .header(reqwest::header::CONTENT_LENGTH, size)
+            .body(file)
+            .timeout(Duration::from_secs(3600))
+            .send()
+            .await
+            .context("Failed to upload app")?;
+
+        if !response.status().is_success() {
+            let error: ApiError = response.json().await
+                .unwrap_or_else(|_| ApiError::new("UNKNOWN", "Unknown error"));
+            anyhow::bail!("Upload failed: {}", error);
+        }
+
+        response.json().await.context("Failed to parse upload response")
+    }
+
+    /// Install a build on a simulator without launching it
+    pub async fn install_app(&self, request: &InstallAppRequest) -> Result<InstallAppResponse> {
+        let url = format!("{}/simulator/install", self.base_url);
+        debug!("POST {}", url);
+
+        let response = self.client
+            .post(&url)
+            .json(request)
+            .send()
+            .await
+            .context("Failed to install app")?;
+
+        if !response.status().is_success() {
+            let error: ApiError = response.json().await
+                .unwrap_or_else(|_| ApiError::new("UNKNOWN", "Unknown error"));
+            anyhow::bail!("Failed to install app: {}", error);
+        }
+
+        response.json().await.context("Failed to parse install response")
+    }
+
     /// Check if agent is reachable
     pub async fn is_reachable(&self) -> bool {
         self.health().await.is_ok()
@@ -0,0 +1,166 @@
+use anyhow::{Context, Result};
+use xscape_common::{
+    ArchiveFormat, ArchiveRequest, ArtifactKind, BuildStatus, CliConfig, ExportMethod,
+};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use tokio::time::sleep;
+use uuid::Uuid;
+
+use super::build::print_app_info;
+use crate::agent_client::{AgentClient, ArchiveUpload};
+use crate::cli::ArchiveArgs;
+use crate::project;
+use crate::tui::styles::Styles;
+
+/// Environment variable holding the .p12 password, so it never appears in
+/// the shell history or process list
+const CERTIFICATE_PASSWORD_ENV: &str = "XSCAPE_CERTIFICATE_PASSWORD";
+
+/// Run the archive command
+pub async fn run(args: ArchiveArgs, client: &AgentClient, config: &CliConfig) -> Result<()> {
+    let project_path = args.project.canonicalize()?;
+    let project_name = project::get_project_name(&project_path);
+
+    // Without an ExportOptions plist, fall back to ad-hoc
+    let export_method = match (args.export_method, &args.export_options) {
+        (None, None) => Some(ExportMethod::AdHoc),
+        (method, _) => method,
+    };
+
+    // Read signing files up front so a typo fails before the upload
+    let upload = ArchiveUpload {
+        export_options: args.export_options.as_deref().map(read_file).transpose()?,
+        certificate: args.certificate.as_deref().map(read_file).transpose()?,
+        certificate_password: match args.certificate {
+            Some(_) => Some(certificate_password()?),
+            None => None,
+        },
+        profiles: args
+            .profiles
+            .iter()
+            .map(|p| read_file(p))
+            .collect::<Result<_>>()?,
+    };
+    super::build::preflight(&project_path, config, args.no_preflight)?;
+
+    println!("Archiving project: {}", project_name);
+    println!("  Configuration: {}", args.configuration);
+    match export_method {
+        Some(method) => println!("  Export method: {}", method),
+        None => println!("  Export options: {}", args.export_options.unwrap_or_default().display()),
+    }
+
+    let health = client.health().await?;
+    if health.xcode_version.is_none() {
+        anyhow::bail!("Agent reports Xcode is not available");
+    }
+    println!("  Agent: {} (Xcode {})", client.base_url(), health.xcode_version.unwrap_or_default());
+
+    println!("\nSyncing project...");
+    let (tarball, checksum) = project::create_tarball(&project_path, &config.project.exclude_patterns)?;
+    let sync_result = client
+        .sync_project(&project_name, &checksum, tarball)
+        .await?;
+
+    if sync_result.was_cached {
+        println!("  Project already synced (cached)");
+    } else {
+        println!("  Synced {} files", sync_result.files_extracted);
+    }
+
+    let scheme = super::build::resolve_scheme(args.scheme.clone(), sync_result.project_id, client, config).await?;
+    println!("  Scheme: {}", scheme);
+
+    println!("\nStarting archive...");
+    let request = ArchiveRequest {
+        project_id: sync_result.project_id,
+        project_file: None,
+        scheme: scheme.clone(),
+        configuration: args.configuration,
+        export_method,
+        team_id: args.team_id,
+        extra_args: vec![],
+        offline: args.offline,
+    };
+
+    let response = client.archive(&request, upload).await?;
+    println!("  Build ID: {}", response.build_id);
+
+    println!("\nArchiving...");
+    loop {
+        sleep(Duration::from_secs(2)).await;
+
+        let status = client.get_build_status(response.build_id).await?;
+
+        match status.status {
+            BuildStatus::Succeeded => {
+                println!("\nArchive succeeded!");
+                if let Some(ref bundle_id) = status.bundle_id {
+                    println!("  Bundle ID: {}", bundle_id);
+                }
+                if let Some(ref app_info) = status.app_info {
+                    print_app_info(app_info);
+                }
+                if let Some(duration) = status.duration_secs {
+                    println!("  Duration: {:.1}s", duration);
+                }
+                if !status.warnings.is_empty() {
+                    println!("  Warnings: {}", status.warnings.len());
+                }
+                break;
+            }
+            BuildStatus::Failed => {
+                println!("\nArchive failed!");
+                if let Some(ref error) = status.error_message {
+                    println!("  Error: {}", error);
+                }
+                anyhow::bail!("Archive failed");
+            }
+            BuildStatus::Cancelled => {
+                println!("\nArchive cancelled");
+                anyhow::bail!("Archive was cancelled");
+            }
+            _ => {
+                print!(".");
+                std::io::Write::flush(&mut std::io::stdout())?;
+            }
+        }
+    }
+
+    let output = args
+        .output
+        .unwrap_or_else(|| PathBuf::from(format!("{}.ipa", scheme.replace('/', "_"))));
+    download_ipa(response.build_id, &output, client).await
+}
+
+async fn download_ipa(build_id: Uuid, output: &Path, client: &AgentClient) -> Result<()> {
+    let artifacts = client.list_artifacts(build_id).await?;
+    let ipa = artifacts
+        .artifacts
+        .iter()
+        .find(|a| a.kind == ArtifactKind::Ipa)
+        .ok_or_else(|| anyhow::anyhow!("Build {} has no .ipa artifact", build_id))?;
+
+    let size = client
+        .download_artifact(build_id, &ipa.name, ArchiveFormat::default(), output)
+        .await?;
+    println!("  IPA: {} ({})", output.display(), Styles::format_bytes(size));
+    Ok(())
+}
+
+fn read_file(path: &Path) -> Result<Vec<u8>> {
+    std::fs::read(path).with_context(|| format!("Failed to read {}", path.display()))
+}
+
+/// The .p12 password from the environment, or prompted for without echo
+fn certificate_password() -> Result<String> {
+    if let Ok(password) = std::env::var(CERTIFICATE_PASSWORD_ENV) {
+        return Ok(password);
+    }
+    dialoguer::Password::new()
+        .with_prompt("Certificate password")
+        .allow_empty_password(true)
+        .interact()
+        .with_context(|| format!("Failed to read certificate password (set {})", CERTIFICATE_PASSWORD_ENV))
+}
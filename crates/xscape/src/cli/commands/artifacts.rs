@@ -0,0 +1,120 @@
+use anyhow::{Context, Result};
+use flate2::read::GzDecoder;
+use tar::Archive;
+use xscape_common::ArchiveFormat;
+use std::path::{Path, PathBuf};
+use uuid::Uuid;
+
+use crate::agent_client::AgentClient;
+use crate::cli::ArtifactsCommands;
+use crate::tui::styles::Styles;
+
+/// Run artifacts commands
+pub async fn run(command: ArtifactsCommands, client: &AgentClient) -> Result<()> {
+    match command {
+        ArtifactsCommands::List { build_id } => list(build_id, client).await,
+        ArtifactsCommands::Pull {
+            build_id,
+            output,
+            only,
+            archive,
+        } => pull(build_id, output, only, archive, client).await,
+    }
+}
+
+async fn resolve_build(build_id: Option<Uuid>, client: &AgentClient) -> Result<Uuid> {
+    match build_id {
+        Some(id) => Ok(id),
+        None => Ok(client.get_latest_build().await?.build_id),
+    }
+}
+
+async fn list(build_id: Option<Uuid>, client: &AgentClient) -> Result<()> {
+    let build_id = resolve_build(build_id, client).await?;
+    let response = client.list_artifacts(build_id).await?;
+
+    println!("Artifacts of build {}:", build_id);
+    if response.artifacts.is_empty() {
+        println!("  (none)");
+    }
+    for artifact in &response.artifacts {
+        println!(
+            "  {:<40} {:<14} {}",
+            artifact.name,
+            artifact.kind.as_str(),
+            Styles::format_bytes(artifact.size_bytes)
+        );
+    }
+
+    Ok(())
+}
+
+async fn pull(
+    build_id: Option<Uuid>,
+    output: PathBuf,
+    only: Vec<String>,
+    archive: Option<ArchiveFormat>,
+    client: &AgentClient,
+) -> Result<()> {
+    let build_id = resolve_build(build_id, client).await?;
+    let response = client.list_artifacts(build_id).await?;
+
+    for name in &only {
+        if !response.artifacts.iter().any(|a| &a.name == name) {
+            anyhow::bail!("Build {} has no artifact named '{}'", build_id, name);
+        }
+    }
+
+    std::fs::create_dir_all(&output)
+        .with_context(|| format!("Failed to create {}", output.display()))?;
+
+    let artifacts = response
+        .artifacts
+        .iter()
+        .filter(|a| only.is_empty() || only.contains(&a.name));
+    for artifact in artifacts {
+        if !artifact.is_dir {
+            let dest = output.join(&artifact.name);
+            let size = client
+                .download_artifact(build_id, &artifact.name, ArchiveFormat::default(), &dest)
+                .await?;
+            println!("  {} ({})", dest.display(), Styles::format_bytes(size));
+            continue;
+        }
+
+        if let Some(format) = archive {
+            let dest = output.join(format!("{}.{}", artifact.name, format));
+            let size = client
+                .download_artifact(build_id, &artifact.name, format, &dest)
+                .await?;
+            println!("  {} ({})", dest.display(), Styles::format_bytes(size));
+            continue;
+        }
+
+        // Download next to the destination, then extract in place
+        let partial = output.join(format!(".{}.tar.gz.partial", artifact.name));
+        let result = async {
+            client
+                .download_artifact(build_id, &artifact.name, ArchiveFormat::TarGz, &partial)
+                .await?;
+            extract(&partial, &output)
+        }
+        .await;
+        let _ = std::fs::remove_file(&partial);
+        result?;
+        println!(
+            "  {} ({})",
+            output.join(&artifact.name).display(),
+            Styles::format_bytes(artifact.size_bytes)
+        );
+    }
+
+    Ok(())
+}
+
+fn extract(archive: &Path, dest: &Path) -> Result<()> {
+    let file = std::fs::File::open(archive)?;
+    Archive::new(GzDecoder::new(file))
+        .unpack(dest)
+        .context("Failed to extract artifact archive")
+}
@@ -1,20 +1,31 @@
-use anyhow::Result;
-use xscape_common::{BuildConfiguration, BuildDestination, BuildRequest, BuildStatus, CliConfig};
+use anyhow::{Context, Result};
+use xscape_common::{
+    validate_build_setting, AppInfo, BuildDestination, BuildRequest, BuildStatus, CliConfig,
+    Platform,
+};
+use dialoguer::{theme::ColorfulTheme, FuzzySelect};
+use std::collections::HashMap;
+use std::io::IsTerminal;
+use std::path::Path;
 use std::time::Duration;
 use tokio::time::sleep;
 use tracing::info;
+use uuid::Uuid;
 
 use crate::agent_client::AgentClient;
-use crate::cli::BuildArgs;
+use crate::cli::{BuildArgs, BuildSettingsArgs};
 use crate::project;
+use crate::project::preflight::Severity;
 
 /// Run the build command
 pub async fn run(args: BuildArgs, client: &AgentClient, config: &CliConfig) -> Result<()> {
     let project_path = args.project.canonicalize()?;
     let project_name = project::get_project_name(&project_path);
 
+    let (build_settings, xcconfig) = build_overrides(&args.overrides, &project_path)?;
+    preflight(&project_path, config, args.no_preflight)?;
+
     println!("Building project: {}", project_name);
-    println!("  Scheme: {}", args.scheme);
     println!("  Configuration: {}", args.configuration);
 
     // Check agent health first
@@ -24,12 +35,18 @@ pub async fn run(args: BuildArgs, client: &AgentClient, config: &CliConfig) -> R
     }
     println!("  Agent: {} (Xcode {})", client.base_url(), health.xcode_version.unwrap_or_default());
 
-    // Get target device
-    let device_name = args
-        .device
-        .clone()
-        .unwrap_or_else(|| config.simulator.preferred_device.clone());
-    println!("  Device: {}", device_name);
+    // Get target device. Only iOS has a preferred device; other platforms
+    // build for any of their simulators unless one is named.
+    let destination = match (args.device.clone(), args.platform) {
+        (_, Platform::MacCatalyst) => BuildDestination::generic(Platform::MacCatalyst),
+        (Some(device), platform) => BuildDestination::simulator(platform, device),
+        (None, Platform::Ios) => {
+            BuildDestination::ios_simulator(config.simulator.preferred_device.clone())
+        }
+        (None, platform) => BuildDestination::generic(platform),
+    };
+    println!("  Platform: {}", args.platform);
+    println!("  Device: {}", destination.describe());
 
     // Create project tarball
     println!("\nSyncing project...");
@@ -46,22 +63,23 @@ pub async fn run(args: BuildArgs, client: &AgentClient, config: &CliConfig) -> R
         println!("  Synced {} files", sync_result.files_extracted);
     }
 
-    // Parse configuration
-    let configuration = match args.configuration.to_lowercase().as_str() {
-        "release" => BuildConfiguration::Release,
-        _ => BuildConfiguration::Debug,
-    };
+    let scheme = resolve_scheme(args.scheme.clone(), sync_result.project_id, client, config).await?;
+    println!("  Scheme: {}", scheme);
 
     // Start build
     println!("\nStarting build...");
     let build_request = BuildRequest {
         project_id: sync_result.project_id,
         project_file: None,
-        scheme: args.scheme.clone(),
-        configuration,
-        destination: BuildDestination::ios_simulator(&device_name),
+        scheme: scheme.clone(),
+        configuration: args.configuration,
+        destination,
+        build_settings,
+        xcconfig,
         extra_args: vec![],
         clean: args.clean,
+        test: args.test,
+        offline: args.offline,
     };
 
     let build_response = client.build(&build_request).await?;
@@ -76,13 +94,20 @@ pub async fn run(args: BuildArgs, client: &AgentClient, config: &CliConfig) -> R
 
         match status.status {
             BuildStatus::Succeeded => {
-                println!("\nBuild succeeded!");
+                if args.test {
+                    println!("\nTests passed!");
+                } else {
+                    println!("\nBuild succeeded!");
+                }
                 if let Some(ref app_path) = status.app_path {
                     println!("  App: {}", app_path);
                 }
                 if let Some(ref bundle_id) = status.bundle_id {
                     println!("  Bundle ID: {}", bundle_id);
                 }
+                if let Some(ref app_info) = status.app_info {
+                    print_app_info(app_info);
+                }
                 if let Some(duration) = status.duration_secs {
                     println!("  Duration: {:.1}s", duration);
                 }
@@ -110,3 +135,128 @@ pub async fn run(args: BuildArgs, client: &AgentClient, config: &CliConfig) -> R
         }
     }
 }
+
+/// Pick the scheme to build: the one given, the configured default, or one of
+/// the schemes the agent finds in the synced project
+pub async fn resolve_scheme(
+    scheme: Option<String>,
+    project_id: Uuid,
+    client: &AgentClient,
+    config: &CliConfig,
+) -> Result<String> {
+    if let Some(scheme) = scheme.or_else(|| config.project.default_scheme.clone()) {
+        return Ok(scheme);
+    }
+
+    println!("  Looking up schemes...");
+    let info = client.project_info(project_id).await?;
+    match info.schemes.len() {
+        0 => anyhow::bail!("{} has no schemes", info.project_file),
+        1 => Ok(info.schemes[0].clone()),
+        _ if !std::io::stdin().is_terminal() => anyhow::bail!(
+            "{} has several schemes, pick one with --scheme: {}",
+            info.project_file,
+            info.schemes.join(", ")
+        ),
+        _ => {
+            let selection = FuzzySelect::with_theme(&ColorfulTheme::default())
+                .with_prompt("Select scheme")
+                .items(&info.schemes)
+                .default(0)
+                .interact()?;
+            Ok(info.schemes[selection].clone())
+        }
+    }
+}
+
+/// Check the project for files that won't reach the agent. Warnings are
+/// printed; errors stop the command before anything is uploaded.
+pub fn preflight(project_path: &Path, config: &CliConfig, skip: bool) -> Result<()> {
+    if skip {
+        return Ok(());
+    }
+
+    let issues = project::preflight::check(project_path, &config.project.exclude_patterns);
+    let mut errors = 0;
+    for issue in &issues {
+        let label = match issue.severity {
+            Severity::Error => {
+                errors += 1;
+                "error"
+            }
+            Severity::Warning => "warning",
+        };
+        match issue.path {
+            Some(ref path) => eprintln!("{}: {}: {}", label, issue.message, path),
+            None => eprintln!("{}: {}", label, issue.message),
+        }
+    }
+
+    if errors > 0 {
+        anyhow::bail!(
+            "Preflight found {} problem(s) that would fail the build (use --no-preflight to build anyway)",
+            errors
+        );
+    }
+    Ok(())
+}
+
+/// Parse `--setting` overrides and resolve `--xcconfig` relative to the project
+pub fn build_overrides(
+    args: &BuildSettingsArgs,
+    project_path: &Path,
+) -> Result<(HashMap<String, String>, Option<String>)> {
+    let mut settings = HashMap::new();
+    for setting in &args.settings {
+        let (name, value) = setting
+            .split_once('=')
+            .ok_or_else(|| anyhow::anyhow!("Invalid build setting '{}', expected KEY=VALUE", setting))?;
+        let name = name.trim();
+        validate_build_setting(name).map_err(anyhow::Error::msg)?;
+        settings.insert(name.to_string(), value.to_string());
+    }
+
+    let xcconfig = match args.xcconfig {
+        Some(ref path) => {
+            let path = path
+                .canonicalize()
+                .with_context(|| format!("Failed to find {}", path.display()))?;
+            let relative = path.strip_prefix(project_path).map_err(|_| {
+                anyhow::anyhow!("{} is not inside the project directory", path.display())
+            })?;
+            Some(relative.to_string_lossy().into_owned())
+        }
+        None => None,
+    };
+
+    Ok((settings, xcconfig))
+}
+
+/// Print the metadata read from the built app's Info.plist
+pub fn print_app_info(info: &AppInfo) {
+    if let Some(ref name) = info.display_name {
+        println!("  Name: {}", name);
+    }
+    match (&info.version, &info.build_number) {
+        (Some(version), Some(build)) => println!("  Version: {} ({})", version, build),
+        (Some(version), None) => println!("  Version: {}", version),
+        (None, Some(build)) => println!("  Build: {}", build),
+        (None, None) => {}
+    }
+    if let Some(ref minimum) = info.minimum_os_version {
+        println!("  Minimum OS: {}", minimum);
+    }
+    if !info.device_families.is_empty() {
+        let families: Vec<String> = info.device_families.iter().map(|f| f.to_string()).collect();
+        println!("  Devices: {}", families.join(", "));
+    }
+    if !info.url_schemes.is_empty() {
+        println!("  URL schemes: {}", info.url_schemes.join(", "));
+    }
+    for extension in &info.extensions {
+        match extension.extension_point {
+            Some(ref point) => println!("  Extension: {} ({})", extension.name, point),
+            None => println!("  Extension: {}", extension.name),
+        }
+    }
+}
@@ -0,0 +1,129 @@
+use anyhow::Result;
+use xscape_common::{CliConfig, SimulatorState};
+use std::time::Duration;
+use tracing::debug;
+
+use crate::agent_client::AgentClient;
+use crate::cli::commands::devices::find_device;
+use crate::cli::{ClipboardArgs, ClipboardCommands};
+use crate::clipboard;
+
+/// Run clipboard commands
+pub async fn run(command: ClipboardCommands, client: &AgentClient, config: &CliConfig) -> Result<()> {
+    match command {
+        ClipboardCommands::Push(args) => push(args, client, config).await,
+        ClipboardCommands::Pull(args) => pull(args, client, config).await,
+        ClipboardCommands::Sync { args, interval } => sync(args, interval, client, config).await,
+    }
+}
+
+async fn push(args: ClipboardArgs, client: &AgentClient, config: &CliConfig) -> Result<()> {
+    let backend = clipboard::detect(args.backend)?;
+    let device = find_device(client, args.device.as_deref(), config).await?;
+
+    let text = backend.read()?;
+    client.set_pasteboard(&device.udid, &text).await?;
+
+    // Status goes to stderr so the stdio backend stays pipe-friendly
+    eprintln!("Copied {} characters to {}", text.chars().count(), device.name);
+    Ok(())
+}
+
+async fn pull(args: ClipboardArgs, client: &AgentClient, config: &CliConfig) -> Result<()> {
+    let backend = clipboard::detect(args.backend)?;
+    let device = find_device(client, args.device.as_deref(), config).await?;
+
+    let content = client.get_pasteboard(&device.udid).await?;
+    backend.write(&content.text)?;
+
+    if backend.is_watchable() {
+        eprintln!(
+            "Copied {} characters from {}",
+            content.text.chars().count(),
+            device.name
+        );
+    }
+    Ok(())
+}
+
+/// Poll both clipboards and copy whichever side changed to the other
+async fn sync(
+    args: ClipboardArgs,
+    interval_ms: u64,
+    client: &AgentClient,
+    config: &CliConfig,
+) -> Result<()> {
+    let backend = clipboard::detect(args.backend)?;
+    if !backend.is_watchable() {
+        anyhow::bail!(
+            "Clipboard sync needs a desktop clipboard (Wayland or X11); use push/pull with --backend stdio instead"
+        );
+    }
+
+    let device = find_device(client, args.device.as_deref(), config).await?;
+    if device.state != SimulatorState::Booted {
+        anyhow::bail!("Simulator {} is not booted", device.name);
+    }
+
+    let mut last_local = backend.read()?;
+    let mut last_remote = client.get_pasteboard(&device.udid).await?.text;
+
+    println!(
+        "Syncing clipboard with {} via {} (Ctrl+C to stop)",
+        device.name,
+        backend.name()
+    );
+
+    let mut interval = tokio::time::interval(Duration::from_millis(interval_ms.max(100)));
+    let ctrl_c = tokio::signal::ctrl_c();
+    tokio::pin!(ctrl_c);
+    loop {
+        tokio::select! {
+            _ = &mut ctrl_c => break,
+            _ = interval.tick() => {}
+        }
+
+        let local = backend.read()?;
+        if local != last_local {
+            last_local = local;
+            if !last_local.is_empty() && last_local != last_remote {
+                debug!("Local clipboard changed, copying to simulator");
+                client.set_pasteboard(&device.udid, &last_local).await?;
+                last_remote = last_local.clone();
+            }
+            continue;
+        }
+
+        let remote = match client.get_pasteboard(&device.udid).await {
+            Ok(content) => content.text,
+            Err(e) => {
+                if !is_booted(client, &device.udid).await {
+                    println!("Simulator {} shut down, stopping clipboard sync", device.name);
+                    return Ok(());
+                }
+                return Err(e);
+            }
+        };
+        if remote != last_remote {
+            last_remote = remote;
+            if !last_remote.is_empty() && last_remote != last_local {
+                debug!("Simulator pasteboard changed, copying to local clipboard");
+                backend.write(&last_remote)?;
+                last_local = last_remote.clone();
+            }
+        }
+    }
+
+    println!("Stopped clipboard sync");
+    Ok(())
+}
+
+async fn is_booted(client: &AgentClient, udid: &str) -> bool {
+    match client.list_simulators().await {
+        Ok(response) => response
+            .devices
+            .iter()
+            .any(|d| d.udid == udid && d.state == SimulatorState::Booted),
+        Err(_) => false,
+    }
+}
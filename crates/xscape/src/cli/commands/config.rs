@@ -77,6 +77,7 @@ fn show_config() -> Result<()> {
     println!("\n[simulator]");
     println!("  preferred_device = {}", config.simulator.preferred_device);
     println!("  preferred_runtime = {:?}", config.simulator.preferred_runtime);
+    println!("  root_certificates = {:?}", config.simulator.root_certificates);
 
     Ok(())
 }
@@ -0,0 +1,127 @@
+use anyhow::{Context, Result};
+use flate2::read::GzDecoder;
+use xscape_common::{CliConfig, ContainerQuery};
+use std::path::PathBuf;
+use tar::Archive;
+
+use crate::agent_client::AgentClient;
+use crate::cli::commands::devices::find_device;
+use crate::cli::{ContainerArgs, ContainerCommands};
+
+/// Run app container commands
+pub async fn run(command: ContainerCommands, client: &AgentClient, config: &CliConfig) -> Result<()> {
+    match command {
+        ContainerCommands::Ls { path, target } => ls(path, target, client, config).await,
+        ContainerCommands::Pull {
+            path,
+            output,
+            target,
+        } => pull(path, output, target, client, config).await,
+        ContainerCommands::Push {
+            local,
+            path,
+            target,
+        } => push(local, path, target, client, config).await,
+    }
+}
+
+async fn ls(path: String, target: ContainerArgs, client: &AgentClient, config: &CliConfig) -> Result<()> {
+    let device = find_device(client, target.device.as_deref(), config).await?;
+    let query = ContainerQuery {
+        container: target.container,
+        path,
+    };
+
+    let listing = client
+        .list_container(&device.udid, &target.bundle_id, &query)
+        .await?;
+
+    if listing.entries.is_empty() {
+        println!("(empty)");
+        return Ok(());
+    }
+
+    for entry in &listing.entries {
+        let modified = entry
+            .modified
+            .map(|m| m.format("%Y-%m-%d %H:%M").to_string())
+            .unwrap_or_default();
+        if entry.is_dir {
+            println!("{:>10}  {:16}  {}/", "-", modified, entry.name);
+        } else {
+            println!("{:>10}  {:16}  {}", entry.size_bytes, modified, entry.name);
+        }
+    }
+
+    Ok(())
+}
+
+async fn pull(
+    path: String,
+    output: Option<PathBuf>,
+    target: ContainerArgs,
+    client: &AgentClient,
+    config: &CliConfig,
+) -> Result<()> {
+    let device = find_device(client, target.device.as_deref(), config).await?;
+    let query = ContainerQuery {
+        container: target.container,
+        path: path.clone(),
+    };
+
+    let (data, is_archive) = client
+        .download_container(&device.udid, &target.bundle_id, &query)
+        .await?;
+
+    if is_archive {
+        let dest = output.unwrap_or_else(|| PathBuf::from("."));
+        std::fs::create_dir_all(&dest)
+            .with_context(|| format!("Failed to create {}", dest.display()))?;
+        Archive::new(GzDecoder::new(data.as_slice()))
+            .unpack(&dest)
+            .context("Failed to extract directory archive")?;
+        println!("Extracted {} into {}", path, dest.display());
+        return Ok(());
+    }
+
+    let file_name = std::path::Path::new(&path)
+        .file_name()
+        .map(PathBuf::from)
+        .unwrap_or_else(|| PathBuf::from(&target.bundle_id));
+    let dest = match output {
+        Some(output) if output.is_dir() => output.join(file_name),
+        Some(output) => output,
+        None => file_name,
+    };
+    std::fs::write(&dest, &data).with_context(|| format!("Failed to write {}", dest.display()))?;
+    println!("Downloaded {} ({} bytes) to {}", path, data.len(), dest.display());
+
+    Ok(())
+}
+
+async fn push(
+    local: PathBuf,
+    path: String,
+    target: ContainerArgs,
+    client: &AgentClient,
+    config: &CliConfig,
+) -> Result<()> {
+    if !local.is_file() {
+        anyhow::bail!("File not found: {}", local.display());
+    }
+
+    let device = find_device(client, target.device.as_deref(), config).await?;
+    let query = ContainerQuery {
+        container: target.container,
+        path,
+    };
+
+    let response = client
+        .upload_container(&device.udid, &target.bundle_id, &query, &local)
+        .await?;
+
+    let verb = if response.replaced { "Replaced" } else { "Uploaded" };
+    println!("{} {} ({} bytes)", verb, response.path, response.size_bytes);
+
+    Ok(())
+}
@@ -1,13 +1,19 @@
 use anyhow::Result;
-use xscape_common::SimulatorState;
+use xscape_common::{CliConfig, Platform, SimulatorDevice, SimulatorState};
 
 use crate::agent_client::AgentClient;
 
 /// List available simulator devices
-pub async fn run(client: &AgentClient, _refresh: bool) -> Result<()> {
+pub async fn run(client: &AgentClient, _refresh: bool, platform: Option<Platform>) -> Result<()> {
     println!("Fetching available simulators...\n");
 
-    let response = client.list_simulators().await?;
+    let mut response = client.list_simulators().await?;
+    if let Some(platform) = platform {
+        if !platform.has_simulator() {
+            anyhow::bail!("{} apps run on the Mac and have no simulators", platform);
+        }
+        response.retain_platform(platform);
+    }
 
     // Group devices by runtime
     let mut devices_by_runtime: std::collections::HashMap<String, Vec<_>> =
@@ -72,3 +78,51 @@ pub async fn run(client: &AgentClient, _refresh: bool) -> Result<()> {
 
     Ok(())
 }
+
+/// Find a simulator by UDID or name, defaulting to the preferred device.
+/// Booted devices win when several names match.
+pub async fn find_device(
+    client: &AgentClient,
+    device: Option<&str>,
+    config: &CliConfig,
+) -> Result<SimulatorDevice> {
+    find_platform_device(client, device, None, config).await
+}
+
+/// Like `find_device`, limited to simulators of one platform. The preferred
+/// device is an iPhone, so other platforms default to any of their simulators.
+pub async fn find_platform_device(
+    client: &AgentClient,
+    device: Option<&str>,
+    platform: Option<Platform>,
+    config: &CliConfig,
+) -> Result<SimulatorDevice> {
+    let mut response = client.list_simulators().await?;
+    if let Some(platform) = platform {
+        response.retain_platform(platform);
+    }
+
+    let query = match (device, platform) {
+        (Some(device), _) => device,
+        (None, None | Some(Platform::Ios)) => &config.simulator.preferred_device,
+        (None, Some(_)) => "",
+    };
+
+    if let Some(device) = response.devices.iter().find(|d| d.udid == query) {
+        return Ok(device.clone());
+    }
+
+    let query_lower = query.to_lowercase();
+    let mut matches: Vec<_> = response
+        .devices
+        .into_iter()
+        .filter(|d| d.is_available && d.name.to_lowercase().contains(&query_lower))
+        .collect();
+    matches.sort_by_key(|d| d.state != SimulatorState::Booted);
+
+    matches.into_iter().next().ok_or_else(|| match platform {
+        Some(platform) if query.is_empty() => anyhow::anyhow!("No {} simulator available", platform),
+        Some(platform) => anyhow::anyhow!("{} device '{}' not found", platform, query),
+        None => anyhow::anyhow!("Device '{}' not found", query),
+    })
+}
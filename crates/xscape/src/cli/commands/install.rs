@@ -0,0 +1,71 @@
+use anyhow::Result;
+use xscape_common::{CliConfig, InstallAppRequest, RunAppRequest, SimulatorState};
+use std::collections::HashMap;
+
+use crate::agent_client::AgentClient;
+use crate::cli::commands::devices::find_device;
+use crate::cli::InstallArgs;
+use crate::tui::styles::Styles;
+
+/// Upload a prebuilt simulator app and install (or run) it
+pub async fn run(args: InstallArgs, client: &AgentClient, config: &CliConfig) -> Result<()> {
+    if !args.app.is_file() {
+        anyhow::bail!(
+            "{} is not a file; zip or tar the .app first (e.g., tar czf MyApp.app.tar.gz MyApp.app)",
+            args.app.display()
+        );
+    }
+
+    let root_certificates = super::keychain::load_root_certificates(config)?;
+    let device = find_device(client, args.device.as_deref(), config).await?;
+
+    let size = std::fs::metadata(&args.app)?.len();
+    println!("Uploading {} ({})...", args.app.display(), Styles::format_bytes(size));
+    let upload = client.upload_app(&args.app).await?;
+    println!("  App: {}", upload.app_name);
+    println!("  Bundle ID: {}", upload.bundle_id);
+    if !upload.platforms.is_empty() {
+        println!("  Platforms: {}", upload.platforms.join(", "));
+    }
+    println!("  Build ID: {}", upload.build_id);
+
+    if device.state != SimulatorState::Booted {
+        println!("\nBooting {}...", device.name);
+        client
+            .boot_simulator(&device.udid, root_certificates.clone())
+            .await?;
+    }
+
+    if args.run {
+        println!("\nLaunching app on {}...", device.name);
+        let response = client
+            .run_app(&RunAppRequest {
+                build_id: upload.build_id,
+                device_udid: device.udid.clone(),
+                launch_args: Vec::new(),
+                environment: HashMap::new(),
+                wait_for_exit: false,
+                permissions: HashMap::new(),
+                locale: None,
+                languages: Vec::new(),
+                root_certificates,
+            })
+            .await?;
+
+        println!("  App launched!");
+        if let Some(pid) = response.pid {
+            println!("  PID: {}", pid);
+        }
+        println!("  Session: {}", response.session_id);
+    } else {
+        client
+            .install_app(&InstallAppRequest {
+                build_id: upload.build_id,
+                device_udid: device.udid.clone(),
+            })
+            .await?;
+        println!("\nInstalled {} on {}", upload.bundle_id, device.name);
+    }
+
+    Ok(())
+}
@@ -1,12 +1,12 @@
 use anyhow::Result;
 use colored::Colorize;
 use dialoguer::{theme::ColorfulTheme, Confirm, FuzzySelect, Input, Select};
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 use crate::agent_client::AgentClient;
 use crate::config::load_config;
 use crate::tui::{progress, Screen};
-use xscape_common::SimulatorState;
+use xscape_common::{BuildConfiguration, Platform, SimulatorState};
 
 /// Cached agent status for status bar
 struct AgentStatus {
@@ -69,7 +69,7 @@ pub async fn run() -> Result<()> {
         match selection {
             0 => run_project_flow(&client, &config).await?,
             1 => build_project_flow(&client, &config).await?,
-            2 => simulators_flow(&client).await?,
+            2 => simulators_flow(&client, &config).await?,
             3 => vm_flow(&config).await?,
             4 => settings_flow(&config).await?,
             5 => setup_flow().await?,
@@ -148,10 +148,19 @@ async fn run_project_flow(client: &AgentClient, config: &xscape_common::CliConfi
 
     let args = crate::cli::RunArgs {
         project: project_path,
-        scheme,
+        scheme: Some(scheme),
+        configuration: BuildConfiguration::Debug,
+        platform: device.platform,
+        overrides: Default::default(),
         device: Some(device.name.clone()),
         args: vec![],
         env: vec![],
+        grant: vec![],
+        locale: None,
+        language: vec![],
+        offline: false,
+        no_preflight: false,
+        detach: false,
         no_logs: false,
     };
 
@@ -194,8 +203,8 @@ async fn build_project_flow(client: &AgentClient, config: &xscape_common::CliCon
     println!();
 
     let devices = get_devices(client).await?;
-    let device_name = if !devices.is_empty() {
-        select_simulator(&devices)?.map(|d| d.name.clone())
+    let device = if !devices.is_empty() {
+        select_simulator(&devices)?
     } else {
         None
     };
@@ -207,8 +216,8 @@ async fn build_project_flow(client: &AgentClient, config: &xscape_common::CliCon
     println!();
     Screen::kv("Project", &project_path.display().to_string());
     Screen::kv("Scheme", &scheme);
-    if let Some(ref dev) = device_name {
-        Screen::kv("Target", dev);
+    if let Some(ref dev) = device {
+        Screen::kv("Target", &dev.name);
     }
     println!();
 
@@ -227,10 +236,15 @@ async fn build_project_flow(client: &AgentClient, config: &xscape_common::CliCon
 
     let args = crate::cli::BuildArgs {
         project: project_path,
-        scheme,
-        configuration: "debug".to_string(),
-        device: device_name,
+        scheme: Some(scheme),
+        configuration: BuildConfiguration::Debug,
+        platform: device.as_ref().map(|d| d.platform).unwrap_or_default(),
+        device: device.map(|d| d.name),
+        overrides: Default::default(),
         clean: false,
+        test: false,
+        offline: false,
+        no_preflight: false,
         no_logs: false,
     };
 
@@ -244,7 +258,7 @@ async fn build_project_flow(client: &AgentClient, config: &xscape_common::CliCon
 }
 
 /// Simulators management flow
-async fn simulators_flow(client: &AgentClient) -> Result<()> {
+async fn simulators_flow(client: &AgentClient, config: &xscape_common::CliConfig) -> Result<()> {
     loop {
         Screen::clear();
         Screen::header(&["Simulators"]);
@@ -264,7 +278,7 @@ async fn simulators_flow(client: &AgentClient) -> Result<()> {
 
         match selection {
             0 => list_simulators(client).await?,
-            1 => boot_simulator(client).await?,
+            1 => boot_simulator(client, config).await?,
             2 => shutdown_simulator(client).await?,
             _ => break,
         }
@@ -308,7 +322,7 @@ async fn list_simulators(client: &AgentClient) -> Result<()> {
     Ok(())
 }
 
-async fn boot_simulator(client: &AgentClient) -> Result<()> {
+async fn boot_simulator(client: &AgentClient, config: &xscape_common::CliConfig) -> Result<()> {
     Screen::clear();
     Screen::header(&["Simulators", "Boot"]);
 
@@ -329,7 +343,8 @@ async fn boot_simulator(client: &AgentClient) -> Result<()> {
     println!();
     let pb = progress::spinner(&format!("Booting {}...", device.name));
 
-    match client.boot_simulator(&device.udid).await {
+    let root_certificates = super::keychain::load_root_certificates(config)?;
+    match client.boot_simulator(&device.udid, root_certificates).await {
         Ok(_) => progress::spinner_success(&pb, &format!("{} is now running", device.name)),
         Err(e) => progress::spinner_error(&pb, &format!("Failed: {}", e)),
     }
@@ -500,8 +515,8 @@ fn select_project() -> Result<Option<PathBuf>> {
     Ok(Some(project_path))
 }
 
-fn select_scheme(project_path: &PathBuf) -> Result<Option<String>> {
-    let schemes = find_schemes(project_path)?;
+fn select_scheme(project_path: &Path) -> Result<Option<String>> {
+    let schemes = crate::project::find_schemes(project_path)?;
 
     if schemes.is_empty() {
         Screen::error("No schemes found in project");
@@ -535,6 +550,7 @@ struct DeviceInfo {
     udid: String,
     name: String,
     runtime: String,
+    platform: Platform,
     is_booted: bool,
 }
 
@@ -605,6 +621,7 @@ async fn get_devices(client: &AgentClient) -> Result<Vec<DeviceInfo>> {
         .into_iter()
         .filter(|d| d.is_available)
         .map(|d| DeviceInfo {
+            platform: d.platform().unwrap_or_default(),
             udid: d.udid,
             name: d.name,
             runtime: d.runtime,
@@ -636,40 +653,3 @@ fn expand_tilde(path: &str) -> String {
     path.to_string()
 }
 
-fn find_schemes(project_path: &PathBuf) -> Result<Vec<String>> {
-    let mut schemes = Vec::new();
-
-    for entry in std::fs::read_dir(project_path)? {
-        let entry = entry?;
-        let path = entry.path();
-
-        if path.extension().map_or(false, |e| e == "xcodeproj" || e == "xcworkspace") {
-            let schemes_dir = path.join("xcshareddata/xcschemes");
-            if schemes_dir.exists() {
-                for scheme_entry in std::fs::read_dir(schemes_dir)? {
-                    let scheme_entry = scheme_entry?;
-                    let scheme_path = scheme_entry.path();
-                    if scheme_path.extension().map_or(false, |e| e == "xcscheme") {
-                        if let Some(name) = scheme_path.file_stem() {
-                            schemes.push(name.to_string_lossy().to_string());
-                        }
-                    }
-                }
-            }
-        }
-    }
-
-    if schemes.is_empty() {
-        for entry in std::fs::read_dir(project_path)? {
-            let entry = entry?;
-            let path = entry.path();
-            if path.extension().map_or(false, |e| e == "xcodeproj") {
-                if let Some(name) = path.file_stem() {
-                    schemes.push(name.to_string_lossy().to_string());
-                }
-            }
-        }
-    }
-
-    Ok(schemes)
-}
@@ -0,0 +1,82 @@
+use anyhow::{Context, Result};
+use xscape_common::{
+    certificate_to_pem, parse_certificates, CliConfig, KeychainAction, KeychainRequest,
+};
+use std::path::Path;
+
+use crate::agent_client::AgentClient;
+use crate::cli::commands::devices::find_device;
+use crate::cli::KeychainCommands;
+
+/// Run keychain commands
+pub async fn run(command: KeychainCommands, client: &AgentClient, config: &CliConfig) -> Result<()> {
+    let (action, file, device) = match command {
+        KeychainCommands::AddRootCert { file, device } => {
+            (KeychainAction::AddRootCert, Some(file), device)
+        }
+        KeychainCommands::AddCert { file, device } => (KeychainAction::AddCert, Some(file), device),
+        KeychainCommands::Reset { device } => (KeychainAction::Reset, None, device),
+    };
+
+    let certificates = match file {
+        Some(ref file) => read_certificates(file)?,
+        None => Vec::new(),
+    };
+
+    let device = find_device(client, device.as_deref(), config).await?;
+
+    if action == KeychainAction::Reset {
+        client
+            .update_keychain(
+                &device.udid,
+                &KeychainRequest {
+                    action,
+                    certificate: None,
+                },
+            )
+            .await?;
+        println!("Reset keychain on {}", device.name);
+        return Ok(());
+    }
+
+    for pem in certificates {
+        client
+            .update_keychain(
+                &device.udid,
+                &KeychainRequest {
+                    action,
+                    certificate: Some(pem),
+                },
+            )
+            .await?;
+    }
+
+    let file = file.unwrap_or_default();
+    match action {
+        KeychainAction::AddRootCert => {
+            println!("Trusted {} as a root certificate on {}", file.display(), device.name)
+        }
+        _ => println!("Added {} to the keychain on {}", file.display(), device.name),
+    }
+
+    Ok(())
+}
+
+/// Read the configured root certificates as PEM, failing on invalid files
+pub fn load_root_certificates(config: &CliConfig) -> Result<Vec<String>> {
+    let mut certificates = Vec::new();
+    for path in &config.simulator.root_certificates {
+        certificates.extend(read_certificates(path)?);
+    }
+    Ok(certificates)
+}
+
+/// Read and validate a PEM or DER file, returning one PEM string per certificate
+fn read_certificates(path: &Path) -> Result<Vec<String>> {
+    let data = std::fs::read(path)
+        .with_context(|| format!("Failed to read certificate {}", path.display()))?;
+    let certificates = parse_certificates(&data)
+        .with_context(|| format!("Invalid certificate {}", path.display()))?;
+
+    Ok(certificates.iter().map(|der| certificate_to_pem(der)).collect())
+}
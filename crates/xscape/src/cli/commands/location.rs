@@ -0,0 +1,80 @@
+use anyhow::{Context, Result};
+use xscape_common::{parse_route, CliConfig, Coordinate, PlayRouteRequest, SetLocationRequest};
+
+use crate::agent_client::AgentClient;
+use crate::cli::commands::devices::find_device;
+use crate::cli::LocationCommands;
+
+/// Run location simulation commands
+pub async fn run(command: LocationCommands, client: &AgentClient, config: &CliConfig) -> Result<()> {
+    match command {
+        LocationCommands::Set {
+            latitude,
+            longitude,
+            device,
+        } => {
+            let location = Coordinate::new(latitude, longitude);
+            location.validate()?;
+
+            let device = find_device(client, device.as_deref(), config).await?;
+            client
+                .set_location(
+                    &device.udid,
+                    &SetLocationRequest {
+                        latitude,
+                        longitude,
+                    },
+                )
+                .await?;
+
+            println!("Location of {} set to {}", device.name, location);
+        }
+        LocationCommands::Clear { device } => {
+            let device = find_device(client, device.as_deref(), config).await?;
+            client.clear_location(&device.udid).await?;
+
+            println!("Location of {} cleared", device.name);
+        }
+        LocationCommands::Play {
+            route,
+            device,
+            speed,
+        } => {
+            if !(speed.is_finite() && speed > 0.0) {
+                anyhow::bail!("Speed must be a positive number");
+            }
+
+            // Validate the route locally before sending it to the agent
+            let content = std::fs::read_to_string(&route)
+                .with_context(|| format!("Failed to read route file {:?}", route))?;
+            let file_name = route
+                .file_name()
+                .map(|n| n.to_string_lossy().to_string())
+                .unwrap_or_default();
+            let waypoints = parse_route(&file_name, &content)
+                .with_context(|| format!("Invalid route file {:?}", route))?;
+
+            let device = find_device(client, device.as_deref(), config).await?;
+            println!(
+                "Playing {} waypoints on {} at {} m/s",
+                waypoints.len(),
+                device.name,
+                speed
+            );
+
+            client
+                .play_route(
+                    &device.udid,
+                    &PlayRouteRequest {
+                        waypoints,
+                        speed_mps: speed,
+                    },
+                )
+                .await?;
+
+            println!("Route started. Use 'xscape location clear' to stop.");
+        }
+    }
+
+    Ok(())
+}
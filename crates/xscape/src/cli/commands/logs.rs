@@ -1,6 +1,7 @@
 use anyhow::Result;
 use futures_util::StreamExt;
 use tokio_tungstenite::connect_async;
+use uuid::Uuid;
 
 use crate::cli::LogsArgs;
 
@@ -10,11 +11,7 @@ pub async fn run(args: LogsArgs, agent_url: &str) -> Result<()> {
         anyhow::anyhow!("Build ID is required. Use --build-id <uuid>")
     })?;
 
-    // Convert HTTP URL to WebSocket URL
-    let ws_url = agent_url
-        .replace("http://", "ws://")
-        .replace("https://", "wss://");
-    let logs_url = format!("{}/logs/{}", ws_url, build_id);
+    let logs_url = logs_url(agent_url, build_id);
 
     println!("Connecting to {}...", logs_url);
 
@@ -49,6 +46,14 @@ pub async fn run(args: LogsArgs, agent_url: &str) -> Result<()> {
     Ok(())
 }
 
+/// WebSocket URL streaming the logs of a build
+pub fn logs_url(agent_url: &str, build_id: Uuid) -> String {
+    let ws_url = agent_url
+        .replace("http://", "ws://")
+        .replace("https://", "wss://");
+    format!("{}/logs/{}", ws_url, build_id)
+}
+
 fn print_log_message(msg: &xscape_common::LogMessage) {
     use xscape_common::{LogLevel, LogMessage};
 
@@ -85,6 +90,7 @@ fn print_log_message(msg: &xscape_common::LogMessage) {
             timestamp,
             event,
             message,
+            ..
         } => {
             println!("\x1b[35m==> {:?}: {}\x1b[0m", event, message);
         }
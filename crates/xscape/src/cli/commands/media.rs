@@ -0,0 +1,37 @@
+use anyhow::Result;
+use xscape_common::{CliConfig, MediaKind};
+
+use crate::agent_client::AgentClient;
+use crate::cli::commands::devices::find_device;
+use crate::cli::MediaCommands;
+
+/// Run media commands
+pub async fn run(command: MediaCommands, client: &AgentClient, config: &CliConfig) -> Result<()> {
+    match command {
+        MediaCommands::Add { files, device } => {
+            for file in &files {
+                if !file.is_file() {
+                    anyhow::bail!("File not found: {}", file.display());
+                }
+                let name = file.file_name().and_then(|n| n.to_str()).unwrap_or_default();
+                if MediaKind::from_file_name(name).is_none() {
+                    anyhow::bail!(
+                        "Unsupported media file: {} (expected an image, video or .vcf)",
+                        file.display()
+                    );
+                }
+            }
+
+            let device = find_device(client, device.as_deref(), config).await?;
+
+            println!("Uploading {} files to {}...", files.len(), device.name);
+            let response = client.add_media(&device.udid, &files).await?;
+
+            for media in &response.added {
+                println!("  Added {} ({})", media.file_name, media.kind);
+            }
+        }
+    }
+
+    Ok(())
+}
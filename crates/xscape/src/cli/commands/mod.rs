@@ -1,7 +1,22 @@
+pub mod archive;
+pub mod artifacts;
 pub mod build;
+pub mod clipboard;
 pub mod config;
+pub mod container;
 pub mod devices;
+pub mod install;
 pub mod interactive;
+pub mod keychain;
+pub mod location;
 pub mod logs;
+pub mod media;
+pub mod new;
+pub mod open_url;
+pub mod privacy;
+pub mod project;
+pub mod push;
 pub mod run;
+pub mod sim;
+pub mod symbolicate;
 pub mod vm;
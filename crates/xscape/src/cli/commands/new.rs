@@ -0,0 +1,40 @@
+use anyhow::Result;
+use std::path::PathBuf;
+
+use crate::cli::NewArgs;
+use crate::project::template::{self, TemplateOptions};
+
+/// Run the new command
+pub async fn run(args: NewArgs) -> Result<()> {
+    // Xcode writes whole versions as "17.0"
+    let mut deployment_target = args.deployment_target;
+    if !deployment_target.contains('.') {
+        deployment_target.push_str(".0");
+    }
+
+    let options = TemplateOptions {
+        name: args.name.clone(),
+        bundle_id_prefix: args.bundle_id_prefix,
+        deployment_target,
+    };
+    options.validate(args.template).map_err(anyhow::Error::msg)?;
+
+    let dir = args.output.unwrap_or_else(|| PathBuf::from(&args.name));
+    let files = template::generate(args.template, &options, &dir)?;
+
+    println!("Created {} ({} template)", dir.display(), args.template);
+    if args.template.is_app() {
+        println!("  Bundle ID: {}", options.bundle_id());
+    }
+    println!("  Deployment target: iOS {}", options.deployment_target);
+    println!("  Files: {}", files.len());
+
+    println!("\nNext steps:");
+    println!("  cd {}", dir.display());
+    if args.template.is_app() {
+        println!("  xscape run");
+    } else {
+        println!("  swift build");
+    }
+    Ok(())
+}
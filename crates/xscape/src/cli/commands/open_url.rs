@@ -0,0 +1,61 @@
+use anyhow::Result;
+use xscape_common::{CliConfig, OpenUrlRequest};
+
+use crate::agent_client::AgentClient;
+use crate::cli::commands::devices::find_device;
+use crate::cli::OpenUrlArgs;
+
+/// Open a URL in the simulator, or list the app's URL schemes
+pub async fn run(args: OpenUrlArgs, client: &AgentClient, config: &CliConfig) -> Result<()> {
+    if args.list_schemes {
+        let build_id = match args.build {
+            Some(id) => id,
+            None => client.get_latest_build().await?.build_id,
+        };
+
+        let response = client.get_url_schemes(build_id).await?;
+        println!(
+            "URL schemes for {} (build {}):",
+            response.bundle_id.as_deref().unwrap_or("unknown app"),
+            build_id
+        );
+
+        if response.url_types.iter().all(|t| t.schemes.is_empty()) {
+            println!("  (none registered)");
+        }
+        for url_type in &response.url_types {
+            for scheme in &url_type.schemes {
+                match &url_type.name {
+                    Some(name) => println!("  {}://  ({})", scheme, name),
+                    None => println!("  {}://", scheme),
+                }
+            }
+        }
+        return Ok(());
+    }
+
+    let url = args
+        .url
+        .ok_or_else(|| anyhow::anyhow!("URL is required"))?;
+    let device = find_device(client, args.device.as_deref(), config).await?;
+
+    let build_id = if args.launch {
+        match args.build {
+            Some(id) => Some(id),
+            None => Some(client.get_latest_build().await?.build_id),
+        }
+    } else {
+        None
+    };
+
+    let response = client
+        .open_url(&device.udid, &OpenUrlRequest { url, build_id })
+        .await?;
+
+    if response.launched_app {
+        println!("App was not installed; installed and launched it first");
+    }
+    println!("Opened {} on {}", response.url, device.name);
+
+    Ok(())
+}
@@ -0,0 +1,45 @@
+use anyhow::Result;
+use xscape_common::{CliConfig, PrivacyAction, PrivacyRequest};
+
+use crate::agent_client::AgentClient;
+use crate::cli::commands::devices::find_device;
+use crate::cli::{PrivacyArgs, PrivacyCommands};
+
+/// Run privacy permission commands
+pub async fn run(command: PrivacyCommands, client: &AgentClient, config: &CliConfig) -> Result<()> {
+    let (action, args) = match command {
+        PrivacyCommands::Grant(args) => (PrivacyAction::Grant, args),
+        PrivacyCommands::Revoke(args) => (PrivacyAction::Revoke, args),
+        PrivacyCommands::Reset(args) => (PrivacyAction::Reset, args),
+    };
+    set_privacy(action, args, client, config).await
+}
+
+async fn set_privacy(
+    action: PrivacyAction,
+    args: PrivacyArgs,
+    client: &AgentClient,
+    config: &CliConfig,
+) -> Result<()> {
+    if args.bundle_id.is_none() && action != PrivacyAction::Reset {
+        anyhow::bail!("--bundle-id is required to {} a permission", action);
+    }
+
+    let device = find_device(client, args.device.as_deref(), config).await?;
+
+    let request = PrivacyRequest {
+        action,
+        service: args.service,
+        bundle_id: args.bundle_id,
+    };
+    let response = client.set_privacy(&device.udid, &request).await?;
+
+    let target = response.bundle_id.as_deref().unwrap_or("all apps");
+    match response.action {
+        PrivacyAction::Grant => println!("Granted {} to {} on {}", response.service, target, device.name),
+        PrivacyAction::Revoke => println!("Revoked {} from {} on {}", response.service, target, device.name),
+        PrivacyAction::Reset => println!("Reset {} for {} on {}", response.service, target, device.name),
+    }
+
+    Ok(())
+}
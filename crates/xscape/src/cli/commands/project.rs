@@ -0,0 +1,178 @@
+use anyhow::{Context, Result};
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+use xscape_common::CliConfig;
+
+use crate::cli::ProjectCommands;
+use crate::project::edit::{self, ProjectEditor};
+use crate::project::preflight::{self, PreflightIssue};
+use crate::project::xcodeproj::{self, ProjectSummary, XcodeProject};
+
+/// Output of `xscape project inspect`
+#[derive(Serialize)]
+struct Inspection {
+    #[serde(flatten)]
+    project: ProjectSummary,
+    issues: Vec<PreflightIssue>,
+}
+
+/// Run project commands
+pub async fn run(command: ProjectCommands, config: &CliConfig) -> Result<()> {
+    match command {
+        ProjectCommands::Inspect { project } => inspect(&project, config),
+        ProjectCommands::Add {
+            files,
+            target,
+            project,
+            check,
+        } => {
+            if check {
+                check_unreferenced(&project, config)
+            } else {
+                add(&files, target, &project)
+            }
+        }
+        ProjectCommands::Remove {
+            files,
+            target,
+            project,
+        } => remove(&files, &target, &project),
+    }
+}
+
+fn inspect(path: &Path, config: &CliConfig) -> Result<()> {
+    let project_path = resolve_project(path)?;
+    let project = XcodeProject::open(&project_path)?;
+
+    // Check against the directory that would be uploaded
+    let root = project_path
+        .parent()
+        .map(Path::to_path_buf)
+        .unwrap_or_default();
+    let mut issues = Vec::new();
+    preflight::check_project(&project, &root, &config.project.exclude_patterns, &mut issues);
+
+    let inspection = Inspection {
+        project: project.summary(),
+        issues,
+    };
+    println!("{}", serde_json::to_string_pretty(&inspection)?);
+    Ok(())
+}
+
+fn add(files: &[PathBuf], targets: Vec<String>, project: &Path) -> Result<()> {
+    let bundle = resolve_project(project)?;
+    let mut editor = ProjectEditor::open(&bundle)?;
+    let targets = if targets.is_empty() {
+        editor.default_targets()?
+    } else {
+        targets
+    };
+
+    for file in files {
+        let path = file
+            .canonicalize()
+            .with_context(|| format!("{} does not exist", file.display()))?;
+        if path.is_dir() && edit::Phase::for_path(&path).is_none() {
+            anyhow::bail!("{} is a directory; add the files in it instead", file.display());
+        }
+        let relative = source_relative(&path, &editor.project().source_root)?;
+        for change in editor.add_file(&relative, &targets)? {
+            println!("{}", change);
+        }
+    }
+
+    save(&editor, &bundle)
+}
+
+fn remove(files: &[PathBuf], targets: &[String], project: &Path) -> Result<()> {
+    let bundle = resolve_project(project)?;
+    let mut editor = ProjectEditor::open(&bundle)?;
+
+    for file in files {
+        // The file may already be deleted, so only its directory has to exist
+        let path = match file.canonicalize() {
+            Ok(path) => path,
+            Err(_) => {
+                let dir = match file.parent().filter(|p| !p.as_os_str().is_empty()) {
+                    Some(dir) => dir.canonicalize(),
+                    None => std::env::current_dir(),
+                }
+                .with_context(|| format!("{} does not exist", file.display()))?;
+                dir.join(file.file_name().unwrap_or_default())
+            }
+        };
+        let relative = source_relative(&path, &editor.project().source_root)?;
+        for change in editor.remove_file(&relative, targets)? {
+            println!("{}", change);
+        }
+    }
+
+    save(&editor, &bundle)
+}
+
+fn save(editor: &ProjectEditor, bundle: &Path) -> Result<()> {
+    if editor.save()? {
+        println!("Updated {}", bundle.display());
+    } else {
+        println!("No changes to {}", bundle.display());
+    }
+    Ok(())
+}
+
+/// List source files on disk that no target builds; fails if there are any
+fn check_unreferenced(project: &Path, config: &CliConfig) -> Result<()> {
+    let bundle = resolve_project(project)?;
+    let project = XcodeProject::open(&bundle)?;
+    let root = bundle.parent().unwrap_or(&bundle);
+
+    let files = edit::unreferenced_files(&project, root, &config.project.exclude_patterns);
+    if files.is_empty() {
+        println!("Every source file belongs to a target");
+        return Ok(());
+    }
+    for file in &files {
+        println!("{}", file);
+    }
+    anyhow::bail!(
+        "{} file(s) are not in any target (add them with `xscape project add`)",
+        files.len()
+    )
+}
+
+/// A file's path relative to the project's source root
+fn source_relative(path: &Path, source_root: &Path) -> Result<PathBuf> {
+    path.strip_prefix(source_root)
+        .map(Path::to_path_buf)
+        .map_err(|_| anyhow::anyhow!("{} is outside the project directory {}", path.display(), source_root.display()))
+}
+
+/// The .xcodeproj a path names: the bundle itself, its project.pbxproj, or
+/// the only project in a directory
+pub fn resolve_project(path: &Path) -> Result<PathBuf> {
+    let path = path.canonicalize()?;
+    if path.extension().is_some_and(|e| e == "xcodeproj") {
+        return Ok(path);
+    }
+    if path.extension().is_some_and(|e| e == "pbxproj") {
+        return Ok(path.parent().unwrap_or(&path).to_path_buf());
+    }
+
+    let mut projects = xcodeproj::find_projects(&path);
+    match projects.len() {
+        0 => anyhow::bail!("No .xcodeproj found in {}", path.display()),
+        1 => Ok(projects.remove(0)),
+        _ => {
+            let names: Vec<String> = projects
+                .iter()
+                .filter_map(|p| p.file_name())
+                .map(|n| n.to_string_lossy().into_owned())
+                .collect();
+            anyhow::bail!(
+                "{} contains several projects ({}); pass one with --project",
+                path.display(),
+                names.join(", ")
+            )
+        }
+    }
+}
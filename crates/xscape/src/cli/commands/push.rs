@@ -0,0 +1,43 @@
+use anyhow::{Context, Result};
+use xscape_common::{validate_push_payload, CliConfig, PushNotificationRequest};
+
+use crate::agent_client::AgentClient;
+use crate::cli::commands::devices::find_device;
+use crate::cli::PushArgs;
+
+/// Send a push notification to an app in the simulator
+pub async fn run(args: PushArgs, client: &AgentClient, config: &CliConfig) -> Result<()> {
+    let content = std::fs::read_to_string(&args.payload)
+        .with_context(|| format!("Failed to read payload file {:?}", args.payload))?;
+    let payload: serde_json::Value =
+        serde_json::from_str(&content).context("Payload is not valid JSON")?;
+    validate_push_payload(&payload)?;
+
+    let device = find_device(client, args.device.as_deref(), config).await?;
+
+    let bundle_id = match args.bundle_id {
+        Some(bundle_id) => bundle_id,
+        None => {
+            // Prefer the latest app run on this device, then the latest overall
+            let sessions = client.list_sessions().await?.sessions;
+            let session = sessions
+                .iter()
+                .find(|s| s.device_udid == device.udid)
+                .or_else(|| sessions.first())
+                .ok_or_else(|| {
+                    anyhow::anyhow!("No recent run session found. Use --bundle-id <id>")
+                })?;
+            session.bundle_id.clone()
+        }
+    };
+
+    let request = PushNotificationRequest {
+        bundle_id: bundle_id.clone(),
+        payload,
+    };
+    client.send_push(&device.udid, &request).await?;
+
+    println!("Push notification sent to {} on {}", bundle_id, device.name);
+
+    Ok(())
+}
@@ -1,11 +1,15 @@
 use anyhow::Result;
+use futures_util::StreamExt;
 use xscape_common::{
-    BuildConfiguration, BuildDestination, BuildRequest, BuildStatus, CliConfig, RunAppRequest,
-    SimulatorState,
+    BuildDestination, BuildRequest, BuildStatus, CliConfig, CrashReportResponse, CrashSummary,
+    LogMessage, PrivacyAction, RunAppRequest, SimulatorState, SystemEventType, validate_locale,
 };
 use std::collections::HashMap;
+use std::path::PathBuf;
 use std::time::Duration;
 use tokio::time::sleep;
+use tokio_tungstenite::{connect_async, tungstenite::Message};
+use uuid::Uuid;
 
 use crate::agent_client::AgentClient;
 use crate::cli::RunArgs;
@@ -13,11 +17,18 @@ use crate::project;
 
 /// Run the run command (build + run in simulator)
 pub async fn run(args: RunArgs, client: &AgentClient, config: &CliConfig) -> Result<()> {
+    if let Some(ref locale) = args.locale {
+        validate_locale(locale).map_err(anyhow::Error::msg)?;
+    }
+
+    let root_certificates = super::keychain::load_root_certificates(config)?;
+
     let project_path = args.project.canonicalize()?;
     let project_name = project::get_project_name(&project_path);
+    let (build_settings, xcconfig) = super::build::build_overrides(&args.overrides, &project_path)?;
+    super::build::preflight(&project_path, config, args.no_preflight)?;
 
     println!("Building and running: {}", project_name);
-    println!("  Scheme: {}", args.scheme);
 
     // Check agent health first
     let health = client.health().await?;
@@ -31,22 +42,21 @@ pub async fn run(args: RunArgs, client: &AgentClient, config: &CliConfig) -> Res
     );
 
     // Get target device
-    let device_name = args
-        .device
-        .clone()
-        .unwrap_or_else(|| config.simulator.preferred_device.clone());
-    println!("  Device: {}", device_name);
-
-    // Find the device UDID
-    let simulators = client.list_simulators().await?;
-    let device = simulators
-        .devices
-        .iter()
-        .find(|d| {
-            d.name.to_lowercase().contains(&device_name.to_lowercase()) && d.is_available
-        })
-        .ok_or_else(|| anyhow::anyhow!("Device '{}' not found", device_name))?;
-
+    if !args.platform.has_simulator() {
+        anyhow::bail!(
+            "{} apps run on the Mac, not in a simulator; use `xscape build --platform {}`",
+            args.platform,
+            args.platform.as_str()
+        );
+    }
+    let device = super::devices::find_platform_device(
+        client,
+        args.device.as_deref(),
+        Some(args.platform),
+        config,
+    )
+    .await?;
+    println!("  Device: {} ({})", device.name, device.runtime);
     println!("  Device UDID: {}", device.udid);
 
     // Create project tarball
@@ -65,16 +75,23 @@ pub async fn run(args: RunArgs, client: &AgentClient, config: &CliConfig) -> Res
         println!("  Synced {} files", sync_result.files_extracted);
     }
 
+    let scheme = super::build::resolve_scheme(args.scheme.clone(), sync_result.project_id, client, config).await?;
+    println!("  Scheme: {}", scheme);
+
     // Start build
     println!("\nBuilding...");
     let build_request = BuildRequest {
         project_id: sync_result.project_id,
         project_file: None,
-        scheme: args.scheme.clone(),
-        configuration: BuildConfiguration::Debug,
-        destination: BuildDestination::ios_simulator(&device_name),
+        scheme: scheme.clone(),
+        configuration: args.configuration,
+        destination: BuildDestination::simulator(args.platform, &device.name),
+        build_settings,
+        xcconfig,
         extra_args: vec![],
         clean: false,
+        test: false,
+        offline: args.offline,
     };
 
     let build_response = client.build(&build_request).await?;
@@ -88,6 +105,9 @@ pub async fn run(args: RunArgs, client: &AgentClient, config: &CliConfig) -> Res
         match status.status {
             BuildStatus::Succeeded => {
                 println!("  Build succeeded ({:.1}s)", status.duration_secs.unwrap_or(0.0));
+                if let Some(ref app_info) = status.app_info {
+                    super::build::print_app_info(app_info);
+                }
                 break status;
             }
             BuildStatus::Failed => {
@@ -110,7 +130,9 @@ pub async fn run(args: RunArgs, client: &AgentClient, config: &CliConfig) -> Res
     // Boot simulator if needed
     if device.state != SimulatorState::Booted {
         println!("\nBooting simulator...");
-        client.boot_simulator(&device.udid).await?;
+        client
+            .boot_simulator(&device.udid, root_certificates.clone())
+            .await?;
         println!("  Simulator booted");
     }
 
@@ -122,6 +144,13 @@ pub async fn run(args: RunArgs, client: &AgentClient, config: &CliConfig) -> Res
         }
     }
 
+    // Permissions to grant before launch
+    let permissions = args
+        .grant
+        .iter()
+        .map(|service| (*service, PrivacyAction::Grant))
+        .collect();
+
     // Run app
     println!("\nLaunching app...");
     let run_request = RunAppRequest {
@@ -130,6 +159,24 @@ pub async fn run(args: RunArgs, client: &AgentClient, config: &CliConfig) -> Res
         launch_args: args.args.clone(),
         environment,
         wait_for_exit: false,
+        permissions,
+        locale: args.locale.clone(),
+        languages: args.language.clone(),
+        root_certificates,
+    };
+
+    // Subscribe before launching so an early crash isn't missed
+    let events = if args.detach {
+        None
+    } else {
+        let url = super::logs::logs_url(client.base_url(), build_response.build_id);
+        match connect_async(&url).await {
+            Ok((stream, _)) => Some(stream),
+            Err(e) => {
+                println!("  Warning: cannot watch app for crashes: {}", e);
+                None
+            }
+        }
     };
 
     let run_result = client.run_app(&run_request).await?;
@@ -144,5 +191,89 @@ pub async fn run(args: RunArgs, client: &AgentClient, config: &CliConfig) -> Res
     println!("\nApp is running in the simulator.");
     println!("Use 'xscape vm vnc' to view the simulator GUI.");
 
-    Ok(())
+    let Some(events) = events else {
+        return Ok(());
+    };
+    println!("Watching for crashes (Ctrl+C to stop)...");
+
+    let (_, mut read) = events.split();
+    let ctrl_c = tokio::signal::ctrl_c();
+    tokio::pin!(ctrl_c);
+    let event = loop {
+        let msg = tokio::select! {
+            _ = &mut ctrl_c => return Ok(()),
+            msg = read.next() => msg,
+        };
+        let text = match msg {
+            Some(Ok(Message::Text(text))) => text,
+            Some(Ok(_)) => continue,
+            Some(Err(_)) | None => return Ok(()),
+        };
+        if let Ok(LogMessage::SystemEvent {
+            event,
+            message,
+            session_id: Some(session_id),
+            ..
+        }) = serde_json::from_str::<LogMessage>(&text)
+        {
+            if session_id == run_result.session_id
+                && matches!(event, SystemEventType::AppCrashed | SystemEventType::AppExited)
+            {
+                break (event, message);
+            }
+        }
+    };
+
+    match event {
+        (SystemEventType::AppCrashed, _) => {
+            let source_root = project_path.to_string_lossy();
+            let report = client
+                .get_crash_report(run_result.session_id, Some(&source_root))
+                .await?;
+            print_crash_report(&report.bundle_id, &report.summary);
+            let path = save_crash_report(&report)?;
+            println!("\nFull report saved to {}", path.display());
+            anyhow::bail!("App crashed");
+        }
+        (_, message) => {
+            println!("\n{}", message);
+            Ok(())
+        }
+    }
+}
+
+/// Print the crash summary: exception, reason and crashing thread
+pub fn print_crash_report(bundle_id: &str, summary: &CrashSummary) {
+    println!("\n\x1b[31mApp crashed!\x1b[0m");
+    println!("  Bundle ID: {}", bundle_id);
+    match (&summary.exception_type, &summary.signal) {
+        (Some(kind), Some(signal)) => println!("  Exception: {} ({})", kind, signal),
+        (Some(kind), None) => println!("  Exception: {}", kind),
+        (None, Some(signal)) => println!("  Signal: {}", signal),
+        (None, None) => {}
+    }
+    if let Some(ref reason) = summary.termination_reason {
+        println!("  Reason: {}", reason);
+    }
+
+    if let Some(thread) = summary.crashed_thread {
+        match summary.crashed_thread_name {
+            Some(ref name) => println!("\nThread {} crashed ({}):", thread, name),
+            None => println!("\nThread {} crashed:", thread),
+        }
+        for (i, frame) in summary.frames.iter().enumerate() {
+            println!("  {:<3} {}", i, frame);
+        }
+    }
+}
+
+/// Write the full .ips report to the current directory
+fn save_crash_report(report: &CrashReportResponse) -> Result<PathBuf> {
+    let path = PathBuf::from(format!("{}-{}.ips", report.bundle_id, short_id(report.session_id)));
+    std::fs::write(&path, &report.report)?;
+    Ok(path)
+}
+
+fn short_id(id: Uuid) -> String {
+    id.to_string().chars().take(8).collect()
 }
@@ -0,0 +1,80 @@
+use anyhow::Result;
+use xscape_common::{CliConfig, StatusBarOverride, UiSettingsRequest};
+
+use crate::agent_client::AgentClient;
+use crate::cli::commands::devices::find_device;
+use crate::cli::{SimCommands, StatusBarArgs};
+
+/// Run simulator UI commands
+pub async fn run(command: SimCommands, client: &AgentClient, config: &CliConfig) -> Result<()> {
+    match command {
+        SimCommands::Appearance { mode, device } => {
+            let device = find_device(client, device.as_deref(), config).await?;
+            let request = UiSettingsRequest {
+                appearance: Some(mode),
+                ..Default::default()
+            };
+            client.set_ui(&device.udid, &request).await?;
+            println!("Set appearance of {} to {}", device.name, mode);
+        }
+        SimCommands::ContentSize { size, device } => {
+            let device = find_device(client, device.as_deref(), config).await?;
+            let request = UiSettingsRequest {
+                content_size: Some(size),
+                ..Default::default()
+            };
+            client.set_ui(&device.udid, &request).await?;
+            println!("Set content size of {} to {}", device.name, size);
+        }
+        SimCommands::Statusbar(args) => status_bar(args, client, config).await?,
+    }
+
+    Ok(())
+}
+
+async fn status_bar(args: StatusBarArgs, client: &AgentClient, config: &CliConfig) -> Result<()> {
+    let device = find_device(client, args.device.as_deref(), config).await?;
+
+    if args.clear {
+        client.clear_status_bar(&device.udid).await?;
+        println!("Cleared status bar overrides on {}", device.name);
+        return Ok(());
+    }
+
+    let mut request = if args.clean {
+        StatusBarOverride::clean()
+    } else {
+        StatusBarOverride::default()
+    };
+    if args.time.is_some() {
+        request.time = args.time;
+    }
+    if args.battery.is_some() {
+        request.battery_level = args.battery;
+    }
+    if args.battery_state.is_some() {
+        request.battery_state = args.battery_state;
+    }
+    if args.data_network.is_some() {
+        request.data_network = args.data_network;
+    }
+    if args.wifi_bars.is_some() {
+        request.wifi_bars = args.wifi_bars;
+    }
+    if args.cellular_bars.is_some() {
+        request.cellular_bars = args.cellular_bars;
+    }
+    if args.operator.is_some() {
+        request.operator_name = args.operator;
+    }
+
+    if request.is_empty() {
+        anyhow::bail!("Nothing to override (pass --clean, --clear or a value such as --time 9:41)");
+    }
+    request.validate().map_err(anyhow::Error::msg)?;
+
+    client.override_status_bar(&device.udid, &request).await?;
+    println!("Overrode status bar on {}", device.name);
+
+    Ok(())
+}
@@ -0,0 +1,40 @@
+use anyhow::{Context, Result};
+
+use crate::agent_client::AgentClient;
+use crate::cli::commands::run::print_crash_report;
+use crate::cli::SymbolicateArgs;
+
+/// Symbolicate a local .ips report on the agent
+pub async fn run(args: SymbolicateArgs, client: &AgentClient) -> Result<()> {
+    let report = std::fs::read_to_string(&args.report)
+        .with_context(|| format!("Failed to read {}", args.report.display()))?;
+
+    let build_id = match args.build {
+        Some(id) => id,
+        None => client.get_latest_build().await?.build_id,
+    };
+
+    let source_root = args.project.canonicalize()?;
+    let response = client
+        .symbolicate(build_id, report, Some(&source_root.to_string_lossy()))
+        .await?;
+
+    print_crash_report(
+        response.bundle_id.as_deref().unwrap_or("unknown app"),
+        &response.summary,
+    );
+
+    if response.symbolicated_images.is_empty() {
+        println!("\nNo frames matched the debug symbols of build {}", build_id);
+    } else {
+        println!("\nSymbolicated: {}", response.symbolicated_images.join(", "));
+    }
+
+    if let Some(output) = args.output {
+        std::fs::write(&output, &response.report)
+            .with_context(|| format!("Failed to write {}", output.display()))?;
+        println!("Symbolicated report saved to {}", output.display());
+    }
+
+    Ok(())
+}
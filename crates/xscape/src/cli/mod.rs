@@ -3,6 +3,13 @@ pub mod commands;
 use clap::{Parser, Subcommand};
 use std::path::PathBuf;
 use uuid::Uuid;
+use xscape_common::{
+    Appearance, ArchiveFormat, BuildConfiguration, ContainerType, ContentSize, ExportMethod,
+    Platform, PrivacyService,
+};
+
+use crate::clipboard::BackendKind;
+use crate::project::template::Template;
 
 #[derive(Parser)]
 #[command(name = "xscape")]
@@ -37,12 +44,21 @@ pub enum Commands {
     /// Setup wizard - verify and configure your installation
     Setup,
 
+    /// Create a new project from a template
+    New(NewArgs),
+
     /// Build an iOS project
     Build(BuildArgs),
 
     /// Build and run an iOS app in the simulator
     Run(RunArgs),
 
+    /// Install a prebuilt simulator app (.app.zip or .app.tar.gz) without building
+    Install(InstallArgs),
+
+    /// Archive for devices and export a signed .ipa
+    Archive(ArchiveArgs),
+
     /// Manage the local macOS VM
     Vm {
         #[command(subcommand)]
@@ -54,11 +70,78 @@ pub enum Commands {
         /// Refresh device list from agent
         #[arg(long)]
         refresh: bool,
+
+        /// Only list simulators of this platform (ios, watchos, tvos, visionos)
+        #[arg(long)]
+        platform: Option<Platform>,
     },
 
     /// Stream build or app logs
     Logs(LogsArgs),
 
+    /// Send a push notification to an app in the simulator
+    Push(PushArgs),
+
+    /// Open a deep link or universal link in the simulator
+    OpenUrl(OpenUrlArgs),
+
+    /// Manage privacy permissions for simulator apps
+    Privacy {
+        #[command(subcommand)]
+        command: PrivacyCommands,
+    },
+
+    /// Simulate GPS location on a simulator
+    Location {
+        #[command(subcommand)]
+        command: LocationCommands,
+    },
+
+    /// Change simulator appearance and status bar
+    Sim {
+        #[command(subcommand)]
+        command: SimCommands,
+    },
+
+    /// Manage simulator keychain certificates
+    Keychain {
+        #[command(subcommand)]
+        command: KeychainCommands,
+    },
+
+    /// Browse, download and upload files in an app's containers
+    Container {
+        #[command(subcommand)]
+        command: ContainerCommands,
+    },
+
+    /// Add photos, videos or contacts to a simulator
+    Media {
+        #[command(subcommand)]
+        command: MediaCommands,
+    },
+
+    /// Share clipboard text with the simulator
+    Clipboard {
+        #[command(subcommand)]
+        command: ClipboardCommands,
+    },
+
+    /// Symbolicate a crash report (.ips) with a build's debug symbols
+    Symbolicate(SymbolicateArgs),
+
+    /// Inspect Xcode projects without the agent
+    Project {
+        #[command(subcommand)]
+        command: ProjectCommands,
+    },
+
+    /// List and download build products (app, dSYMs, result bundle, log)
+    Artifacts {
+        #[command(subcommand)]
+        command: ArtifactsCommands,
+    },
+
     /// Manage configuration
     Config {
         #[command(subcommand)]
@@ -66,19 +149,48 @@ pub enum Commands {
     },
 }
 
+#[derive(clap::Args)]
+pub struct NewArgs {
+    /// Project name, also used for the app target and module
+    pub name: String,
+
+    /// Template: swiftui, uikit or package
+    #[arg(short, long, default_value = "swiftui")]
+    pub template: Template,
+
+    /// Bundle ID prefix; the app's bundle ID is <prefix>.<name>
+    #[arg(long, default_value = "com.example")]
+    pub bundle_id_prefix: String,
+
+    /// Minimum iOS version
+    #[arg(long, default_value = "17.0")]
+    pub deployment_target: String,
+
+    /// Directory to create the project in (defaults to ./<name>)
+    #[arg(short, long)]
+    pub output: Option<PathBuf>,
+}
+
 #[derive(clap::Args)]
 pub struct BuildArgs {
     /// Path to project directory
     #[arg(short, long, default_value = ".")]
     pub project: PathBuf,
 
-    /// Xcode scheme to build
+    /// Xcode scheme to build (defaults to project.default_scheme, then the project's schemes)
     #[arg(short, long)]
-    pub scheme: String,
+    pub scheme: Option<String>,
 
-    /// Build configuration (debug/release)
+    /// Build configuration (debug, release, or any configuration of the project)
     #[arg(short = 'C', long, default_value = "debug")]
-    pub configuration: String,
+    pub configuration: BuildConfiguration,
+
+    /// Platform to build for (ios, watchos, tvos, visionos, catalyst)
+    #[arg(long, default_value = "ios")]
+    pub platform: Platform,
+
+    #[command(flatten)]
+    pub overrides: BuildSettingsArgs,
 
     /// Target simulator device name
     #[arg(short, long)]
@@ -88,6 +200,18 @@ pub struct BuildArgs {
     #[arg(long)]
     pub clean: bool,
 
+    /// Run the scheme's tests (xcodebuild test, or swift test for host-only packages)
+    #[arg(long)]
+    pub test: bool,
+
+    /// Use only the agent's cached pods and Swift packages, without network access
+    #[arg(long)]
+    pub offline: bool,
+
+    /// Skip checking the project for missing files before uploading it
+    #[arg(long)]
+    pub no_preflight: bool,
+
     /// Don't stream build logs
     #[arg(long)]
     pub no_logs: bool,
@@ -99,9 +223,20 @@ pub struct RunArgs {
     #[arg(short, long, default_value = ".")]
     pub project: PathBuf,
 
-    /// Xcode scheme to build and run
+    /// Xcode scheme to build and run (defaults to project.default_scheme, then the project's schemes)
     #[arg(short, long)]
-    pub scheme: String,
+    pub scheme: Option<String>,
+
+    /// Build configuration (debug, release, or any configuration of the project)
+    #[arg(short = 'C', long, default_value = "debug")]
+    pub configuration: BuildConfiguration,
+
+    /// Platform to run on (ios, watchos, tvos, visionos)
+    #[arg(long, default_value = "ios")]
+    pub platform: Platform,
+
+    #[command(flatten)]
+    pub overrides: BuildSettingsArgs,
 
     /// Target simulator device name
     #[arg(short, long)]
@@ -115,11 +250,109 @@ pub struct RunArgs {
     #[arg(long)]
     pub env: Vec<String>,
 
+    /// Grant a privacy permission before launch (e.g., photos, location)
+    #[arg(long = "grant", value_name = "SERVICE")]
+    pub grant: Vec<PrivacyService>,
+
+    /// Launch the app with this locale (e.g., de_DE)
+    #[arg(long)]
+    pub locale: Option<String>,
+
+    /// Preferred language, highest priority first (defaults to the locale's language)
+    #[arg(long = "language", value_name = "LANG")]
+    pub language: Vec<String>,
+
+    /// Use only the agent's cached pods and Swift packages, without network access
+    #[arg(long)]
+    pub offline: bool,
+
+    /// Skip checking the project for missing files before uploading it
+    #[arg(long)]
+    pub no_preflight: bool,
+
+    /// Return right after launch instead of staying attached until the app
+    /// exits, which reports a crash
+    #[arg(long)]
+    pub detach: bool,
+
     /// Don't stream logs
     #[arg(long)]
     pub no_logs: bool,
 }
 
+#[derive(clap::Args, Default)]
+pub struct BuildSettingsArgs {
+    /// Override a build setting (KEY=VALUE); can be repeated
+    #[arg(long = "setting", value_name = "KEY=VALUE")]
+    pub settings: Vec<String>,
+
+    /// .xcconfig file inside the project to layer over its settings
+    #[arg(long, value_name = "PATH")]
+    pub xcconfig: Option<PathBuf>,
+}
+
+#[derive(clap::Args)]
+pub struct InstallArgs {
+    /// Zipped or tarred simulator .app (e.g., MyApp.app.zip, MyApp.app.tar.gz)
+    pub app: PathBuf,
+
+    /// Launch the app after installing it
+    #[arg(long)]
+    pub run: bool,
+
+    /// Simulator device name or UDID
+    #[arg(short, long)]
+    pub device: Option<String>,
+}
+
+#[derive(clap::Args)]
+pub struct ArchiveArgs {
+    /// Path to project directory
+    #[arg(short, long, default_value = ".")]
+    pub project: PathBuf,
+
+    /// Xcode scheme to archive (defaults to project.default_scheme, then the project's schemes)
+    #[arg(short, long)]
+    pub scheme: Option<String>,
+
+    /// Build configuration (debug, release, or any configuration of the project)
+    #[arg(short = 'C', long, default_value = "release")]
+    pub configuration: BuildConfiguration,
+
+    /// Export method: ad-hoc, development, app-store or enterprise (default: ad-hoc)
+    #[arg(long, value_name = "METHOD", conflicts_with = "export_options")]
+    pub export_method: Option<ExportMethod>,
+
+    /// ExportOptions plist to export with instead of --export-method
+    #[arg(long, value_name = "PLIST")]
+    pub export_options: Option<PathBuf>,
+
+    /// Development team ID
+    #[arg(long)]
+    pub team_id: Option<String>,
+
+    /// Signing certificate with its private key (.p12). The password is read
+    /// from XSCAPE_CERTIFICATE_PASSWORD or prompted for.
+    #[arg(long, value_name = "P12")]
+    pub certificate: Option<PathBuf>,
+
+    /// Provisioning profile (.mobileprovision); can be repeated
+    #[arg(long = "profile", value_name = "PROFILE")]
+    pub profiles: Vec<PathBuf>,
+
+    /// Use only the agent's cached pods and Swift packages, without network access
+    #[arg(long)]
+    pub offline: bool,
+
+    /// Skip checking the project for missing files before uploading it
+    #[arg(long)]
+    pub no_preflight: bool,
+
+    /// Where to save the .ipa (defaults to <scheme>.ipa)
+    #[arg(short, long)]
+    pub output: Option<PathBuf>,
+}
+
 #[derive(Subcommand)]
 pub enum VmCommands {
     /// Start the macOS VM
@@ -162,6 +395,374 @@ pub struct LogsArgs {
     pub follow: bool,
 }
 
+#[derive(clap::Args)]
+pub struct PushArgs {
+    /// APNs JSON payload file
+    pub payload: PathBuf,
+
+    /// Target simulator device name or UDID
+    #[arg(short, long)]
+    pub device: Option<String>,
+
+    /// Bundle ID of the app (defaults to the most recently run app)
+    #[arg(short, long)]
+    pub bundle_id: Option<String>,
+}
+
+#[derive(clap::Args)]
+pub struct OpenUrlArgs {
+    /// URL to open (e.g., "myapp://checkout?id=1")
+    #[arg(required_unless_present = "list_schemes")]
+    pub url: Option<String>,
+
+    /// Target simulator device name or UDID
+    #[arg(short, long)]
+    pub device: Option<String>,
+
+    /// Install and launch the build first if the app isn't installed
+    #[arg(long)]
+    pub launch: bool,
+
+    /// Build ID to use (defaults to the most recent build)
+    #[arg(long)]
+    pub build: Option<Uuid>,
+
+    /// List the URL schemes registered by the built app
+    #[arg(long, conflicts_with = "url")]
+    pub list_schemes: bool,
+}
+
+#[derive(clap::Args)]
+pub struct SymbolicateArgs {
+    /// Crash report (.ips) to symbolicate
+    pub report: PathBuf,
+
+    /// Build that produced the crashing app (defaults to the most recent build)
+    #[arg(long)]
+    pub build: Option<Uuid>,
+
+    /// Local checkout that source paths are mapped to
+    #[arg(short, long, default_value = ".")]
+    pub project: PathBuf,
+
+    /// Write the symbolicated report to this file
+    #[arg(short, long)]
+    pub output: Option<PathBuf>,
+}
+
+#[derive(Subcommand)]
+pub enum ProjectCommands {
+    /// Print a project's targets, configurations, files and packages as JSON
+    Inspect {
+        /// Project directory, .xcodeproj or project.pbxproj
+        #[arg(short, long, default_value = ".")]
+        project: PathBuf,
+    },
+    /// Add files to targets, creating groups that match their directories
+    Add {
+        /// Files to add
+        #[arg(required_unless_present = "check", conflicts_with = "check")]
+        files: Vec<PathBuf>,
+
+        /// Target to add the files to (defaults to the only app target); can be repeated
+        #[arg(short, long)]
+        target: Vec<String>,
+
+        /// Project directory or .xcodeproj
+        #[arg(short, long, default_value = ".")]
+        project: PathBuf,
+
+        /// Don't edit the project; list source files no target builds
+        #[arg(long)]
+        check: bool,
+    },
+    /// Remove files from targets, or from the project when no target is given
+    Remove {
+        /// Files to remove (they stay on disk)
+        #[arg(required = true)]
+        files: Vec<PathBuf>,
+
+        /// Only remove the files from this target; can be repeated
+        #[arg(short, long)]
+        target: Vec<String>,
+
+        /// Project directory or .xcodeproj
+        #[arg(short, long, default_value = ".")]
+        project: PathBuf,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum ArtifactsCommands {
+    /// List the products of a build
+    List {
+        /// Build ID (defaults to the most recent build)
+        build_id: Option<Uuid>,
+    },
+    /// Download the products of a build
+    Pull {
+        /// Build ID (defaults to the most recent build)
+        build_id: Option<Uuid>,
+
+        /// Output directory
+        #[arg(short, long, default_value = ".")]
+        output: PathBuf,
+
+        /// Only download these artifacts (e.g., MyApp.app)
+        #[arg(long, value_name = "NAME")]
+        only: Vec<String>,
+
+        /// Keep directories as archives (tar.gz or zip) instead of extracting them
+        #[arg(long, value_name = "FORMAT")]
+        archive: Option<ArchiveFormat>,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum PrivacyCommands {
+    /// Grant access without prompting
+    Grant(PrivacyArgs),
+    /// Deny access without prompting
+    Revoke(PrivacyArgs),
+    /// Prompt again on next use (all apps if no bundle ID is given)
+    Reset(PrivacyArgs),
+}
+
+#[derive(clap::Args)]
+pub struct PrivacyArgs {
+    /// Service (all, calendar, contacts, contacts-limited, location, location-always,
+    /// media-library, microphone, motion, photos, photos-add, reminders, siri)
+    pub service: PrivacyService,
+
+    /// Bundle ID of the app
+    #[arg(short, long)]
+    pub bundle_id: Option<String>,
+
+    /// Target simulator device name or UDID
+    #[arg(short, long)]
+    pub device: Option<String>,
+}
+
+#[derive(Subcommand)]
+pub enum LocationCommands {
+    /// Set a fixed location
+    #[command(allow_negative_numbers = true)]
+    Set {
+        /// Latitude in decimal degrees
+        latitude: f64,
+        /// Longitude in decimal degrees
+        longitude: f64,
+        /// Target simulator device name or UDID
+        #[arg(short, long)]
+        device: Option<String>,
+    },
+
+    /// Clear the simulated location (stops route playback)
+    Clear {
+        /// Target simulator device name or UDID
+        #[arg(short, long)]
+        device: Option<String>,
+    },
+
+    /// Play back a route from a GPX or GeoJSON file
+    Play {
+        /// Route file (.gpx, .geojson)
+        route: PathBuf,
+        /// Target simulator device name or UDID
+        #[arg(short, long)]
+        device: Option<String>,
+        /// Travel speed in meters per second
+        #[arg(long, default_value = "10")]
+        speed: f64,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum SimCommands {
+    /// Switch between light and dark mode
+    Appearance {
+        /// light or dark
+        mode: Appearance,
+        /// Target simulator device name or UDID
+        #[arg(short, long)]
+        device: Option<String>,
+    },
+
+    /// Set the Dynamic Type text size
+    ContentSize {
+        /// Size category (e.g., large, extra-large, accessibility-medium)
+        size: ContentSize,
+        /// Target simulator device name or UDID
+        #[arg(short, long)]
+        device: Option<String>,
+    },
+
+    /// Override status bar values for clean screenshots
+    Statusbar(StatusBarArgs),
+}
+
+#[derive(clap::Args)]
+pub struct StatusBarArgs {
+    /// Displayed time (e.g., 9:41)
+    #[arg(long)]
+    pub time: Option<String>,
+
+    /// Battery level (0-100)
+    #[arg(long)]
+    pub battery: Option<u8>,
+
+    /// Battery state (charging, charged, discharging)
+    #[arg(long)]
+    pub battery_state: Option<String>,
+
+    /// Data network (hide, wifi, 3g, 4g, lte, lte-a, lte+, 5g, 5g+, 5g-uwb, 5g-uc)
+    #[arg(long)]
+    pub data_network: Option<String>,
+
+    /// Wi-Fi bars (0-3)
+    #[arg(long)]
+    pub wifi_bars: Option<u8>,
+
+    /// Cellular bars (0-4)
+    #[arg(long)]
+    pub cellular_bars: Option<u8>,
+
+    /// Carrier name
+    #[arg(long)]
+    pub operator: Option<String>,
+
+    /// Start from the classic 9:41, full signal, full battery status bar
+    #[arg(long, conflicts_with = "clear")]
+    pub clean: bool,
+
+    /// Remove all status bar overrides
+    #[arg(long)]
+    pub clear: bool,
+
+    /// Target simulator device name or UDID
+    #[arg(short, long)]
+    pub device: Option<String>,
+}
+
+#[derive(Subcommand)]
+pub enum KeychainCommands {
+    /// Trust a root CA certificate (PEM or DER)
+    AddRootCert {
+        /// Certificate file
+        file: PathBuf,
+        /// Target simulator device name or UDID
+        #[arg(short, long)]
+        device: Option<String>,
+    },
+
+    /// Add a certificate without trusting it (PEM or DER)
+    AddCert {
+        /// Certificate file
+        file: PathBuf,
+        /// Target simulator device name or UDID
+        #[arg(short, long)]
+        device: Option<String>,
+    },
+
+    /// Remove all keychain items, including added certificates
+    Reset {
+        /// Target simulator device name or UDID
+        #[arg(short, long)]
+        device: Option<String>,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum ContainerCommands {
+    /// List a directory in the container
+    Ls {
+        /// Path relative to the container root
+        #[arg(default_value = "")]
+        path: String,
+        #[command(flatten)]
+        target: ContainerArgs,
+    },
+
+    /// Download a file, or a directory (extracted locally)
+    Pull {
+        /// Path relative to the container root
+        path: String,
+        /// Local destination (defaults to the current directory)
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+        #[command(flatten)]
+        target: ContainerArgs,
+    },
+
+    /// Upload a file, replacing any existing file
+    Push {
+        /// Local file to upload
+        local: PathBuf,
+        /// Destination path relative to the container root
+        path: String,
+        #[command(flatten)]
+        target: ContainerArgs,
+    },
+}
+
+#[derive(clap::Args)]
+pub struct ContainerArgs {
+    /// Bundle ID of the app
+    #[arg(short, long)]
+    pub bundle_id: String,
+
+    /// Container: app, data or an app group identifier (group.com.example)
+    #[arg(long, default_value = "data")]
+    pub container: ContainerType,
+
+    /// Target simulator device name or UDID
+    #[arg(short, long)]
+    pub device: Option<String>,
+}
+
+#[derive(Subcommand)]
+pub enum MediaCommands {
+    /// Add images and videos to Photos, or vCard files to Contacts
+    Add {
+        /// Files to add (.jpg, .png, .heic, .gif, .mov, .mp4, .vcf, ...)
+        #[arg(required = true)]
+        files: Vec<PathBuf>,
+        /// Target simulator device name or UDID
+        #[arg(short, long)]
+        device: Option<String>,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum ClipboardCommands {
+    /// Copy the local clipboard into the simulator
+    Push(ClipboardArgs),
+
+    /// Copy the simulator pasteboard to the local clipboard
+    Pull(ClipboardArgs),
+
+    /// Keep both clipboards in sync until Ctrl+C or the simulator shuts down
+    Sync {
+        #[command(flatten)]
+        args: ClipboardArgs,
+
+        /// Polling interval in milliseconds
+        #[arg(long, default_value = "500")]
+        interval: u64,
+    },
+}
+
+#[derive(clap::Args)]
+pub struct ClipboardArgs {
+    /// Local clipboard backend (auto, wayland, x11, stdio)
+    #[arg(long, default_value = "auto")]
+    pub backend: BackendKind,
+
+    /// Target simulator device name or UDID
+    #[arg(short, long)]
+    pub device: Option<String>,
+}
+
 #[derive(Subcommand)]
 pub enum ConfigCommands {
     /// Initialize configuration file
@@ -0,0 +1,195 @@
+use anyhow::{Context, Result};
+use std::io::{Read, Write};
+use std::path::PathBuf;
+use std::process::{Command, Stdio};
+use tracing::debug;
+
+/// Access to the local (Linux desktop) clipboard
+pub trait ClipboardBackend {
+    fn name(&self) -> &'static str;
+
+    /// Read clipboard text (empty if the clipboard holds no text)
+    fn read(&self) -> Result<String>;
+
+    /// Replace clipboard contents with text
+    fn write(&self, text: &str) -> Result<()>;
+
+    /// Whether the backend is a real clipboard that can be polled for changes
+    fn is_watchable(&self) -> bool {
+        true
+    }
+}
+
+/// Which clipboard backend to use
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum BackendKind {
+    /// Wayland if available, then X11, then stdin/stdout
+    #[default]
+    Auto,
+    Wayland,
+    X11,
+    Stdio,
+}
+
+impl std::str::FromStr for BackendKind {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "auto" => Ok(Self::Auto),
+            "wayland" => Ok(Self::Wayland),
+            "x11" => Ok(Self::X11),
+            "stdio" => Ok(Self::Stdio),
+            _ => Err(format!(
+                "unknown clipboard backend '{}' (expected auto, wayland, x11 or stdio)",
+                s
+            )),
+        }
+    }
+}
+
+/// Pick a clipboard backend
+pub fn detect(kind: BackendKind) -> Result<Box<dyn ClipboardBackend>> {
+    match kind {
+        BackendKind::Wayland => wayland().context(
+            "Wayland clipboard not available. Install wl-clipboard and run inside a Wayland session",
+        ),
+        BackendKind::X11 => x11().context(
+            "X11 clipboard not available. Install xclip or xsel and make sure DISPLAY is set",
+        ),
+        BackendKind::Stdio => Ok(Box::new(StdioBackend)),
+        BackendKind::Auto => {
+            let backend = wayland()
+                .or_else(x11)
+                .unwrap_or_else(|| Box::new(StdioBackend));
+            debug!("Using {} clipboard backend", backend.name());
+            Ok(backend)
+        }
+    }
+}
+
+fn wayland() -> Option<Box<dyn ClipboardBackend>> {
+    std::env::var_os("WAYLAND_DISPLAY")?;
+    if find_in_path("wl-paste").is_none() || find_in_path("wl-copy").is_none() {
+        return None;
+    }
+
+    Some(Box::new(CommandBackend {
+        name: "wayland",
+        read: &["wl-paste", "--no-newline", "--type", "text"],
+        write: &["wl-copy", "--type", "text/plain"],
+    }))
+}
+
+fn x11() -> Option<Box<dyn ClipboardBackend>> {
+    std::env::var_os("DISPLAY")?;
+
+    if find_in_path("xclip").is_some() {
+        return Some(Box::new(CommandBackend {
+            name: "x11 (xclip)",
+            read: &["xclip", "-selection", "clipboard", "-out"],
+            write: &["xclip", "-selection", "clipboard", "-in"],
+        }));
+    }
+    if find_in_path("xsel").is_some() {
+        return Some(Box::new(CommandBackend {
+            name: "x11 (xsel)",
+            read: &["xsel", "--clipboard", "--output"],
+            write: &["xsel", "--clipboard", "--input"],
+        }));
+    }
+
+    None
+}
+
+fn find_in_path(program: &str) -> Option<PathBuf> {
+    let path = std::env::var_os("PATH")?;
+    std::env::split_paths(&path)
+        .map(|dir| dir.join(program))
+        .find(|candidate| candidate.is_file())
+}
+
+/// Backend driven by external clipboard tools (wl-clipboard, xclip, xsel)
+struct CommandBackend {
+    name: &'static str,
+    read: &'static [&'static str],
+    write: &'static [&'static str],
+}
+
+impl ClipboardBackend for CommandBackend {
+    fn name(&self) -> &'static str {
+        self.name
+    }
+
+    fn read(&self) -> Result<String> {
+        let output = Command::new(self.read[0])
+            .args(&self.read[1..])
+            .stdin(Stdio::null())
+            .output()
+            .with_context(|| format!("Failed to run {}", self.read[0]))?;
+
+        // The tools exit non-zero when the clipboard is empty or holds no text
+        if !output.status.success() {
+            debug!(
+                "{} exited with {}: {}",
+                self.read[0],
+                output.status,
+                String::from_utf8_lossy(&output.stderr).trim()
+            );
+            return Ok(String::new());
+        }
+
+        Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+    }
+
+    fn write(&self, text: &str) -> Result<()> {
+        // The tools fork to keep serving the selection; don't wait on their
+        // output pipes or we'd block until the selection changes hands.
+        let mut child = Command::new(self.write[0])
+            .args(&self.write[1..])
+            .stdin(Stdio::piped())
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .spawn()
+            .with_context(|| format!("Failed to run {}", self.write[0]))?;
+
+        let mut stdin = child.stdin.take().context("Failed to open clipboard tool stdin")?;
+        stdin.write_all(text.as_bytes())?;
+        drop(stdin);
+
+        let status = child.wait()?;
+        if !status.success() {
+            anyhow::bail!("{} exited with {}", self.write[0], status);
+        }
+
+        Ok(())
+    }
+}
+
+/// Fallback for headless sessions: read from stdin, write to stdout
+struct StdioBackend;
+
+impl ClipboardBackend for StdioBackend {
+    fn name(&self) -> &'static str {
+        "stdio"
+    }
+
+    fn read(&self) -> Result<String> {
+        let mut text = String::new();
+        std::io::stdin()
+            .read_to_string(&mut text)
+            .context("Failed to read clipboard text from stdin")?;
+        Ok(text)
+    }
+
+    fn write(&self, text: &str) -> Result<()> {
+        let mut stdout = std::io::stdout();
+        stdout.write_all(text.as_bytes())?;
+        stdout.flush()?;
+        Ok(())
+    }
+
+    fn is_watchable(&self) -> bool {
+        false
+    }
+}
@@ -5,6 +5,7 @@ use tracing_subscriber::FmtSubscriber;
 
 mod agent_client;
 mod cli;
+mod clipboard;
 mod config;
 mod project;
 mod tui;
@@ -55,21 +56,66 @@ async fn main() -> Result<()> {
         Commands::Setup => {
             tui::SetupWizard::run().await?;
         }
+        Commands::New(args) => {
+            cli::commands::new::run(args).await?;
+        }
         Commands::Build(args) => {
             cli::commands::build::run(args, &client, &config).await?;
         }
         Commands::Run(args) => {
             cli::commands::run::run(args, &client, &config).await?;
         }
+        Commands::Install(args) => {
+            cli::commands::install::run(args, &client, &config).await?;
+        }
+        Commands::Archive(args) => {
+            cli::commands::archive::run(args, &client, &config).await?;
+        }
         Commands::Vm { command } => {
             cli::commands::vm::run(command, &config).await?;
         }
-        Commands::Devices { refresh } => {
-            cli::commands::devices::run(&client, refresh).await?;
+        Commands::Devices { refresh, platform } => {
+            cli::commands::devices::run(&client, refresh, platform).await?;
         }
         Commands::Logs(args) => {
             cli::commands::logs::run(args, &agent_url).await?;
         }
+        Commands::Push(args) => {
+            cli::commands::push::run(args, &client, &config).await?;
+        }
+        Commands::OpenUrl(args) => {
+            cli::commands::open_url::run(args, &client, &config).await?;
+        }
+        Commands::Privacy { command } => {
+            cli::commands::privacy::run(command, &client, &config).await?;
+        }
+        Commands::Location { command } => {
+            cli::commands::location::run(command, &client, &config).await?;
+        }
+        Commands::Sim { command } => {
+            cli::commands::sim::run(command, &client, &config).await?;
+        }
+        Commands::Keychain { command } => {
+            cli::commands::keychain::run(command, &client, &config).await?;
+        }
+        Commands::Container { command } => {
+            cli::commands::container::run(command, &client, &config).await?;
+        }
+        Commands::Media { command } => {
+            cli::commands::media::run(command, &client, &config).await?;
+        }
+        Commands::Clipboard { command } => {
+            cli::commands::clipboard::run(command, &client, &config).await?;
+        }
+        Commands::Symbolicate(args) => {
+            cli::commands::symbolicate::run(args, &client).await?;
+        }
+        Commands::Project { command } => {
+            cli::commands::project::run(command, &config).await?;
+        }
+        Commands::Artifacts { command } => {
+            cli::commands::artifacts::run(command, &client).await?;
+        }
         Commands::Config { command } => {
             cli::commands::config::run(command).await?;
         }
@@ -0,0 +1,1011 @@
+use anyhow::{anyhow, Context, Result};
+use ignore::WalkBuilder;
+use sha2::{Digest, Sha256};
+use std::collections::HashSet;
+use std::ops::Range;
+use std::path::{Path, PathBuf};
+
+use super::pbxproj::quote;
+use super::should_exclude;
+use super::xcodeproj::XcodeProject;
+
+/// First line of every project.pbxproj Xcode writes
+const XCODE_HEADER: &str = "// !$*UTF8*$!";
+
+/// Directories Xcode treats as a single file
+const BUNDLE_EXTENSIONS: &[&str] = &[
+    "xcassets", "xcdatamodeld", "framework", "xcframework", "bundle", "xcodeproj", "xcworkspace",
+    "playground",
+];
+
+/// Directories that never hold project sources
+const SKIPPED_DIRS: &[&str] = &["build", "DerivedData", ".build", "Pods", "Carthage", "node_modules"];
+
+/// Build phase a file is added to
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Phase {
+    Sources,
+    Resources,
+    Frameworks,
+    Headers,
+}
+
+impl Phase {
+    fn isa(&self) -> &'static str {
+        match self {
+            Self::Sources => "PBXSourcesBuildPhase",
+            Self::Resources => "PBXResourcesBuildPhase",
+            Self::Frameworks => "PBXFrameworksBuildPhase",
+            Self::Headers => "PBXHeadersBuildPhase",
+        }
+    }
+
+    /// Name Xcode shows for the phase
+    fn name(&self) -> &'static str {
+        match self {
+            Self::Sources => "Sources",
+            Self::Resources => "Resources",
+            Self::Frameworks => "Frameworks",
+            Self::Headers => "Headers",
+        }
+    }
+
+    /// Phase a file belongs in. Files like Info.plist or .xcconfig are only
+    /// referenced, never built.
+    pub fn for_path(path: &Path) -> Option<Self> {
+        let ext = path.extension()?.to_str()?.to_lowercase();
+        match ext.as_str() {
+            "swift" | "m" | "mm" | "c" | "cc" | "cpp" | "cxx" | "metal" | "xcdatamodeld"
+            | "intentdefinition" => Some(Self::Sources),
+            "storyboard" | "xib" | "xcassets" | "strings" | "stringsdict" | "xcstrings" | "json"
+            | "png" | "jpg" | "jpeg" | "pdf" | "ttf" | "otf" | "wav" | "mp3" | "mp4" | "mov"
+            | "html" | "bundle" | "xcprivacy" => Some(Self::Resources),
+            "framework" | "xcframework" | "a" | "dylib" | "tbd" => Some(Self::Frameworks),
+            "h" | "hpp" => Some(Self::Headers),
+            _ => None,
+        }
+    }
+}
+
+/// Xcode's `lastKnownFileType` for a path
+fn file_type(path: &Path) -> &'static str {
+    let ext = path
+        .extension()
+        .and_then(|e| e.to_str())
+        .unwrap_or_default()
+        .to_lowercase();
+    match ext.as_str() {
+        "swift" => "sourcecode.swift",
+        "m" => "sourcecode.c.objc",
+        "mm" => "sourcecode.cpp.objcpp",
+        "c" => "sourcecode.c.c",
+        "cc" | "cpp" | "cxx" => "sourcecode.cpp.cpp",
+        "h" => "sourcecode.c.h",
+        "hpp" => "sourcecode.cpp.h",
+        "metal" => "sourcecode.metal",
+        "storyboard" => "file.storyboard",
+        "xib" => "file.xib",
+        "xcassets" => "folder.assetcatalog",
+        "xcdatamodeld" => "wrapper.xcdatamodel",
+        "strings" => "text.plist.strings",
+        "stringsdict" => "text.plist.stringsdict",
+        "xcstrings" => "text.json.xcstrings",
+        "json" => "text.json",
+        "plist" => "text.plist.xml",
+        "entitlements" => "text.plist.entitlements",
+        "xcconfig" => "text.xcconfig",
+        "xcprivacy" => "text.xml",
+        "png" => "image.png",
+        "jpg" | "jpeg" => "image.jpeg",
+        "pdf" => "image.pdf",
+        "md" => "net.daringfireball.markdown",
+        "html" => "text.html",
+        "framework" => "wrapper.framework",
+        "xcframework" => "wrapper.xcframework",
+        "a" => "archive.ar",
+        "dylib" => "compiled.mach-o.dylib",
+        "tbd" => "sourcecode.text-based-dylib-definition",
+        "bundle" => "wrapper.plug-in",
+        _ => "file",
+    }
+}
+
+/// Edits a project.pbxproj in place. Changes are spliced into the text
+/// so everything they don't touch stays byte-for-byte the same.
+pub struct ProjectEditor {
+    pbxproj: PathBuf,
+    original: String,
+    text: String,
+    project: XcodeProject,
+}
+
+impl ProjectEditor {
+    /// Open a .xcodeproj bundle for editing
+    pub fn open(bundle: &Path) -> Result<Self> {
+        let pbxproj = bundle.join("project.pbxproj");
+        let text = std::fs::read_to_string(&pbxproj)
+            .with_context(|| format!("Failed to read {}", pbxproj.display()))?;
+        if !text.starts_with(XCODE_HEADER) {
+            anyhow::bail!(
+                "{} is not in the format Xcode writes; open and save the project in Xcode first",
+                pbxproj.display()
+            );
+        }
+        let project = XcodeProject::parse(&text, bundle)
+            .with_context(|| format!("Failed to parse {}", pbxproj.display()))?;
+
+        Ok(Self {
+            pbxproj,
+            original: text.clone(),
+            text,
+            project,
+        })
+    }
+
+    pub fn project(&self) -> &XcodeProject {
+        &self.project
+    }
+
+    /// Write the project back if anything changed. Returns whether it did.
+    pub fn save(&self) -> Result<bool> {
+        if self.text == self.original {
+            return Ok(false);
+        }
+        // Write next to the original and rename, so a failure can't leave
+        // half a project behind
+        let tmp = self.pbxproj.with_extension("pbxproj.tmp");
+        std::fs::write(&tmp, &self.text)
+            .with_context(|| format!("Failed to write {}", tmp.display()))?;
+        std::fs::rename(&tmp, &self.pbxproj)
+            .with_context(|| format!("Failed to replace {}", self.pbxproj.display()))?;
+        Ok(true)
+    }
+
+    /// Targets to add files to when none are named: the only native target
+    pub fn default_targets(&self) -> Result<Vec<String>> {
+        let native: Vec<String> = self
+            .project
+            .target_ids()
+            .into_iter()
+            .filter(|id| self.project.isa(id) == Some("PBXNativeTarget"))
+            .filter_map(|id| self.project.object(id)?.get_str("name").map(String::from))
+            .collect();
+        match native.len() {
+            1 => Ok(native),
+            0 => anyhow::bail!("The project has no targets to add files to"),
+            _ => anyhow::bail!("Choose a target with --target ({})", native.join(", ")),
+        }
+    }
+
+    fn target_id(&self, name: &str) -> Result<String> {
+        self.project
+            .target_ids()
+            .into_iter()
+            .find(|id| self.project.object(id).and_then(|t| t.get_str("name")) == Some(name))
+            .map(String::from)
+            .ok_or_else(|| {
+                anyhow!(
+                    "No target named '{}' (targets: {})",
+                    name,
+                    self.project.target_names().join(", ")
+                )
+            })
+    }
+
+    /// Add a file (relative to the project's source root) to targets,
+    /// creating groups that mirror its directory. Returns what changed.
+    pub fn add_file(&mut self, relative: &Path, targets: &[String]) -> Result<Vec<String>> {
+        let display = relative.to_string_lossy().into_owned();
+        let mut changes = Vec::new();
+
+        // Files in synchronized folders already belong to the folder's targets
+        let mut remaining = Vec::new();
+        for name in targets {
+            let id = self.target_id(name)?;
+            let folder = self.project.target(&id).and_then(|t| {
+                t.synchronized_folders
+                    .into_iter()
+                    .find(|f| relative.starts_with(f))
+            });
+            match folder {
+                Some(folder) => changes.push(format!(
+                    "{} is already in {} through the synchronized folder {}",
+                    display, name, folder
+                )),
+                None => remaining.push((id, name.clone())),
+            }
+        }
+        if remaining.is_empty() && !targets.is_empty() {
+            return Ok(changes);
+        }
+        if let Some(folder) = self.synchronized_folder_containing(relative) {
+            anyhow::bail!(
+                "{} is inside the synchronized folder {}; change its targets in Xcode",
+                display,
+                folder.display()
+            );
+        }
+
+        let existing = self
+            .project
+            .file_references()
+            .into_iter()
+            .find(|(_, path)| *path == relative)
+            .map(|(id, _)| id.to_string());
+        let file_id = match existing {
+            Some(id) => id,
+            None => self.insert_file_reference(relative, &mut changes)?,
+        };
+        let file_name = file_name(relative);
+
+        let Some(phase) = Phase::for_path(relative) else {
+            if !remaining.is_empty() {
+                changes.push(format!("{} isn't built by any phase, so it was only referenced", display));
+            }
+            return self.reload(changes);
+        };
+
+        for (target_id, target_name) in remaining {
+            let phase_id = self
+                .project
+                .object(&target_id)
+                .map(|t| t.get_strings("buildPhases"))
+                .unwrap_or_default()
+                .into_iter()
+                .find(|id| self.project.isa(id) == Some(phase.isa()))
+                .map(String::from)
+                .ok_or_else(|| anyhow!("Target '{}' has no {} phase", target_name, phase.name()))?;
+            let phase_object = self.project.object(&phase_id).cloned().unwrap_or_default();
+
+            let already = phase_object.get_strings("files").into_iter().any(|build_file| {
+                self.project
+                    .object(build_file)
+                    .and_then(|b| b.get_str("fileRef"))
+                    == Some(file_id.as_str())
+            });
+            if already {
+                changes.push(format!("{} is already in {}", display, target_name));
+                continue;
+            }
+
+            let phase_name = phase_object.get_str("name").unwrap_or(phase.name());
+            let comment = format!("{} in {}", file_name, phase_name);
+            let build_id = self.new_id(&format!("build:{}:{}", file_id, phase_id));
+            self.insert_object(
+                "PBXBuildFile",
+                &build_id,
+                &format!(
+                    "\t\t{} /* {} */ = {{isa = PBXBuildFile; fileRef = {} /* {} */; }};\n",
+                    build_id, comment, file_id, file_name
+                ),
+            )?;
+            self.append_to_array(&phase_id, "files", &build_id, &comment)?;
+            changes.push(format!("Added {} to {} ({})", display, target_name, phase_name));
+        }
+
+        self.reload(changes)
+    }
+
+    /// Remove a file from the named targets, or from the project entirely
+    /// when no targets are named. The file itself is left on disk.
+    pub fn remove_file(&mut self, relative: &Path, targets: &[String]) -> Result<Vec<String>> {
+        let display = relative.to_string_lossy().into_owned();
+        let file_ids: Vec<String> = self
+            .project
+            .file_references()
+            .into_iter()
+            .filter(|(_, path)| *path == relative)
+            .map(|(id, _)| id.to_string())
+            .collect();
+        if file_ids.is_empty() {
+            anyhow::bail!("{} is not in the project", display);
+        }
+
+        // Phases to remove from: the named targets', or every phase
+        let phases: Vec<(String, Option<String>)> = if targets.is_empty() {
+            self.project
+                .objects()
+                .filter(|(_, o)| o.get_str("isa").is_some_and(|isa| isa.ends_with("BuildPhase")))
+                .map(|(id, _)| (id.to_string(), None))
+                .collect()
+        } else {
+            let mut phases = Vec::new();
+            for name in targets {
+                let target_id = self.target_id(name)?;
+                let target_phases = self
+                    .project
+                    .object(&target_id)
+                    .map(|t| t.get_strings("buildPhases"))
+                    .unwrap_or_default();
+                phases.extend(target_phases.into_iter().map(|p| (p.to_string(), Some(name.clone()))));
+            }
+            phases
+        };
+
+        let mut changes = Vec::new();
+        let mut removed_from = HashSet::new();
+        for (phase_id, target) in &phases {
+            let build_files: Vec<String> = self
+                .project
+                .object(phase_id)
+                .map(|p| p.get_strings("files"))
+                .unwrap_or_default()
+                .into_iter()
+                .filter(|b| {
+                    self.project
+                        .object(b)
+                        .and_then(|o| o.get_str("fileRef"))
+                        .is_some_and(|f| file_ids.iter().any(|id| id == f))
+                })
+                .map(String::from)
+                .collect();
+            for build_id in build_files {
+                self.remove_from_array(phase_id, "files", &build_id)?;
+                self.remove_object(&build_id)?;
+                if let Some(target) = target {
+                    if removed_from.insert(target.clone()) {
+                        changes.push(format!("Removed {} from {}", display, target));
+                    }
+                }
+            }
+        }
+        for name in targets {
+            if !removed_from.contains(name) {
+                changes.push(format!("{} is not in {}", display, name));
+            }
+        }
+
+        if targets.is_empty() {
+            let mut emptied = Vec::new();
+            for file_id in &file_ids {
+                let parents = self.parents(file_id);
+                for parent in &parents {
+                    self.remove_from_array(parent, "children", file_id)?;
+                }
+                self.remove_object(file_id)?;
+                emptied.extend(parents);
+            }
+            changes.push(format!("Removed {} from the project", display));
+            self.reload(Vec::new())?;
+            self.remove_empty_groups(emptied, &mut changes)?;
+        }
+
+        self.reload(changes)
+    }
+
+    /// Groups and other containers listing `id` as a child
+    fn parents(&self, id: &str) -> Vec<String> {
+        self.project
+            .objects()
+            .filter(|(_, o)| o.get_strings("children").contains(&id))
+            .map(|(id, _)| id.to_string())
+            .collect()
+    }
+
+    /// Remove the groups among `candidates` that no longer hold anything,
+    /// then their parents if that empties them too, so removing a file
+    /// undoes the groups adding it created. Only groups with a directory
+    /// of their own go; ones like Products or Frameworks stay.
+    fn remove_empty_groups(&mut self, mut candidates: Vec<String>, changes: &mut Vec<String>) -> Result<()> {
+        let main_group = self.project.main_group().unwrap_or_default().to_string();
+        while !candidates.is_empty() {
+            candidates.sort();
+            candidates.dedup();
+            let mut next = Vec::new();
+            for id in candidates {
+                let empty = self.project.object(&id).is_some_and(|o| {
+                    o.get_str("isa") == Some("PBXGroup")
+                        && o.get_str("path").is_some()
+                        && o.get_strings("children").is_empty()
+                });
+                if !empty || id == main_group {
+                    continue;
+                }
+                let path = self.project.file_path(&id).map(Path::to_path_buf).unwrap_or_default();
+                let parents = self.parents(&id);
+                for parent in &parents {
+                    self.remove_from_array(parent, "children", &id)?;
+                }
+                self.remove_object(&id)?;
+                changes.push(format!("Removed empty group {}", path.display()));
+                next.extend(parents);
+            }
+            self.reload(Vec::new())?;
+            candidates = next;
+        }
+        Ok(())
+    }
+
+    /// Synchronized folder (of any target) a path lies in
+    fn synchronized_folder_containing(&self, relative: &Path) -> Option<PathBuf> {
+        self.project
+            .objects()
+            .filter(|(_, o)| o.get_str("isa") == Some("PBXFileSystemSynchronizedRootGroup"))
+            .filter_map(|(id, _)| self.project.file_path(id))
+            .find(|folder| relative.starts_with(folder))
+            .map(Path::to_path_buf)
+    }
+
+    /// Add a file reference, creating any groups between the closest
+    /// existing group and the file's directory. Returns the reference's ID.
+    fn insert_file_reference(&mut self, relative: &Path, changes: &mut Vec<String>) -> Result<String> {
+        let dir = relative.parent().unwrap_or(Path::new(""));
+        let (parent_id, parent_path) = self.closest_group(dir)?;
+        let missing: Vec<String> = dir
+            .strip_prefix(&parent_path)
+            .unwrap_or(Path::new(""))
+            .components()
+            .map(|c| c.as_os_str().to_string_lossy().into_owned())
+            .collect();
+
+        let name = file_name(relative);
+        let file_id = self.new_id(&format!("file:{}", relative.display()));
+        self.insert_object(
+            "PBXFileReference",
+            &file_id,
+            &format!(
+                "\t\t{} /* {} */ = {{isa = PBXFileReference; lastKnownFileType = {}; path = {}; sourceTree = \"<group>\"; }};\n",
+                file_id,
+                name,
+                quote(file_type(relative)),
+                quote(&name)
+            ),
+        )?;
+
+        // Build the new groups innermost first, each holding the previous one
+        let created_at = changes.len();
+        let mut child = (file_id.clone(), name);
+        for (depth, component) in missing.iter().enumerate().rev() {
+            let group_path = parent_path.join(missing[..=depth].join("/"));
+            let group_id = self.new_id(&format!("group:{}", group_path.display()));
+            self.insert_object(
+                "PBXGroup",
+                &group_id,
+                &format!(
+                    "\t\t{id} /* {name} */ = {{\n\t\t\tisa = PBXGroup;\n\t\t\tchildren = (\n\t\t\t\t{child} /* {child_name} */,\n\t\t\t);\n\t\t\tpath = {path};\n\t\t\tsourceTree = \"<group>\";\n\t\t}};\n",
+                    id = group_id,
+                    name = component,
+                    child = child.0,
+                    child_name = child.1,
+                    path = quote(component),
+                ),
+            )?;
+            changes.insert(created_at, format!("Created group {}", group_path.display()));
+            child = (group_id, component.clone());
+        }
+
+        self.append_to_array(&parent_id, "children", &child.0, &child.1)?;
+        changes.push(format!("Referenced {}", relative.display()));
+        Ok(file_id)
+    }
+
+    /// The group whose directory is the deepest ancestor of `dir`. Groups
+    /// without a path of their own only win when nothing else matches.
+    fn closest_group(&self, dir: &Path) -> Result<(String, PathBuf)> {
+        let main_group = self.project.main_group().unwrap_or_default();
+        self.project
+            .objects()
+            .filter(|(_, o)| o.get_str("isa") == Some("PBXGroup"))
+            .filter_map(|(id, o)| {
+                let path = self.project.file_path(id)?;
+                if !dir.starts_with(path) {
+                    return None;
+                }
+                let own_path = o.get_str("path").is_some() || id == main_group;
+                Some(((path.components().count(), own_path, std::cmp::Reverse(id)), id, path))
+            })
+            .max_by(|a, b| a.0.cmp(&b.0))
+            .map(|(_, id, path)| (id.to_string(), path.to_path_buf()))
+            .ok_or_else(|| anyhow!("The project has no group for {}", dir.display()))
+    }
+
+    /// A new object ID, derived from `seed` so repeated runs produce the
+    /// same project
+    fn new_id(&self, seed: &str) -> String {
+        (0u32..)
+            .map(|n| {
+                let digest = Sha256::digest(format!("{}:{}", seed, n).as_bytes());
+                digest[..12].iter().map(|b| format!("{:02X}", b)).collect::<String>()
+            })
+            .find(|id| !self.text.contains(id.as_str()))
+            .expect("ID space exhausted")
+    }
+
+    /// Byte range of an object, including its trailing newline
+    fn object_range(&self, id: &str) -> Result<Range<usize>> {
+        let start = self
+            .text
+            .find(&format!("\n\t\t{} ", id))
+            .map(|i| i + 1)
+            .ok_or_else(|| anyhow!("Object {} not found in project.pbxproj", id))?;
+        let line_end = self.text[start..]
+            .find('\n')
+            .map_or(self.text.len(), |i| start + i + 1);
+        if self.text[start..line_end].trim_end().ends_with("};") {
+            return Ok(start..line_end);
+        }
+        let close = "\n\t\t};\n";
+        let end = self.text[start..]
+            .find(close)
+            .map(|i| start + i + close.len())
+            .ok_or_else(|| anyhow!("Object {} is not terminated", id))?;
+        Ok(start..end)
+    }
+
+    /// Range of the items of an object's array (e.g., a group's `children`)
+    fn array_range(&self, object_id: &str, key: &str) -> Result<Range<usize>> {
+        let object = self.object_range(object_id)?;
+        let open = format!("\n\t\t\t{} = (\n", key);
+        let start = self.text[object.clone()]
+            .find(&open)
+            .map(|i| object.start + i + open.len())
+            .ok_or_else(|| anyhow!("Object {} has no {} list", object_id, key))?;
+        let end = self.text[start..object.end]
+            .find("\t\t\t);")
+            .map(|i| start + i)
+            .ok_or_else(|| anyhow!("The {} list of {} is not terminated", key, object_id))?;
+        Ok(start..end)
+    }
+
+    fn append_to_array(&mut self, object_id: &str, key: &str, item: &str, comment: &str) -> Result<()> {
+        let range = self.array_range(object_id, key)?;
+        self.text
+            .insert_str(range.end, &format!("\t\t\t\t{} /* {} */,\n", item, comment));
+        Ok(())
+    }
+
+    fn remove_from_array(&mut self, object_id: &str, key: &str, item: &str) -> Result<()> {
+        let range = self.array_range(object_id, key)?;
+        let mut offset = range.start;
+        for line in self.text[range.clone()].split_inclusive('\n') {
+            let entry = line.trim_start_matches('\t');
+            if entry.strip_prefix(item).is_some_and(|rest| rest.starts_with([' ', ','])) {
+                self.text.replace_range(offset..offset + line.len(), "");
+                return Ok(());
+            }
+            offset += line.len();
+        }
+        Ok(())
+    }
+
+    /// Insert an object into its section, keeping the section sorted by ID
+    /// as Xcode does, and creating the section if the project has none
+    fn insert_object(&mut self, isa: &str, id: &str, object: &str) -> Result<()> {
+        let begin = format!("/* Begin {} section */\n", isa);
+        let end = format!("/* End {} section */\n", isa);
+
+        if let Some(section_start) = self.text.find(&begin).map(|i| i + begin.len()) {
+            let section_end = self.text[section_start..]
+                .find(&end)
+                .map(|i| section_start + i)
+                .ok_or_else(|| anyhow!("The {} section is not terminated", isa))?;
+            let mut offset = section_start;
+            let mut insert_at = section_end;
+            for line in self.text[section_start..section_end].split_inclusive('\n') {
+                let key = line.strip_prefix("\t\t").and_then(|l| l.split(' ').next());
+                if key.is_some_and(|k| k.starts_with(|c: char| c.is_ascii_alphanumeric()) && k > id) {
+                    insert_at = offset;
+                    break;
+                }
+                offset += line.len();
+            }
+            self.text.insert_str(insert_at, object);
+            return Ok(());
+        }
+
+        // Sections are sorted by name
+        let block = format!("{}{}{}", begin, object, end);
+        let next_section = self.text.match_indices("/* Begin ").find(|(i, _)| {
+            self.text[i + "/* Begin ".len()..]
+                .split(' ')
+                .next()
+                .is_some_and(|name| name > isa)
+        });
+        match next_section {
+            Some((i, _)) => self.text.insert_str(i, &format!("{}\n", block)),
+            None => {
+                let last_end = self
+                    .text
+                    .rmatch_indices("/* End ")
+                    .next()
+                    .map(|(i, _)| i)
+                    .ok_or_else(|| anyhow!("project.pbxproj has no object sections"))?;
+                let after = self.text[last_end..]
+                    .find('\n')
+                    .map_or(self.text.len(), |i| last_end + i + 1);
+                self.text.insert_str(after, &format!("\n{}", block));
+            }
+        }
+        Ok(())
+    }
+
+    /// Remove an object, and its section if that leaves it empty
+    fn remove_object(&mut self, id: &str) -> Result<()> {
+        let isa = self.project.isa(id).unwrap_or_default().to_string();
+        let range = self.object_range(id)?;
+        self.text.replace_range(range, "");
+
+        let empty = format!("/* Begin {isa} section */\n/* End {isa} section */\n");
+        if let Some(start) = self.text.find(&empty) {
+            let mut end = start + empty.len();
+            let mut start = start;
+            if self.text[end..].starts_with('\n') {
+                end += 1;
+            } else if self.text[..start].ends_with("\n\n") {
+                start -= 1;
+            }
+            self.text.replace_range(start..end, "");
+        }
+        Ok(())
+    }
+
+    /// Re-read the edited text so later edits see this one
+    fn reload(&mut self, changes: Vec<String>) -> Result<Vec<String>> {
+        self.project = XcodeProject::parse(&self.text, &self.project.path)
+            .context("The edited project no longer parses")?;
+        Ok(changes)
+    }
+}
+
+/// Source files, interface files and asset catalogs under the project's
+/// source root that no target builds, relative to `root`
+pub fn unreferenced_files(project: &XcodeProject, root: &Path, exclude_patterns: &[String]) -> Vec<String> {
+    let mut built: HashSet<PathBuf> = HashSet::new();
+    let mut folders: Vec<PathBuf> = Vec::new();
+    for target in project.target_ids().into_iter().filter_map(|id| project.target(id)) {
+        built.extend(target.files.into_iter().map(|f| PathBuf::from(f.path)));
+        folders.extend(target.synchronized_folders.into_iter().map(PathBuf::from));
+    }
+
+    let source_root = project.source_root.clone();
+    let walker = WalkBuilder::new(&source_root)
+        .filter_entry(|entry| {
+            let name = entry.file_name().to_string_lossy();
+            if entry.depth() > 0 && entry.path().is_dir() {
+                if SKIPPED_DIRS.contains(&name.as_ref()) {
+                    return false;
+                }
+                // Swift packages are built by SwiftPM, not by a target
+                if entry.path().join("Package.swift").exists() {
+                    return false;
+                }
+            }
+            // Don't look inside bundles
+            !entry
+                .path()
+                .parent()
+                .and_then(|p| p.extension())
+                .is_some_and(|e| BUNDLE_EXTENSIONS.iter().any(|b| e == *b))
+        })
+        .build();
+
+    let mut files = Vec::new();
+    for entry in walker.filter_map(|e| e.ok()) {
+        let path = entry.path();
+        let is_bundle = path
+            .extension()
+            .is_some_and(|e| BUNDLE_EXTENSIONS.iter().any(|b| e == *b));
+        if path.is_dir() && !is_bundle {
+            continue;
+        }
+        if !matches!(Phase::for_path(path), Some(Phase::Sources))
+            && !path.extension().is_some_and(|e| {
+                ["storyboard", "xib", "xcassets", "strings", "stringsdict", "xcstrings"]
+                    .iter()
+                    .any(|x| e == *x)
+            })
+        {
+            continue;
+        }
+
+        let Ok(relative) = path.strip_prefix(&source_root) else {
+            continue;
+        };
+        if built.contains(relative) || folders.iter().any(|f| relative.starts_with(f)) {
+            continue;
+        }
+        let display = path.strip_prefix(root).unwrap_or(path).to_string_lossy().into_owned();
+        if should_exclude(&display, exclude_patterns) {
+            continue;
+        }
+        files.push(display);
+    }
+    files.sort();
+    files
+}
+
+fn file_name(path: &Path) -> String {
+    path.file_name()
+        .map(|n| n.to_string_lossy().into_owned())
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const FIXTURE: &str = include_str!("../../tests/fixtures/Demo.pbxproj");
+    const TARGET: &str = "Demo";
+    const SOURCES_PHASE: &str = "5A1C2E402B8D4A0200F1A2B3";
+
+    fn editor(text: &str) -> ProjectEditor {
+        let bundle = Path::new("/work/Demo.xcodeproj");
+        ProjectEditor {
+            pbxproj: bundle.join("project.pbxproj"),
+            original: text.to_string(),
+            text: text.to_string(),
+            project: XcodeProject::parse(text, bundle).unwrap(),
+        }
+    }
+
+    fn targets() -> Vec<String> {
+        vec![TARGET.to_string()]
+    }
+
+    /// ID of the object of type `isa` at `path`
+    fn id_at(editor: &ProjectEditor, isa: &str, path: &str) -> String {
+        editor
+            .project()
+            .objects()
+            .find(|(id, o)| o.get_str("isa") == Some(isa) && editor.project().file_path(id) == Some(Path::new(path)))
+            .map(|(id, _)| id.to_string())
+            .unwrap_or_else(|| panic!("no {} at {}", isa, path))
+    }
+
+    /// IDs of the objects in a section, in file order
+    fn section_ids(text: &str, isa: &str) -> Vec<String> {
+        let begin = format!("/* Begin {} section */\n", isa);
+        let start = text.find(&begin).unwrap() + begin.len();
+        let end = text[start..].find(&format!("/* End {} section */", isa)).unwrap() + start;
+        text[start..end]
+            .lines()
+            .filter_map(|line| line.strip_prefix("\t\t"))
+            .filter(|line| !line.starts_with('\t') && !line.starts_with('}'))
+            .filter_map(|line| line.split(' ').next())
+            .map(String::from)
+            .collect()
+    }
+
+    fn assert_sorted(text: &str, isa: &str) {
+        let ids = section_ids(text, isa);
+        let mut sorted = ids.clone();
+        sorted.sort();
+        assert_eq!(ids, sorted, "{} section is not sorted", isa);
+    }
+
+    #[test]
+    fn add_inserts_only_the_new_lines() {
+        let mut editor = editor(FIXTURE);
+        let changes = editor.add_file(Path::new("Demo/Views/Row.swift"), &targets()).unwrap();
+        assert_eq!(
+            changes,
+            [
+                "Created group Demo/Views",
+                "Referenced Demo/Views/Row.swift",
+                "Added Demo/Views/Row.swift to Demo (Sources)",
+            ]
+        );
+
+        let file = id_at(&editor, "PBXFileReference", "Demo/Views/Row.swift");
+        let group = id_at(&editor, "PBXGroup", "Demo/Views");
+        let build = editor
+            .project()
+            .objects()
+            .find(|(_, o)| o.get_str("fileRef") == Some(file.as_str()))
+            .map(|(id, _)| id.to_string())
+            .unwrap();
+        let inserted = [
+            format!(
+                "\t\t{build} /* Row.swift in Sources */ = {{isa = PBXBuildFile; fileRef = {file} /* Row.swift */; }};\n"
+            ),
+            format!(
+                "\t\t{file} /* Row.swift */ = {{isa = PBXFileReference; lastKnownFileType = sourcecode.swift; path = Row.swift; sourceTree = \"<group>\"; }};\n"
+            ),
+            format!(
+                "\t\t{group} /* Views */ = {{\n\t\t\tisa = PBXGroup;\n\t\t\tchildren = (\n\t\t\t\t{file} /* Row.swift */,\n\t\t\t);\n\t\t\tpath = Views;\n\t\t\tsourceTree = \"<group>\";\n\t\t}};\n"
+            ),
+            format!("/* Info.plist */,\n\t\t\t\t{group} /* Views */,\n"),
+            format!("/* DemoApp.swift in Sources */,\n\t\t\t\t{build} /* Row.swift in Sources */,\n"),
+        ];
+
+        // Taking the inserted lines back out leaves the original byte for byte
+        let mut text = editor.text.clone();
+        for chunk in &inserted {
+            assert_eq!(text.matches(chunk.as_str()).count(), 1, "{}", chunk);
+            let kept = chunk.split_once(",\n\t\t\t\t").map_or("", |(before, _)| before);
+            let kept = if kept.is_empty() { String::new() } else { format!("{},\n", kept) };
+            text = text.replacen(chunk.as_str(), &kept, 1);
+        }
+        assert_eq!(text, FIXTURE);
+
+        for isa in ["PBXBuildFile", "PBXFileReference", "PBXGroup"] {
+            assert_sorted(&editor.text, isa);
+        }
+        assert_eq!(
+            editor.project().object(SOURCES_PHASE).unwrap().get_strings("files").last(),
+            Some(&build.as_str())
+        );
+    }
+
+    #[test]
+    fn add_is_idempotent() {
+        let mut editor = editor(FIXTURE);
+        editor.add_file(Path::new("Demo/Row.swift"), &targets()).unwrap();
+        let once = editor.text.clone();
+        let changes = editor.add_file(Path::new("Demo/Row.swift"), &targets()).unwrap();
+        assert_eq!(changes, ["Demo/Row.swift is already in Demo"]);
+        assert_eq!(editor.text, once);
+    }
+
+    #[test]
+    fn add_rejects_unknown_targets() {
+        let mut editor = editor(FIXTURE);
+        let err = editor
+            .add_file(Path::new("Demo/Row.swift"), &["Missing".to_string()])
+            .unwrap_err();
+        assert!(err.to_string().contains("No target named 'Missing'"), "{}", err);
+        assert_eq!(editor.text, FIXTURE);
+    }
+
+    #[test]
+    fn files_outside_phases_are_only_referenced() {
+        let mut editor = editor(FIXTURE);
+        let changes = editor.add_file(Path::new("Demo/Config.xcconfig"), &targets()).unwrap();
+        assert_eq!(
+            changes,
+            [
+                "Referenced Demo/Config.xcconfig",
+                "Demo/Config.xcconfig isn't built by any phase, so it was only referenced",
+            ]
+        );
+        assert!(!editor.text.contains("Config.xcconfig in "));
+    }
+
+    #[test]
+    fn inserts_objects_in_id_order() {
+        let mut editor = editor(FIXTURE);
+        let line = |id: &str| format!("\t\t{id} /* X */ = {{isa = PBXBuildFile; fileRef = 5A1C2E472B8D4A0200F1A2B3; }};\n");
+        for id in ["5A1C2E492B8D4A0200F1A2B3", "0000000000000000000000AA", "FFFFFFFFFFFFFFFFFFFFFFFF"] {
+            editor.insert_object("PBXBuildFile", id, &line(id)).unwrap();
+        }
+        assert_eq!(
+            section_ids(&editor.text, "PBXBuildFile"),
+            [
+                "0000000000000000000000AA",
+                "5A1C2E482B8D4A0200F1A2B3",
+                "5A1C2E492B8D4A0200F1A2B3",
+                "5A1C2E4A2B8D4A0200F1A2B3",
+                "5A1C2E4C2B8D4A0300F1A2B3",
+                "FFFFFFFFFFFFFFFFFFFFFFFF",
+            ]
+        );
+    }
+
+    #[test]
+    fn add_then_remove_restores_the_project() {
+        let mut editor = editor(FIXTURE);
+        editor.add_file(Path::new("Demo/Views/Rows/Row.swift"), &targets()).unwrap();
+        editor.add_file(Path::new("Demo/Views/Rows/Cell.swift"), &targets()).unwrap();
+
+        editor.remove_file(Path::new("Demo/Views/Rows/Row.swift"), &[]).unwrap();
+        assert!(editor.text.contains("/* Rows */ = {"), "group still holds Cell.swift");
+
+        let changes = editor.remove_file(Path::new("Demo/Views/Rows/Cell.swift"), &[]).unwrap();
+        assert_eq!(
+            changes,
+            [
+                "Removed Demo/Views/Rows/Cell.swift from the project",
+                "Removed empty group Demo/Views/Rows",
+                "Removed empty group Demo/Views",
+            ]
+        );
+        assert_eq!(editor.text, FIXTURE);
+        assert!(!editor.save().unwrap());
+    }
+
+    #[test]
+    fn remove_keeps_groups_without_a_directory() {
+        let mut editor = editor(FIXTURE);
+        for file in ["DemoApp.swift", "ContentView.swift", "Assets.xcassets", "Info.plist"] {
+            editor.remove_file(&Path::new("Demo").join(file), &[]).unwrap();
+        }
+        // The Demo group went with its last file; Products and the main group have no path
+        assert!(!editor.text.contains("/* Demo */ = {\n\t\t\tisa = PBXGroup;"));
+        assert!(editor.text.contains("/* Products */ = {"));
+        assert_eq!(editor.project().main_group(), Some("5A1C2E3B2B8D4A0200F1A2B3"));
+    }
+
+    #[test]
+    fn remove_from_a_target_keeps_the_reference() {
+        let mut editor = editor(FIXTURE);
+        let changes = editor.remove_file(Path::new("Demo/DemoApp.swift"), &targets()).unwrap();
+        assert_eq!(changes, ["Removed Demo/DemoApp.swift from Demo"]);
+        assert!(!editor.text.contains("DemoApp.swift in Sources"));
+        assert!(editor.text.contains("/* DemoApp.swift */ = {isa = PBXFileReference;"));
+
+        let changes = editor.remove_file(Path::new("Demo/DemoApp.swift"), &targets()).unwrap();
+        assert_eq!(changes, ["Demo/DemoApp.swift is not in Demo"]);
+        assert!(editor.remove_file(Path::new("Demo/Missing.swift"), &[]).is_err());
+    }
+
+    #[test]
+    fn drops_and_recreates_empty_sections() {
+        let mut editor = editor(FIXTURE);
+        for file in ["DemoApp.swift", "ContentView.swift", "Assets.xcassets"] {
+            editor.remove_file(&Path::new("Demo").join(file), &targets()).unwrap();
+        }
+        assert!(!editor.text.contains("PBXBuildFile section"));
+        assert!(editor.text.contains("\tobjects = {\n\n/* Begin PBXFileReference section */\n"));
+        assert!(!editor.text.contains("\n\n\n"));
+
+        editor.add_file(Path::new("Demo/DemoApp.swift"), &targets()).unwrap();
+        let build = section_ids(&editor.text, "PBXBuildFile");
+        assert_eq!(build.len(), 1);
+        assert!(editor.text.contains(&format!(
+            "\tobjects = {{\n\n/* Begin PBXBuildFile section */\n\t\t{} /* DemoApp.swift in Sources */",
+            build[0]
+        )));
+        assert!(editor.text.contains("/* End PBXBuildFile section */\n\n/* Begin PBXFileReference section */\n"));
+    }
+
+    #[test]
+    fn adds_a_section_after_the_last_one() {
+        let mut editor = editor(FIXTURE);
+        let id = "5A1C2E702B8D4A0300F1A2B3";
+        let object = format!(
+            "\t\t{id} /* Model.xcdatamodeld */ = {{\n\t\t\tisa = XCVersionGroup;\n\t\t\tchildren = (\n\t\t\t);\n\t\t}};\n"
+        );
+        editor.insert_object("XCVersionGroup", id, &object).unwrap();
+        assert!(editor.text.contains(&format!(
+            "/* End XCConfigurationList section */\n\n/* Begin XCVersionGroup section */\n{object}/* End XCVersionGroup section */\n\t}};"
+        )));
+
+        editor.reload(Vec::new()).unwrap();
+        editor.remove_object(id).unwrap();
+        assert_eq!(editor.text, FIXTURE);
+    }
+
+    #[test]
+    fn check_lists_sources_no_target_builds() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        let files = [
+            "Demo/DemoApp.swift",
+            "Demo/ContentView.swift",
+            "Demo/Assets.xcassets/Contents.json",
+            "Demo/Info.plist",
+            "Demo/Notes.md",
+            "Demo/Stray.swift",
+            "Demo/Views/Row.swift",
+            "Demo/Base.lproj/Main.storyboard",
+            "Demo/Generated/Model.swift",
+            "build/Derived.swift",
+            "Packages/Kit/Package.swift",
+            "Packages/Kit/Sources/Kit/Kit.swift",
+        ];
+        for file in files {
+            let path = root.join(file);
+            std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+            std::fs::write(&path, "").unwrap();
+        }
+        let bundle = root.join("Demo.xcodeproj");
+        std::fs::create_dir_all(&bundle).unwrap();
+        std::fs::write(bundle.join("project.pbxproj"), FIXTURE).unwrap();
+
+        let excluded = vec!["Generated/".to_string()];
+        let project = XcodeProject::open(&bundle).unwrap();
+        assert_eq!(
+            unreferenced_files(&project, root, &excluded),
+            ["Demo/Base.lproj/Main.storyboard", "Demo/Stray.swift", "Demo/Views/Row.swift"]
+        );
+
+        let mut editor = ProjectEditor::open(&bundle).unwrap();
+        editor.add_file(Path::new("Demo/Stray.swift"), &targets()).unwrap();
+        assert!(editor.save().unwrap());
+        let project = XcodeProject::open(&bundle).unwrap();
+        assert_eq!(
+            unreferenced_files(&project, root, &excluded),
+            ["Demo/Base.lproj/Main.storyboard", "Demo/Views/Row.swift"]
+        );
+    }
+}
@@ -0,0 +1,87 @@
+use std::path::Path;
+
+use super::package::{name_argument, strip_comments};
+
+/// Schemes of a project XcodeGen or Tuist generates, read from its manifest:
+/// the schemes it declares, or else its targets, which get one each. Only
+/// plain YAML keys and literal `name:` arguments are understood; the agent
+/// lists the generated project properly.
+pub fn schemes(dir: &Path) -> Option<Vec<String>> {
+    for spec in ["project.yml", "project.yaml"] {
+        if let Ok(text) = std::fs::read_to_string(dir.join(spec)) {
+            return Some(xcodegen_schemes(&text));
+        }
+    }
+    let text = std::fs::read_to_string(dir.join("Project.swift")).ok()?;
+    Some(tuist_schemes(&text))
+}
+
+fn xcodegen_schemes(text: &str) -> Vec<String> {
+    let schemes = yaml_keys(text, "schemes");
+    if schemes.is_empty() {
+        yaml_keys(text, "targets")
+    } else {
+        schemes
+    }
+}
+
+fn tuist_schemes(text: &str) -> Vec<String> {
+    let code = strip_comments(text);
+    let schemes = call_names(&code, &["Scheme(", ".scheme("]);
+    if schemes.is_empty() {
+        // Dependencies are `.target(name:)` too, but only name declared targets
+        call_names(&code, &["Target(", ".target("])
+    } else {
+        schemes
+    }
+}
+
+/// The `name:` of each call in `code`, in order and without repeats
+fn call_names(code: &str, calls: &[&str]) -> Vec<String> {
+    let mut found = Vec::new();
+    for call in calls {
+        for (start, _) in code.match_indices(call) {
+            if let Some(name) = name_argument(&code[start..]) {
+                found.push((start, name));
+            }
+        }
+    }
+    found.sort();
+
+    let mut names: Vec<String> = Vec::new();
+    for (_, name) in found {
+        if !names.contains(&name) {
+            names.push(name);
+        }
+    }
+    names
+}
+
+/// Keys of the mapping under a top-level `section:` of a YAML document
+fn yaml_keys(text: &str, section: &str) -> Vec<String> {
+    let mut keys = Vec::new();
+    let mut in_section = false;
+    let mut key_indent = None;
+    for line in text.lines() {
+        let content = line.trim_start();
+        if content.is_empty() || content.starts_with('#') {
+            continue;
+        }
+        let indent = line.len() - content.len();
+        if indent == 0 {
+            in_section = content
+                .strip_prefix(section)
+                .and_then(|rest| rest.strip_prefix(':'))
+                .is_some_and(|rest| rest.trim().is_empty() || rest.trim_start().starts_with('#'));
+            key_indent = None;
+            continue;
+        }
+        if !in_section || *key_indent.get_or_insert(indent) != indent {
+            continue;
+        }
+        if let Some((key, _)) = content.split_once(':') {
+            keys.push(key.trim().trim_matches(|c| c == '"' || c == '\'').to_string());
+        }
+    }
+    keys
+}
@@ -4,10 +4,18 @@ use flate2::Compression;
 use ignore::WalkBuilder;
 use sha2::{Digest, Sha256};
 use std::io::Write;
-use std::path::Path;
+use std::path::{Component, Path, PathBuf};
 use tar::Builder;
 use tracing::{debug, info};
 
+pub mod edit;
+pub mod generator;
+pub mod package;
+pub mod pbxproj;
+pub mod preflight;
+pub mod template;
+pub mod xcodeproj;
+
 /// Create a tarball of a project directory
 /// Returns (tarball_bytes, sha256_checksum)
 pub fn create_tarball(
@@ -122,6 +130,24 @@ fn should_exclude(path: &str, patterns: &[String]) -> bool {
     false
 }
 
+/// Resolve `.` and `..` without touching the filesystem, so missing files
+/// still get a clean path
+fn normalize(path: &Path) -> PathBuf {
+    let mut out = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir => {
+                if !out.pop() {
+                    out.push("..");
+                }
+            }
+            other => out.push(other),
+        }
+    }
+    out
+}
+
 /// Find the project name from directory
 pub fn get_project_name(project_path: &Path) -> String {
     project_path
@@ -130,7 +156,7 @@ pub fn get_project_name(project_path: &Path) -> String {
         .unwrap_or_else(|| "project".to_string())
 }
 
-/// Find Xcode project/workspace in directory
+/// Find the Xcode workspace, project or Swift package in a directory
 pub fn find_xcode_project(project_path: &Path) -> Option<String> {
     // Look for workspace first
     for entry in std::fs::read_dir(project_path).ok()?.filter_map(|e| e.ok()) {
@@ -148,5 +174,100 @@ pub fn find_xcode_project(project_path: &Path) -> Option<String> {
         }
     }
 
+    // Then a package, which xcodebuild opens like a workspace
+    if project_path.join("Package.swift").is_file() {
+        return Some("Package.swift".to_string());
+    }
+
     None
 }
+
+/// Find Xcode schemes in a project directory without asking the agent:
+/// shared schemes, then each user's own, falling back to project targets,
+/// the schemes of an XcodeGen or Tuist manifest, or package products
+pub fn find_schemes(project_path: &Path) -> Result<Vec<String>> {
+    let mut schemes = Vec::new();
+
+    let mut containers = Vec::new();
+    for entry in std::fs::read_dir(project_path)? {
+        let path = entry?.path();
+        if path.extension().map_or(false, |e| e == "xcodeproj" || e == "xcworkspace") {
+            containers.push(path);
+        }
+    }
+    // Projects XcodeGen or Tuist generate on the agent declare theirs in the
+    // manifest
+    let generated = if containers.is_empty() {
+        generator::schemes(project_path)
+    } else {
+        None
+    };
+    // Xcode keeps a package's schemes in .swiftpm
+    let package = if containers.is_empty() && generated.is_none() {
+        package::PackageManifest::read(project_path)
+    } else {
+        None
+    };
+    if package.is_some() {
+        containers.push(project_path.join(".swiftpm/xcode"));
+    }
+
+    for path in containers {
+
+        let mut scheme_dirs = vec![path.join("xcshareddata/xcschemes")];
+        if let Ok(users) = std::fs::read_dir(path.join("xcuserdata")) {
+            scheme_dirs.extend(users.filter_map(|e| e.ok()).map(|e| e.path().join("xcschemes")));
+        }
+
+        for dir in scheme_dirs {
+            let Ok(entries) = std::fs::read_dir(&dir) else {
+                continue;
+            };
+            for scheme_path in entries.filter_map(|e| e.ok()).map(|e| e.path()) {
+                if scheme_path.extension().map_or(false, |e| e == "xcscheme") {
+                    if let Some(name) = scheme_path.file_stem() {
+                        let name = name.to_string_lossy().to_string();
+                        if !schemes.contains(&name) {
+                            schemes.push(name);
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    // Without scheme files, Xcode autocreates one per target (or package
+    // product), so use the targets of projects we can parse and the project
+    // name otherwise
+    if schemes.is_empty() {
+        if let Some(generated) = generated {
+            schemes = generated;
+        } else if let Some(package) = package {
+            schemes = package.schemes();
+        }
+    }
+    if schemes.is_empty() {
+        for path in xcodeproj::find_projects(project_path) {
+            let targets = match xcodeproj::XcodeProject::open(&path) {
+                Ok(project) => project.target_names(),
+                Err(e) => {
+                    debug!("Failed to read {:?}: {:#}", path, e);
+                    Vec::new()
+                }
+            };
+            if targets.is_empty() {
+                if let Some(name) = path.file_stem() {
+                    schemes.push(name.to_string_lossy().to_string());
+                }
+            }
+            for name in targets {
+                if !schemes.contains(&name) {
+                    schemes.push(name);
+                }
+            }
+        }
+    }
+
+    schemes.sort();
+    Ok(schemes)
+}
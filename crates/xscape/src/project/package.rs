@@ -0,0 +1,105 @@
+use std::path::Path;
+
+/// What a Package.swift declares, read from its source. Manifests are Swift
+/// code, so this only understands the literal `name:` arguments of the usual
+/// `Package(...)`, `.library(...)` and `.executable(...)` calls; the agent
+/// evaluates the manifest properly.
+#[derive(Debug, Clone)]
+pub struct PackageManifest {
+    pub name: String,
+    /// Library and executable products, in declaration order
+    pub products: Vec<String>,
+}
+
+impl PackageManifest {
+    /// Read the Package.swift in `dir`, if there is one
+    pub fn read(dir: &Path) -> Option<Self> {
+        let text = std::fs::read_to_string(dir.join("Package.swift")).ok()?;
+        Self::parse(&text)
+    }
+
+    pub fn parse(text: &str) -> Option<Self> {
+        let code = strip_comments(text);
+        let package = code.find("Package(")?;
+        let name = name_argument(&code[package..])?;
+
+        let mut products = Vec::new();
+        for call in [".library(", ".executable("] {
+            for (start, _) in code.match_indices(call) {
+                if let Some(product) = name_argument(&code[start..]) {
+                    products.push((start, product));
+                }
+            }
+        }
+        products.sort();
+
+        Some(Self {
+            name,
+            products: products.into_iter().map(|(_, name)| name).collect(),
+        })
+    }
+
+    /// Schemes Xcode creates for the package: one per product, plus
+    /// `<name>-Package` building everything when there are several products
+    pub fn schemes(&self) -> Vec<String> {
+        let mut schemes = self.products.clone();
+        if self.products.len() != 1 {
+            schemes.push(format!("{}-Package", self.name));
+        }
+        schemes
+    }
+}
+
+/// The string literal passed as the first `name:` argument in `code`
+pub(super) fn name_argument(code: &str) -> Option<String> {
+    let rest = &code[code.find("name:")? + "name:".len()..];
+    let rest = rest.trim_start().strip_prefix('"')?;
+    let end = rest.find('"')?;
+    Some(rest[..end].to_string())
+}
+
+/// Remove `//` and `/* */` comments, leaving string literals (which may hold
+/// URLs) alone
+pub(super) fn strip_comments(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut chars = text.chars().peekable();
+    let mut in_string = false;
+    while let Some(c) = chars.next() {
+        if in_string {
+            out.push(c);
+            match c {
+                '\\' => out.extend(chars.next()),
+                '"' => in_string = false,
+                _ => {}
+            }
+            continue;
+        }
+        match (c, chars.peek()) {
+            ('"', _) => {
+                in_string = true;
+                out.push(c);
+            }
+            ('/', Some('/')) => {
+                for c in chars.by_ref() {
+                    if c == '\n' {
+                        out.push(c);
+                        break;
+                    }
+                }
+            }
+            ('/', Some('*')) => {
+                chars.next();
+                let mut previous = ' ';
+                for c in chars.by_ref() {
+                    if previous == '*' && c == '/' {
+                        break;
+                    }
+                    previous = c;
+                }
+                out.push(' ');
+            }
+            _ => out.push(c),
+        }
+    }
+    out
+}
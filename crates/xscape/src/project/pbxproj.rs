@@ -0,0 +1,460 @@
+use std::fmt;
+use thiserror::Error;
+
+/// Errors from parsing an OpenStep property list
+#[derive(Debug, Error)]
+pub enum PbxprojError {
+    #[error("line {line}: {message}")]
+    Syntax { line: usize, message: String },
+}
+
+/// A value in an OpenStep-style property list, the format of project.pbxproj
+#[derive(Debug, Clone, PartialEq)]
+pub enum Value {
+    String(String),
+    Data(Vec<u8>),
+    Array(Vec<Value>),
+    Dictionary(Dictionary),
+}
+
+impl Value {
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            Self::String(s) => Some(s),
+            _ => None,
+        }
+    }
+
+    pub fn as_array(&self) -> Option<&[Value]> {
+        match self {
+            Self::Array(items) => Some(items),
+            _ => None,
+        }
+    }
+
+    pub fn as_dict(&self) -> Option<&Dictionary> {
+        match self {
+            Self::Dictionary(dict) => Some(dict),
+            _ => None,
+        }
+    }
+
+    /// The strings of an array, skipping anything else
+    pub fn string_array(&self) -> Vec<&str> {
+        self.as_array()
+            .map(|items| items.iter().filter_map(|v| v.as_str()).collect())
+            .unwrap_or_default()
+    }
+}
+
+/// A dictionary that keeps its keys in file order
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Dictionary {
+    entries: Vec<(String, Value)>,
+}
+
+impl Dictionary {
+    pub fn get(&self, key: &str) -> Option<&Value> {
+        self.entries.iter().find(|(k, _)| k == key).map(|(_, v)| v)
+    }
+
+    pub fn get_str(&self, key: &str) -> Option<&str> {
+        self.get(key).and_then(|v| v.as_str())
+    }
+
+    pub fn get_dict(&self, key: &str) -> Option<&Dictionary> {
+        self.get(key).and_then(|v| v.as_dict())
+    }
+
+    /// Strings of an array entry (e.g., the object IDs in `children`)
+    pub fn get_strings(&self, key: &str) -> Vec<&str> {
+        self.get(key).map(|v| v.string_array()).unwrap_or_default()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &Value)> {
+        self.entries.iter().map(|(k, v)| (k.as_str(), v))
+    }
+}
+
+/// Parse an OpenStep property list (e.g., the contents of project.pbxproj)
+pub fn parse(text: &str) -> Result<Value, PbxprojError> {
+    let mut parser = Parser { input: text.as_bytes(), pos: 0 };
+    let value = parser.value()?;
+    parser.skip_trivia()?;
+    if parser.pos < parser.input.len() {
+        return Err(parser.error("unexpected content after the root value"));
+    }
+    Ok(value)
+}
+
+struct Parser<'a> {
+    input: &'a [u8],
+    pos: usize,
+}
+
+impl Parser<'_> {
+    fn value(&mut self) -> Result<Value, PbxprojError> {
+        self.skip_trivia()?;
+        match self.peek() {
+            Some(b'{') => self.dictionary(),
+            Some(b'(') => self.array(),
+            Some(b'<') => self.data(),
+            Some(b'"') | Some(b'\'') => self.quoted_string().map(Value::String),
+            Some(c) if is_unquoted(c) => Ok(Value::String(self.unquoted_string())),
+            Some(c) => Err(self.error(format!("unexpected character '{}'", c as char))),
+            None => Err(self.error("unexpected end of input")),
+        }
+    }
+
+    fn dictionary(&mut self) -> Result<Value, PbxprojError> {
+        self.pos += 1;
+        let mut dict = Dictionary::default();
+        loop {
+            self.skip_trivia()?;
+            if self.eat(b'}') {
+                return Ok(Value::Dictionary(dict));
+            }
+            let key = match self.value()? {
+                Value::String(key) => key,
+                _ => return Err(self.error("dictionary keys must be strings")),
+            };
+            self.skip_trivia()?;
+            if !self.eat(b'=') {
+                return Err(self.error(format!("expected '=' after key '{}'", key)));
+            }
+            let value = self.value()?;
+            self.skip_trivia()?;
+            if !self.eat(b';') {
+                return Err(self.error(format!("expected ';' after value of '{}'", key)));
+            }
+            dict.entries.push((key, value));
+        }
+    }
+
+    fn array(&mut self) -> Result<Value, PbxprojError> {
+        self.pos += 1;
+        let mut items = Vec::new();
+        loop {
+            self.skip_trivia()?;
+            if self.eat(b')') {
+                return Ok(Value::Array(items));
+            }
+            items.push(self.value()?);
+            self.skip_trivia()?;
+            // A trailing comma before ')' is allowed
+            if !self.eat(b',') && self.peek() != Some(b')') {
+                return Err(self.error("expected ',' or ')' in array"));
+            }
+        }
+    }
+
+    fn data(&mut self) -> Result<Value, PbxprojError> {
+        self.pos += 1;
+        let mut hex = Vec::new();
+        loop {
+            match self.next() {
+                Some(b'>') => break,
+                Some(c) if c.is_ascii_hexdigit() => hex.push(c),
+                Some(c) if c.is_ascii_whitespace() => {}
+                Some(c) => return Err(self.error(format!("invalid character '{}' in data", c as char))),
+                None => return Err(self.error("unterminated data")),
+            }
+        }
+        if hex.len() % 2 != 0 {
+            return Err(self.error("odd number of hex digits in data"));
+        }
+        let bytes = hex
+            .chunks(2)
+            .map(|pair| (hex_value(pair[0]) << 4) | hex_value(pair[1]))
+            .collect();
+        Ok(Value::Data(bytes))
+    }
+
+    fn quoted_string(&mut self) -> Result<String, PbxprojError> {
+        let quote = self.input[self.pos];
+        self.pos += 1;
+        let mut bytes = Vec::new();
+        loop {
+            match self.next() {
+                Some(c) if c == quote => break,
+                Some(b'\\') => self.escape(&mut bytes)?,
+                Some(c) => bytes.push(c),
+                None => return Err(self.error("unterminated string")),
+            }
+        }
+        String::from_utf8(bytes).map_err(|_| self.error("string is not valid UTF-8"))
+    }
+
+    fn escape(&mut self, out: &mut Vec<u8>) -> Result<(), PbxprojError> {
+        let c = self.next().ok_or_else(|| self.error("unterminated escape"))?;
+        let unescaped = match c {
+            b'n' => b'\n',
+            b't' => b'\t',
+            b'r' => b'\r',
+            b'a' => 0x07,
+            b'b' => 0x08,
+            b'f' => 0x0c,
+            b'v' => 0x0b,
+            b'U' => {
+                let mut code = 0u32;
+                for _ in 0..4 {
+                    let digit = self
+                        .next()
+                        .filter(|d| d.is_ascii_hexdigit())
+                        .ok_or_else(|| self.error("invalid \\U escape"))?;
+                    code = (code << 4) | hex_value(digit) as u32;
+                }
+                let ch = char::from_u32(code).unwrap_or(char::REPLACEMENT_CHARACTER);
+                out.extend_from_slice(ch.to_string().as_bytes());
+                return Ok(());
+            }
+            b'0'..=b'7' => {
+                let mut code = (c - b'0') as u32;
+                for _ in 0..2 {
+                    match self.peek() {
+                        Some(d @ b'0'..=b'7') => {
+                            code = (code << 3) | (d - b'0') as u32;
+                            self.pos += 1;
+                        }
+                        _ => break,
+                    }
+                }
+                let ch = char::from_u32(code).unwrap_or(char::REPLACEMENT_CHARACTER);
+                out.extend_from_slice(ch.to_string().as_bytes());
+                return Ok(());
+            }
+            other => other,
+        };
+        out.push(unescaped);
+        Ok(())
+    }
+
+    fn unquoted_string(&mut self) -> String {
+        let start = self.pos;
+        while self.peek().is_some_and(is_unquoted) {
+            self.pos += 1;
+        }
+        String::from_utf8_lossy(&self.input[start..self.pos]).into_owned()
+    }
+
+    /// Skip whitespace and `//` and `/* */` comments
+    fn skip_trivia(&mut self) -> Result<(), PbxprojError> {
+        loop {
+            match (self.peek(), self.input.get(self.pos + 1)) {
+                (Some(c), _) if c.is_ascii_whitespace() => self.pos += 1,
+                (Some(b'/'), Some(b'/')) => {
+                    while self.peek().is_some_and(|c| c != b'\n') {
+                        self.pos += 1;
+                    }
+                }
+                (Some(b'/'), Some(b'*')) => {
+                    let end = self.input[self.pos + 2..]
+                        .windows(2)
+                        .position(|w| w == b"*/")
+                        .ok_or_else(|| self.error("unterminated comment"))?;
+                    self.pos += 2 + end + 2;
+                }
+                _ => return Ok(()),
+            }
+        }
+    }
+
+    fn peek(&self) -> Option<u8> {
+        self.input.get(self.pos).copied()
+    }
+
+    fn next(&mut self) -> Option<u8> {
+        let c = self.peek()?;
+        self.pos += 1;
+        Some(c)
+    }
+
+    fn eat(&mut self, expected: u8) -> bool {
+        if self.peek() == Some(expected) {
+            self.pos += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn error(&self, message: impl fmt::Display) -> PbxprojError {
+        let line = self.input[..self.pos.min(self.input.len())]
+            .iter()
+            .filter(|&&c| c == b'\n')
+            .count()
+            + 1;
+        PbxprojError::Syntax {
+            line,
+            message: message.to_string(),
+        }
+    }
+}
+
+/// Format a string the way Xcode writes it, quoting it unless every
+/// character is safe to leave bare
+pub fn quote(s: &str) -> String {
+    let bare = !s.is_empty()
+        && s.bytes().all(|c| c.is_ascii_alphanumeric() || matches!(c, b'_' | b'$' | b'/' | b':' | b'.'))
+        && !s.contains("___")
+        && !s.contains("//");
+    if bare {
+        return s.to_string();
+    }
+
+    let mut quoted = String::with_capacity(s.len() + 2);
+    quoted.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => quoted.push_str("\\\""),
+            '\\' => quoted.push_str("\\\\"),
+            '\n' => quoted.push_str("\\n"),
+            '\t' => quoted.push_str("\\t"),
+            c => quoted.push(c),
+        }
+    }
+    quoted.push('"');
+    quoted
+}
+
+/// Characters allowed in strings without quotes
+fn is_unquoted(c: u8) -> bool {
+    c.is_ascii_alphanumeric() || matches!(c, b'_' | b'$' | b'+' | b'/' | b':' | b'.' | b'-')
+}
+
+fn hex_value(c: u8) -> u8 {
+    match c {
+        b'0'..=b'9' => c - b'0',
+        b'a'..=b'f' => c - b'a' + 10,
+        _ => c - b'A' + 10,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn string(text: &str) -> String {
+        parse(text).unwrap().as_str().unwrap().to_string()
+    }
+
+    #[test]
+    fn parses_escapes() {
+        assert_eq!(string(r#""a\"b\\c\nd\te""#), "a\"b\\c\nd\te");
+        assert_eq!(string(r#""\a\b\f\v\r""#), "\u{7}\u{8}\u{c}\u{b}\r");
+        assert_eq!(string(r#"'single "quoted"'"#), "single \"quoted\"");
+        // Unknown escapes keep the character
+        assert_eq!(string(r#""\q""#), "q");
+    }
+
+    #[test]
+    fn parses_unicode_and_octal_escapes() {
+        assert_eq!(string(r#""caf\U00e9""#), "café");
+        assert_eq!(string(r#""\U2713 done""#), "✓ done");
+        assert_eq!(string(r#""\101\102C""#), "ABC");
+        // Octal escapes stop after three digits or at a non-octal digit
+        assert_eq!(string(r#""\1018""#), "A8");
+        assert_eq!(string(r#""\7x""#), "\u{7}x");
+        assert!(parse(r#""\U00g1""#).is_err());
+    }
+
+    #[test]
+    fn parses_data() {
+        let value = parse("<0fbd 7A00\n 01>").unwrap();
+        assert_eq!(value, Value::Data(vec![0x0f, 0xbd, 0x7a, 0x00, 0x01]));
+        assert_eq!(parse("<>").unwrap(), Value::Data(Vec::new()));
+        assert!(parse("<abc>").is_err());
+        assert!(parse("<zz>").is_err());
+        assert!(parse("<00").is_err());
+    }
+
+    #[test]
+    fn skips_comments() {
+        let text = "// header\n{ /* before key */ a /* after key */ = /* before value */ b; // trailing\n}";
+        let value = parse(text).unwrap();
+        assert_eq!(value.as_dict().unwrap().get_str("a"), Some("b"));
+        assert!(parse("{ a = b; /* open").is_err());
+    }
+
+    #[test]
+    fn parses_arrays_with_and_without_trailing_commas() {
+        let value = parse("{ a = (x, y,); b = (x, y); c = (); d = ( /* none */ ); }").unwrap();
+        let dict = value.as_dict().unwrap();
+        assert_eq!(dict.get_strings("a"), ["x", "y"]);
+        assert_eq!(dict.get_strings("b"), ["x", "y"]);
+        assert!(dict.get_strings("c").is_empty());
+        assert!(dict.get_strings("d").is_empty());
+        assert!(parse("(x,,)").is_err());
+        assert!(parse("(x y)").is_err());
+    }
+
+    #[test]
+    fn keeps_dictionary_order() {
+        let value = parse("{ zeta = 1; alpha = { nested = \"$(inherited)\"; }; mid = -1.5; }").unwrap();
+        let dict = value.as_dict().unwrap();
+        let keys: Vec<&str> = dict.iter().map(|(k, _)| k).collect();
+        assert_eq!(keys, ["zeta", "alpha", "mid"]);
+        assert_eq!(dict.get_dict("alpha").unwrap().get_str("nested"), Some("$(inherited)"));
+        assert_eq!(dict.get_str("mid"), Some("-1.5"));
+    }
+
+    #[test]
+    fn reports_the_line_of_errors() {
+        let err = parse("{\n\ta = b;\n\tc = d\n}").unwrap_err();
+        let PbxprojError::Syntax { line, message } = err;
+        assert_eq!(line, 4);
+        assert!(message.contains("';'"), "{}", message);
+
+        let PbxprojError::Syntax { line, .. } = parse("{\n\ta = \"open;\n}").unwrap_err();
+        assert_eq!(line, 3);
+        assert!(parse("{ a = b; } extra").is_err());
+        assert!(parse("{ (a) = b; }").is_err());
+    }
+
+    #[test]
+    fn quote_round_trips() {
+        for s in [
+            "App.swift",
+            "$(inherited)",
+            "DEBUG=1",
+            "",
+            "with space",
+            "quote\"and\\backslash",
+            "line\nbreak\ttab",
+            "___PROJECTNAME___",
+            "http://example.com",
+            "café",
+        ] {
+            let quoted = quote(s);
+            assert_eq!(string(&quoted), s, "{}", quoted);
+        }
+    }
+
+    #[test]
+    fn quotes_like_xcode() {
+        assert_eq!(quote("Demo/Info.plist"), "Demo/Info.plist");
+        assert_eq!(quote("sourcecode.swift"), "sourcecode.swift");
+        assert_eq!(quote("<group>"), "\"<group>\"");
+        assert_eq!(quote("com.apple.product-type.application"), "\"com.apple.product-type.application\"");
+        assert_eq!(quote(""), "\"\"");
+        assert_eq!(quote("a___b"), "\"a___b\"");
+    }
+
+    #[test]
+    fn parses_an_xcode_project() {
+        let text = include_str!("../../tests/fixtures/Demo.pbxproj");
+        let root = parse(text).unwrap();
+        let root = root.as_dict().unwrap();
+        assert_eq!(root.get_str("objectVersion"), Some("60"));
+        let objects = root.get_dict("objects").unwrap();
+        assert_eq!(objects.iter().count(), 22);
+
+        let debug = objects.get_dict("5A1C2E5D2B8D4A0300F1A2B3").unwrap().get_dict("buildSettings").unwrap();
+        assert_eq!(debug.get_strings("GCC_PREPROCESSOR_DEFINITIONS"), ["DEBUG=1", "$(inherited)"]);
+        assert_eq!(debug.get_str("SWIFT_ACTIVE_COMPILATION_CONDITIONS"), Some("DEBUG $(inherited)"));
+
+        let project = objects.get_dict("5A1C2E3C2B8D4A0200F1A2B3").unwrap();
+        assert_eq!(project.get_str("projectDirPath"), Some(""));
+        assert_eq!(project.get_str("compatibilityVersion"), Some("Xcode 14.0"));
+    }
+}
@@ -0,0 +1,161 @@
+use serde::Serialize;
+use std::collections::HashSet;
+use std::path::Path;
+
+use super::{normalize, should_exclude};
+use super::xcodeproj::{find_projects, PackageReference, XcodeProject};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    /// The build will fail
+    Error,
+    /// Worth knowing, but the build may still succeed
+    Warning,
+}
+
+/// A problem with a project found before uploading it
+#[derive(Debug, Clone, Serialize)]
+pub struct PreflightIssue {
+    pub severity: Severity,
+    pub message: String,
+    /// The offending path, relative to the project directory where possible
+    pub path: Option<String>,
+}
+
+impl PreflightIssue {
+    fn new(severity: Severity, message: impl Into<String>, path: &Path, root: &Path) -> Self {
+        Self {
+            severity,
+            message: message.into(),
+            path: Some(display_path(path, root)),
+        }
+    }
+}
+
+/// Check the Xcode projects in `root` for files the agent won't receive:
+/// references to missing files, files outside `root` and files the exclude
+/// patterns leave out of the upload.
+pub fn check(root: &Path, exclude_patterns: &[String]) -> Vec<PreflightIssue> {
+    let mut issues = Vec::new();
+    for path in find_projects(root) {
+        match XcodeProject::open(&path) {
+            Ok(project) => check_project(&project, root, exclude_patterns, &mut issues),
+            Err(e) => issues.push(PreflightIssue {
+                severity: Severity::Warning,
+                message: format!("{:#}", e),
+                path: Some(display_path(&path, root)),
+            }),
+        }
+    }
+    issues
+}
+
+/// Check one project. Problems with files a target builds are errors;
+/// problems with other references only warnings.
+pub fn check_project(
+    project: &XcodeProject,
+    root: &Path,
+    exclude_patterns: &[String],
+    issues: &mut Vec<PreflightIssue>,
+) {
+    let root = normalize(root);
+    let source_root = normalize(&project.source_root);
+    let project_name = project
+        .path
+        .file_name()
+        .map(|n| n.to_string_lossy().into_owned())
+        .unwrap_or_default();
+
+    let targets: Vec<_> = project
+        .target_ids()
+        .into_iter()
+        .filter_map(|id| project.target(id))
+        .collect();
+    let built: HashSet<&str> = targets
+        .iter()
+        .flat_map(|t| t.files.iter().map(|f| f.path.as_str()))
+        .collect();
+
+    for (_, relative) in project.file_references() {
+        let relative_str = relative.to_string_lossy();
+        let severity = if built.contains(relative_str.as_ref()) {
+            Severity::Error
+        } else {
+            Severity::Warning
+        };
+        check_path(&source_root.join(relative), severity, "File", &root, exclude_patterns, issues);
+    }
+
+    for target in &targets {
+        for folder in &target.synchronized_folders {
+            let label = format!("Folder of target '{}'", target.name);
+            check_path(&source_root.join(folder), Severity::Error, &label, &root, exclude_patterns, issues);
+        }
+
+        // Targets often share an Info.plist across configurations
+        let mut plists: Vec<&str> = target
+            .configurations
+            .iter()
+            .filter_map(|c| c.info_plist.as_deref())
+            .collect();
+        plists.dedup();
+        for plist in plists {
+            let label = format!("Info.plist of target '{}'", target.name);
+            check_path(&source_root.join(plist), Severity::Error, &label, &root, exclude_patterns, issues);
+        }
+    }
+
+    for package in project.package_references() {
+        if let PackageReference::Local { path } = package {
+            let label = format!("Local package of {}", project_name);
+            check_path(&source_root.join(path), Severity::Error, &label, &root, exclude_patterns, issues);
+        }
+    }
+}
+
+fn check_path(
+    path: &Path,
+    severity: Severity,
+    label: &str,
+    root: &Path,
+    exclude_patterns: &[String],
+    issues: &mut Vec<PreflightIssue>,
+) {
+    // Paths built from build settings can't be resolved here
+    if path.to_string_lossy().contains("$(") {
+        return;
+    }
+    let path = normalize(path);
+
+    if !path.exists() {
+        issues.push(PreflightIssue::new(severity, format!("{} does not exist", label), &path, root));
+        return;
+    }
+
+    match path.strip_prefix(root) {
+        Ok(relative) => {
+            if should_exclude(&relative.to_string_lossy(), exclude_patterns) {
+                issues.push(PreflightIssue::new(
+                    severity,
+                    format!("{} is excluded from the upload by project.exclude_patterns", label),
+                    &path,
+                    root,
+                ));
+            }
+        }
+        Err(_) => issues.push(PreflightIssue::new(
+            severity,
+            format!("{} is outside the project directory and won't be uploaded", label),
+            &path,
+            root,
+        )),
+    }
+}
+
+fn display_path(path: &Path, root: &Path) -> String {
+    path.strip_prefix(root)
+        .unwrap_or(path)
+        .to_string_lossy()
+        .into_owned()
+}
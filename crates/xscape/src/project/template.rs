@@ -0,0 +1,250 @@
+use anyhow::{Context, Result};
+use std::path::{Path, PathBuf};
+
+use super::pbxproj::quote;
+
+/// A project template embedded in the binary
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Template {
+    /// iOS app with the SwiftUI app lifecycle
+    #[default]
+    SwiftUi,
+    /// iOS app with a UIKit scene delegate and view controller
+    UiKit,
+    /// Swift package with a library and its tests
+    Package,
+}
+
+impl Template {
+    /// Files of the template. Paths and contents use `{{NAME}}`-style
+    /// placeholders.
+    fn files(&self) -> Vec<(&'static str, &'static str)> {
+        let app_files = [
+            (
+                "{{NAME}}.xcodeproj/project.xcworkspace/contents.xcworkspacedata",
+                include_str!("../../templates/common/contents.xcworkspacedata"),
+            ),
+            (
+                "{{NAME}}.xcodeproj/xcshareddata/xcschemes/{{NAME}}.xcscheme",
+                include_str!("../../templates/common/scheme.xcscheme"),
+            ),
+            (
+                "{{NAME}}/Assets.xcassets/Contents.json",
+                include_str!("../../templates/common/Assets.xcassets/Contents.json"),
+            ),
+            (
+                "{{NAME}}/Assets.xcassets/AccentColor.colorset/Contents.json",
+                include_str!("../../templates/common/Assets.xcassets/AccentColor.colorset/Contents.json"),
+            ),
+            (
+                "{{NAME}}/Assets.xcassets/AppIcon.appiconset/Contents.json",
+                include_str!("../../templates/common/Assets.xcassets/AppIcon.appiconset/Contents.json"),
+            ),
+            (
+                "{{NAME}}Tests/{{NAME}}Tests.swift",
+                include_str!("../../templates/common/Tests.swift"),
+            ),
+        ];
+        let gitignore = (".gitignore", include_str!("../../templates/common/gitignore"));
+
+        let mut files = match self {
+            Self::SwiftUi => vec![
+                (
+                    "{{NAME}}.xcodeproj/project.pbxproj",
+                    include_str!("../../templates/swiftui/project.pbxproj"),
+                ),
+                ("{{NAME}}/{{NAME}}App.swift", include_str!("../../templates/swiftui/App.swift")),
+                (
+                    "{{NAME}}/ContentView.swift",
+                    include_str!("../../templates/swiftui/ContentView.swift"),
+                ),
+                ("{{NAME}}/Info.plist", include_str!("../../templates/swiftui/Info.plist")),
+            ],
+            Self::UiKit => vec![
+                (
+                    "{{NAME}}.xcodeproj/project.pbxproj",
+                    include_str!("../../templates/uikit/project.pbxproj"),
+                ),
+                (
+                    "{{NAME}}/AppDelegate.swift",
+                    include_str!("../../templates/uikit/AppDelegate.swift"),
+                ),
+                (
+                    "{{NAME}}/SceneDelegate.swift",
+                    include_str!("../../templates/uikit/SceneDelegate.swift"),
+                ),
+                (
+                    "{{NAME}}/ViewController.swift",
+                    include_str!("../../templates/uikit/ViewController.swift"),
+                ),
+                ("{{NAME}}/Info.plist", include_str!("../../templates/uikit/Info.plist")),
+            ],
+            Self::Package => {
+                return vec![
+                    ("Package.swift", include_str!("../../templates/package/Package.swift")),
+                    (
+                        "Sources/{{NAME}}/{{NAME}}.swift",
+                        include_str!("../../templates/package/Library.swift"),
+                    ),
+                    (
+                        "Tests/{{NAME}}Tests/{{NAME}}Tests.swift",
+                        include_str!("../../templates/package/Tests.swift"),
+                    ),
+                    gitignore,
+                ];
+            }
+        };
+        files.extend(app_files);
+        files.push(gitignore);
+        files
+    }
+
+    /// Oldest iOS release the template's code runs on
+    fn minimum_ios(&self) -> u32 {
+        match self {
+            // The SwiftUI App protocol
+            Self::SwiftUi => 14,
+            // Scene delegates
+            Self::UiKit => 13,
+            Self::Package => 12,
+        }
+    }
+
+    pub fn is_app(&self) -> bool {
+        !matches!(self, Self::Package)
+    }
+}
+
+impl std::fmt::Display for Template {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Self::SwiftUi => "swiftui",
+            Self::UiKit => "uikit",
+            Self::Package => "package",
+        })
+    }
+}
+
+impl std::str::FromStr for Template {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "swiftui" => Ok(Self::SwiftUi),
+            "uikit" => Ok(Self::UiKit),
+            "package" | "spm" => Ok(Self::Package),
+            _ => Err(format!(
+                "unknown template '{}' (expected swiftui, uikit or package)",
+                s
+            )),
+        }
+    }
+}
+
+/// What to fill a template's placeholders with
+pub struct TemplateOptions {
+    /// Project, target and module name
+    pub name: String,
+    /// Reverse-DNS prefix of the bundle ID (e.g., com.example)
+    pub bundle_id_prefix: String,
+    /// Minimum iOS version (e.g., 17.0)
+    pub deployment_target: String,
+}
+
+impl TemplateOptions {
+    /// Check the options can produce a project that builds
+    pub fn validate(&self, template: Template) -> Result<(), String> {
+        let mut chars = self.name.chars();
+        let valid_name = chars.next().is_some_and(|c| c.is_ascii_alphabetic())
+            && chars.all(|c| c.is_ascii_alphanumeric() || c == '_');
+        if !valid_name {
+            return Err(format!(
+                "invalid name '{}': use letters, digits and underscores, starting with a letter",
+                self.name
+            ));
+        }
+
+        let valid_prefix = !self.bundle_id_prefix.is_empty()
+            && self
+                .bundle_id_prefix
+                .split('.')
+                .all(|part| !part.is_empty() && part.chars().all(|c| c.is_ascii_alphanumeric() || c == '-'));
+        if !valid_prefix {
+            return Err(format!(
+                "invalid bundle ID prefix '{}' (e.g., com.example)",
+                self.bundle_id_prefix
+            ));
+        }
+
+        let parts: Vec<&str> = self.deployment_target.split('.').collect();
+        let valid_target = parts.len() <= 3
+            && parts
+                .iter()
+                .all(|p| !p.is_empty() && p.chars().all(|c| c.is_ascii_digit()));
+        if !valid_target {
+            return Err(format!(
+                "invalid deployment target '{}' (e.g., 17.0)",
+                self.deployment_target
+            ));
+        }
+        let major: u32 = parts[0].parse().unwrap_or(0);
+        if major < template.minimum_ios() {
+            return Err(format!(
+                "the {} template needs iOS {} or later",
+                template,
+                template.minimum_ios()
+            ));
+        }
+        Ok(())
+    }
+
+    /// Bundle ID of the app. Underscores aren't allowed in bundle IDs.
+    pub fn bundle_id(&self) -> String {
+        format!("{}.{}", self.bundle_id_prefix, self.name.replace('_', "-"))
+    }
+
+    fn variables(&self) -> [(&'static str, String); 4] {
+        let bundle_id = self.bundle_id();
+        [
+            ("NAME", self.name.clone()),
+            ("TESTS_BUNDLE_ID", format!("{}Tests", bundle_id)),
+            ("BUNDLE_ID", bundle_id),
+            ("DEPLOYMENT_TARGET", self.deployment_target.clone()),
+        ]
+    }
+}
+
+/// Write a new project from a template into `dir`, which must be empty or
+/// not exist yet. Returns the written files, relative to `dir`.
+pub fn generate(template: Template, options: &TemplateOptions, dir: &Path) -> Result<Vec<PathBuf>> {
+    if dir.read_dir().is_ok_and(|mut entries| entries.next().is_some()) {
+        anyhow::bail!("{} already exists and is not empty", dir.display());
+    }
+
+    let variables = options.variables();
+    let mut written = Vec::new();
+    for (path, contents) in template.files() {
+        let relative = PathBuf::from(render(path, &variables, false));
+        // Values in project.pbxproj must be quoted like the rest of the file
+        let pbxproj = relative.extension().is_some_and(|e| e == "pbxproj");
+        let contents = render(contents, &variables, pbxproj);
+
+        let dest = dir.join(&relative);
+        if let Some(parent) = dest.parent() {
+            std::fs::create_dir_all(parent)
+                .with_context(|| format!("Failed to create {}", parent.display()))?;
+        }
+        std::fs::write(&dest, contents).with_context(|| format!("Failed to write {}", dest.display()))?;
+        written.push(relative);
+    }
+    Ok(written)
+}
+
+fn render(text: &str, variables: &[(&str, String)], pbxproj: bool) -> String {
+    let mut rendered = text.to_string();
+    for (key, value) in variables {
+        let value = if pbxproj { quote(value) } else { value.clone() };
+        rendered = rendered.replace(&format!("{{{{{}}}}}", key), &value);
+    }
+    rendered
+}
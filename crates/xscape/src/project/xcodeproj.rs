@@ -0,0 +1,436 @@
+use anyhow::{anyhow, Context, Result};
+use serde::Serialize;
+use std::collections::{BTreeMap, HashMap};
+use std::path::{Path, PathBuf};
+
+use super::pbxproj::{self, Dictionary};
+
+/// An Xcode project read from its project.pbxproj
+pub struct XcodeProject {
+    /// The .xcodeproj bundle
+    pub path: PathBuf,
+    /// Directory that `SOURCE_ROOT` paths are relative to
+    pub source_root: PathBuf,
+    pub object_version: Option<String>,
+    objects: HashMap<String, Dictionary>,
+    root: String,
+    /// Where each file reference and group lives, relative to the source root
+    /// (absolute for `<absolute>` references). Missing for SDK and build
+    /// product references.
+    paths: HashMap<String, PathBuf>,
+}
+
+/// Summary of a project, as printed by `xscape project inspect`
+#[derive(Debug, Serialize)]
+pub struct ProjectSummary {
+    pub path: String,
+    pub object_version: Option<String>,
+    pub configurations: Vec<String>,
+    pub targets: Vec<TargetSummary>,
+    pub packages: Vec<PackageReference>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct TargetSummary {
+    pub name: String,
+    /// e.g., PBXNativeTarget, PBXAggregateTarget
+    pub kind: String,
+    /// e.g., com.apple.product-type.application
+    pub product_type: Option<String>,
+    pub product_name: Option<String>,
+    pub configurations: Vec<TargetConfiguration>,
+    pub dependencies: Vec<String>,
+    pub files: Vec<TargetFile>,
+    /// Folders whose contents all belong to the target (Xcode 16 synchronized groups)
+    pub synchronized_folders: Vec<String>,
+    /// Swift package products the target links
+    pub package_products: Vec<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct TargetConfiguration {
+    pub name: String,
+    pub bundle_id: Option<String>,
+    pub info_plist: Option<String>,
+}
+
+/// A file in one of a target's build phases
+#[derive(Debug, Clone, Serialize)]
+pub struct TargetFile {
+    pub path: String,
+    /// sources, resources, frameworks, headers or copy
+    pub phase: &'static str,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "kind", rename_all = "lowercase")]
+pub enum PackageReference {
+    Remote {
+        url: String,
+        requirement: BTreeMap<String, String>,
+    },
+    Local {
+        path: String,
+    },
+}
+
+impl XcodeProject {
+    /// Open a .xcodeproj bundle (or its project.pbxproj directly)
+    pub fn open(path: &Path) -> Result<Self> {
+        let (bundle, pbxproj) = if path.extension().is_some_and(|e| e == "pbxproj") {
+            (path.parent().unwrap_or(path).to_path_buf(), path.to_path_buf())
+        } else {
+            (path.to_path_buf(), path.join("project.pbxproj"))
+        };
+        let text = std::fs::read_to_string(&pbxproj)
+            .with_context(|| format!("Failed to read {}", pbxproj.display()))?;
+        Self::parse(&text, &bundle).with_context(|| format!("Failed to parse {}", pbxproj.display()))
+    }
+
+    /// Parse the contents of a project.pbxproj belonging to `bundle`
+    pub fn parse(text: &str, bundle: &Path) -> Result<Self> {
+        let root_value = pbxproj::parse(text)?;
+        let root_dict = root_value
+            .as_dict()
+            .ok_or_else(|| anyhow!("project.pbxproj is not a dictionary"))?;
+
+        let objects: HashMap<String, Dictionary> = root_dict
+            .get_dict("objects")
+            .ok_or_else(|| anyhow!("project.pbxproj has no objects"))?
+            .iter()
+            .filter_map(|(id, value)| value.as_dict().map(|d| (id.to_string(), d.clone())))
+            .collect();
+        let root = root_dict
+            .get_str("rootObject")
+            .ok_or_else(|| anyhow!("project.pbxproj has no rootObject"))?
+            .to_string();
+        if !objects.contains_key(&root) {
+            return Err(anyhow!("rootObject {} is not defined", root));
+        }
+
+        let bundle_dir = bundle.parent().unwrap_or(Path::new("")).to_path_buf();
+        let mut project = Self {
+            path: bundle.to_path_buf(),
+            source_root: bundle_dir,
+            object_version: root_dict.get_str("objectVersion").map(String::from),
+            objects,
+            root,
+            paths: HashMap::new(),
+        };
+
+        // projectDirPath moves the source root (rarely set)
+        if let Some(dir) = project.root_object().get_str("projectDirPath").filter(|d| !d.is_empty()) {
+            project.source_root = project.source_root.join(dir);
+        }
+        if let Some(main_group) = project.root_object().get_str("mainGroup").map(String::from) {
+            project.resolve_paths(&main_group, Path::new(""), 0);
+        }
+        Ok(project)
+    }
+
+    fn root_object(&self) -> &Dictionary {
+        &self.objects[&self.root]
+    }
+
+    pub fn object(&self, id: &str) -> Option<&Dictionary> {
+        self.objects.get(id)
+    }
+
+    /// Every object with its ID, in no particular order
+    pub fn objects(&self) -> impl Iterator<Item = (&str, &Dictionary)> {
+        self.objects.iter().map(|(id, object)| (id.as_str(), object))
+    }
+
+    /// ID of the group at the top of the project navigator
+    pub fn main_group(&self) -> Option<&str> {
+        self.root_object().get_str("mainGroup")
+    }
+
+    pub fn isa(&self, id: &str) -> Option<&str> {
+        self.object(id).and_then(|o| o.get_str("isa"))
+    }
+
+    /// Record the path of `id` and everything below it
+    fn resolve_paths(&mut self, id: &str, parent: &Path, depth: usize) {
+        // Guards against reference cycles in malformed projects
+        if depth > 64 || self.paths.contains_key(id) {
+            return;
+        }
+        let Some(object) = self.objects.get(id) else {
+            return;
+        };
+
+        let path = object.get_str("path");
+        let resolved = match object.get_str("sourceTree").unwrap_or("<group>") {
+            "<group>" => Some(match path {
+                Some(path) => parent.join(path),
+                None => parent.to_path_buf(),
+            }),
+            "SOURCE_ROOT" => Some(PathBuf::from(path.unwrap_or(""))),
+            "<absolute>" => path.map(PathBuf::from),
+            // SDKROOT, BUILT_PRODUCTS_DIR, DEVELOPER_DIR, ...
+            _ => None,
+        };
+        let Some(resolved) = resolved else {
+            return;
+        };
+
+        let children: Vec<String> = object.get_strings("children").into_iter().map(String::from).collect();
+        self.paths.insert(id.to_string(), resolved.clone());
+        for child in children {
+            self.resolve_paths(&child, &resolved, depth + 1);
+        }
+    }
+
+    /// Path of a file reference or group, relative to the source root
+    pub fn file_path(&self, id: &str) -> Option<&Path> {
+        self.paths.get(id).map(PathBuf::as_path)
+    }
+
+    /// Every file reference with a resolvable path, sorted by path
+    pub fn file_references(&self) -> Vec<(&str, &Path)> {
+        let mut files: Vec<(&str, &Path)> = self
+            .paths
+            .iter()
+            .filter(|(id, _)| self.isa(id) == Some("PBXFileReference"))
+            .map(|(id, path)| (id.as_str(), path.as_path()))
+            .collect();
+        files.sort_by(|a, b| a.1.cmp(b.1).then(a.0.cmp(b.0)));
+        files
+    }
+
+    /// Names of the project-level build configurations
+    pub fn configurations(&self) -> Vec<String> {
+        self.configuration_list(self.root_object())
+            .into_iter()
+            .map(|(name, _)| name)
+            .collect()
+    }
+
+    /// Configurations of an object's buildConfigurationList, with their settings
+    fn configuration_list<'a>(&'a self, owner: &Dictionary) -> Vec<(String, Option<&'a Dictionary>)> {
+        let Some(list) = owner
+            .get_str("buildConfigurationList")
+            .and_then(|id| self.object(id))
+        else {
+            return Vec::new();
+        };
+        list.get_strings("buildConfigurations")
+            .into_iter()
+            .filter_map(|id| self.object(id))
+            .filter_map(|config| {
+                let name = config.get_str("name")?.to_string();
+                Some((name, config.get_dict("buildSettings")))
+            })
+            .collect()
+    }
+
+    /// IDs of the project's targets, in project order
+    pub fn target_ids(&self) -> Vec<&str> {
+        self.root_object().get_strings("targets")
+    }
+
+    /// Names of the project's targets
+    pub fn target_names(&self) -> Vec<String> {
+        self.target_ids()
+            .into_iter()
+            .filter_map(|id| self.object(id)?.get_str("name").map(String::from))
+            .collect()
+    }
+
+    /// Describe one target
+    pub fn target(&self, id: &str) -> Option<TargetSummary> {
+        let target = self.object(id)?;
+
+        // Target settings win over the project's for the same configuration
+        let project_settings: HashMap<String, Option<&Dictionary>> =
+            self.configuration_list(self.root_object()).into_iter().collect();
+        let setting = |settings: Option<&Dictionary>, config: &str, key: &str| {
+            settings
+                .and_then(|s| s.get_str(key))
+                .or_else(|| project_settings.get(config).copied().flatten().and_then(|s| s.get_str(key)))
+                .map(String::from)
+        };
+        let configurations = self
+            .configuration_list(target)
+            .into_iter()
+            .map(|(name, settings)| TargetConfiguration {
+                bundle_id: setting(settings, &name, "PRODUCT_BUNDLE_IDENTIFIER"),
+                info_plist: setting(settings, &name, "INFOPLIST_FILE"),
+                name,
+            })
+            .collect();
+
+        let dependencies = target
+            .get_strings("dependencies")
+            .into_iter()
+            .filter_map(|dep| self.object(dep))
+            .filter_map(|dep| {
+                dep.get_str("target")
+                    .and_then(|t| self.object(t))
+                    .and_then(|t| t.get_str("name"))
+                    .or_else(|| dep.get_str("name"))
+                    .map(String::from)
+            })
+            .collect();
+
+        let synchronized_folders = target
+            .get_strings("fileSystemSynchronizedGroups")
+            .into_iter()
+            .filter_map(|group| self.file_path(group))
+            .map(|p| p.to_string_lossy().into_owned())
+            .collect();
+
+        let package_products = target
+            .get_strings("packageProductDependencies")
+            .into_iter()
+            .filter_map(|dep| self.object(dep)?.get_str("productName").map(String::from))
+            .collect();
+
+        Some(TargetSummary {
+            name: target.get_str("name").unwrap_or_default().to_string(),
+            kind: target.get_str("isa").unwrap_or_default().to_string(),
+            product_type: target.get_str("productType").map(String::from),
+            product_name: target.get_str("productName").map(String::from),
+            configurations,
+            dependencies,
+            files: self.target_files(id),
+            synchronized_folders,
+            package_products,
+        })
+    }
+
+    /// Files in a target's build phases. Localized files (variant groups)
+    /// expand to each localization.
+    pub fn target_files(&self, target_id: &str) -> Vec<TargetFile> {
+        let Some(target) = self.object(target_id) else {
+            return Vec::new();
+        };
+
+        let mut files = Vec::new();
+        for phase in target.get_strings("buildPhases").into_iter().filter_map(|id| self.object(id)) {
+            let phase_name = match phase.get_str("isa") {
+                Some("PBXSourcesBuildPhase") => "sources",
+                Some("PBXResourcesBuildPhase") => "resources",
+                Some("PBXFrameworksBuildPhase") => "frameworks",
+                Some("PBXHeadersBuildPhase") => "headers",
+                Some("PBXCopyFilesBuildPhase") => "copy",
+                _ => continue,
+            };
+            for build_file in phase.get_strings("files").into_iter().filter_map(|id| self.object(id)) {
+                let Some(file_ref) = build_file.get_str("fileRef") else {
+                    continue;
+                };
+                let refs = match self.isa(file_ref) {
+                    Some("PBXVariantGroup") | Some("XCVersionGroup") => self
+                        .object(file_ref)
+                        .map(|g| g.get_strings("children"))
+                        .unwrap_or_default(),
+                    _ => vec![file_ref],
+                };
+                files.extend(refs.into_iter().filter_map(|id| self.file_path(id)).map(|path| {
+                    TargetFile {
+                        path: path.to_string_lossy().into_owned(),
+                        phase: phase_name,
+                    }
+                }));
+            }
+        }
+        files
+    }
+
+    /// Swift packages the project depends on
+    pub fn package_references(&self) -> Vec<PackageReference> {
+        self.root_object()
+            .get_strings("packageReferences")
+            .into_iter()
+            .filter_map(|id| {
+                let package = self.object(id)?;
+                match package.get_str("isa")? {
+                    "XCRemoteSwiftPackageReference" => Some(PackageReference::Remote {
+                        url: package.get_str("repositoryURL")?.to_string(),
+                        requirement: package
+                            .get_dict("requirement")
+                            .map(|r| {
+                                r.iter()
+                                    .filter_map(|(k, v)| Some((k.to_string(), v.as_str()?.to_string())))
+                                    .collect()
+                            })
+                            .unwrap_or_default(),
+                    }),
+                    "XCLocalSwiftPackageReference" => Some(PackageReference::Local {
+                        path: package.get_str("relativePath")?.to_string(),
+                    }),
+                    _ => None,
+                }
+            })
+            .collect()
+    }
+
+    /// Everything `xscape project inspect` reports
+    pub fn summary(&self) -> ProjectSummary {
+        ProjectSummary {
+            path: self.path.to_string_lossy().into_owned(),
+            object_version: self.object_version.clone(),
+            configurations: self.configurations(),
+            targets: self.target_ids().into_iter().filter_map(|id| self.target(id)).collect(),
+            packages: self.package_references(),
+        }
+    }
+}
+
+/// The .xcodeproj bundles directly inside a directory, sorted
+pub fn find_projects(dir: &Path) -> Vec<PathBuf> {
+    let mut projects: Vec<PathBuf> = std::fs::read_dir(dir)
+        .map(|entries| {
+            entries
+                .filter_map(|e| e.ok())
+                .map(|e| e.path())
+                .filter(|p| p.extension().is_some_and(|e| e == "xcodeproj"))
+                .collect()
+        })
+        .unwrap_or_default();
+    projects.sort();
+    projects
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn describes_an_xcode_project() {
+        let text = include_str!("../../tests/fixtures/Demo.pbxproj");
+        let project = XcodeProject::parse(text, Path::new("/work/Demo.xcodeproj")).unwrap();
+        assert_eq!(project.source_root, Path::new("/work"));
+        assert_eq!(project.object_version.as_deref(), Some("60"));
+        assert_eq!(project.configurations(), ["Debug", "Release"]);
+        assert_eq!(project.target_names(), ["Demo"]);
+
+        let paths: Vec<&Path> = project.file_references().into_iter().map(|(_, path)| path).collect();
+        assert_eq!(
+            paths,
+            [
+                Path::new("Demo/Assets.xcassets"),
+                Path::new("Demo/ContentView.swift"),
+                Path::new("Demo/DemoApp.swift"),
+                Path::new("Demo/Info.plist"),
+            ]
+        );
+
+        let target = project.target("5A1C2E432B8D4A0200F1A2B3").unwrap();
+        assert_eq!(target.product_type.as_deref(), Some("com.apple.product-type.application"));
+        let files: Vec<(&str, &str)> = target.files.iter().map(|f| (f.path.as_str(), f.phase)).collect();
+        assert_eq!(
+            files,
+            [
+                ("Demo/ContentView.swift", "sources"),
+                ("Demo/DemoApp.swift", "sources"),
+                ("Demo/Assets.xcassets", "resources"),
+            ]
+        );
+        let debug = &target.configurations[0];
+        assert_eq!(debug.bundle_id.as_deref(), Some("com.example.Demo"));
+        assert_eq!(debug.info_plist.as_deref(), Some("Demo/Info.plist"));
+    }
+}
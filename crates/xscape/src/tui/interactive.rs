@@ -32,7 +32,7 @@ impl ProjectSelector {
             .context("Failed to resolve project path")?;
 
         // Find schemes
-        let schemes = find_schemes(&project_path)?;
+        let schemes = crate::project::find_schemes(&project_path)?;
 
         if schemes.is_empty() {
             anyhow::bail!("No schemes found in project. Make sure it's a valid Xcode project.");
@@ -333,47 +333,6 @@ pub enum VmAction {
     Back,
 }
 
-/// Find Xcode schemes in a project
-fn find_schemes(project_path: &PathBuf) -> Result<Vec<String>> {
-    let mut schemes = Vec::new();
-
-    // Look for shared schemes
-    for entry in std::fs::read_dir(project_path)? {
-        let entry = entry?;
-        let path = entry.path();
-
-        if path.extension().map_or(false, |e| e == "xcodeproj" || e == "xcworkspace") {
-            let schemes_dir = path.join("xcshareddata/xcschemes");
-            if schemes_dir.exists() {
-                for scheme_entry in std::fs::read_dir(schemes_dir)? {
-                    let scheme_entry = scheme_entry?;
-                    let scheme_path = scheme_entry.path();
-                    if scheme_path.extension().map_or(false, |e| e == "xcscheme") {
-                        if let Some(name) = scheme_path.file_stem() {
-                            schemes.push(name.to_string_lossy().to_string());
-                        }
-                    }
-                }
-            }
-        }
-    }
-
-    // If no shared schemes, try to guess from project name
-    if schemes.is_empty() {
-        for entry in std::fs::read_dir(project_path)? {
-            let entry = entry?;
-            let path = entry.path();
-            if path.extension().map_or(false, |e| e == "xcodeproj") {
-                if let Some(name) = path.file_stem() {
-                    schemes.push(name.to_string_lossy().to_string());
-                }
-            }
-        }
-    }
-
-    Ok(schemes)
-}
-
 // Shell expansion helper
 mod shellexpand {
     pub fn tilde(path: &str) -> std::borrow::Cow<str> {
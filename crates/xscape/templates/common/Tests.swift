@@ -0,0 +1,8 @@
+import XCTest
+@testable import {{NAME}}
+
+final class {{NAME}}Tests: XCTestCase {
+    func testExample() throws {
+        XCTAssertEqual(1 + 1, 2)
+    }
+}
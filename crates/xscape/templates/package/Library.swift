@@ -0,0 +1,4 @@
+/// A greeting from {{NAME}}
+public func greeting() -> String {
+    "Hello, world!"
+}
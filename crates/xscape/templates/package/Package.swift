@@ -0,0 +1,17 @@
+// swift-tools-version:5.9
+import PackageDescription
+
+let package = Package(
+    name: "{{NAME}}",
+    platforms: [
+        .iOS("{{DEPLOYMENT_TARGET}}"),
+        .macOS(.v13),
+    ],
+    products: [
+        .library(name: "{{NAME}}", targets: ["{{NAME}}"]),
+    ],
+    targets: [
+        .target(name: "{{NAME}}"),
+        .testTarget(name: "{{NAME}}Tests", dependencies: ["{{NAME}}"]),
+    ]
+)
@@ -0,0 +1,8 @@
+import XCTest
+@testable import {{NAME}}
+
+final class {{NAME}}Tests: XCTestCase {
+    func testGreeting() throws {
+        XCTAssertEqual(greeting(), "Hello, world!")
+    }
+}
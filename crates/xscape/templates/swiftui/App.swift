@@ -0,0 +1,10 @@
+import SwiftUI
+
+@main
+struct {{NAME}}App: App {
+    var body: some Scene {
+        WindowGroup {
+            ContentView()
+        }
+    }
+}
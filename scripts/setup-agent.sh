@@ -58,8 +58,11 @@ port = $AGENT_PORT
 [storage]
 projects_dir = "$AGENT_DIR/projects"
 logs_dir = "$AGENT_DIR/logs"
+dependencies_dir = "$AGENT_DIR/dependencies"
 max_projects = 10
 cleanup_after_hours = 24
+max_builds = 20
+dependencies_max_age_days = 30
 
 [xcode]
 # Path is auto-detected
@@ -67,6 +70,8 @@ cleanup_after_hours = 24
 [simulator]
 auto_boot = true
 shutdown_idle_after_minutes = 30
+# Shut down the least recently used simulator past this many booted (0 for no limit)
+max_booted_devices = 0
 EOF
 
 echo "Created: $AGENT_DIR/config.toml"